@@ -12,16 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use proc_macro::TokenStream;
+use proc_macro::{
+    TokenStream,
+    TokenTree,
+};
 use quote::quote;
 
+/// The UIs which a test can `skip`, as named in `WATERFALL_UI`.
+const UIS: [&str; 3] = ["canvas-ui", "polkadot-js", "rpc"];
+
 /// The macro is used to do some initial set-up for a waterfall test and handle
 /// the shutdown at the end of a test.
 ///
+/// The artifacts for debugging a failure, e.g. a screenshot of the browser, are
+/// saved when a step of the test fails. If the test panics, or returns an error
+/// without a failed step, they are saved at the end of the test.
+///
+/// A test which the UI chosen via `WATERFALL_UI` does not support can name it
+/// with `skip`, the test then returns right away when run against that UI.
+///
 /// # Usage
 ///
 /// ```no_compile
-/// #[waterfall_test]
+/// #[waterfall_test(example = "flipper", skip = "polkadot-js")]
 /// async fn works(mut ui: Ui) -> Result<()> {
 ///     let _contract_addr = ui.upload(contract_file).await?;
 ///     Ok(())
@@ -29,15 +42,25 @@ use quote::quote;
 /// ```
 #[proc_macro_attribute]
 pub fn waterfall_test(waterfall_attrs: TokenStream, item: TokenStream) -> TokenStream {
-    let example = waterfall_attrs
-        .into_iter()
-        .find_map(|item| {
-            if let proc_macro::TokenTree::Literal(lit) = item {
-                return Some(format!("{}", lit.to_string().replace("\"", "")))
-            }
-            None
-        })
+    let params = params(waterfall_attrs);
+    let example = params
+        .iter()
+        .find(|(name, _)| name == "example")
+        .map(|(_, value)| value.clone())
         .expect("example param must exist");
+    let skip: Vec<_> = params
+        .into_iter()
+        .filter(|(name, _)| name == "skip")
+        .map(|(_, ui)| ui)
+        .collect();
+    for ui in &skip {
+        assert!(
+            UIS.contains(&ui.as_str()),
+            "unknown UI {:?} to skip, expected one of {:?}",
+            ui,
+            UIS
+        );
+    }
 
     let item_fn =
         syn::parse2::<syn::ItemFn>(item.into()).expect("no item_fn can be parsed");
@@ -50,6 +73,10 @@ pub fn waterfall_test(waterfall_attrs: TokenStream, item: TokenStream) -> TokenS
         syn::ReturnType::Default => quote! {},
         syn::ReturnType::Type(rarrow, ret_type) => quote! { #rarrow #ret_type },
     };
+    let ret_annotation = match fn_return_type {
+        syn::ReturnType::Default => quote! {},
+        syn::ReturnType::Type(_, ret_type) => quote! { : #ret_type },
+    };
     let res = quote! {
         #( #attrs )*
         #[tokio::test]
@@ -62,14 +89,40 @@ pub fn waterfall_test(waterfall_attrs: TokenStream, item: TokenStream) -> TokenS
             crate::INIT.call_once(|| {
                 env_logger::init();
             });
+            let backend = crate::uis::UiBackend::from_env();
+            if [#( #skip ),*].contains(&backend.to_string().as_str()) {
+                log::info!(
+                    "skipping {}, the test is not supported by the `{}` UI",
+                    stringify!(#fn_name),
+                    backend
+                );
+                return Ok(())
+            }
+            crate::uis::trace::reset();
 
             use crate::uis::ContractsUi;
             log::debug!("creating new ui for {}", stringify!(#fn_name));
             let mut ui = Ui::new().await?;
             log::debug!("invoking block for {}", stringify!(#fn_name));
-            let __ret = {
-                #block
+            // The panic is caught to save the failure artifacts while the browser
+            // is still open, and then resumed.
+            let __ret #ret_annotation = {
+                let __block = std::panic::AssertUnwindSafe(async { #block });
+                match futures_util::FutureExt::catch_unwind(__block).await {
+                    Ok(ret) => ret,
+                    Err(panic) => {
+                        ui.save_failure_artifacts().await;
+                        crate::uis::trace::save();
+                        crate::uis::gas::save_summary();
+                        std::panic::resume_unwind(panic)
+                    }
+                }
             };
+            if __ret.is_err() && !ui.saved_failure_artifacts() {
+                ui.save_failure_artifacts().await;
+            }
+            crate::uis::trace::save();
+            crate::uis::gas::save_summary();
             ui.shutdown().await?;
             log::debug!("shutdown for {} complete", stringify!(#fn_name));
             __ret
@@ -77,3 +130,22 @@ pub fn waterfall_test(waterfall_attrs: TokenStream, item: TokenStream) -> TokenS
     };
     res.into()
 }
+
+/// Returns the `name = "value"` params of the attribute, in their order.
+fn params(attrs: TokenStream) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut name = None;
+    for token in attrs {
+        match token {
+            TokenTree::Ident(ident) => name = Some(ident.to_string()),
+            TokenTree::Literal(lit) => {
+                let name = name
+                    .take()
+                    .expect("param must be named, e.g. `example = ..`");
+                params.push((name, lit.to_string().replace('"', "")));
+            }
+            _ => (),
+        }
+    }
+    params
+}
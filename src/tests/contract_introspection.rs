@@ -16,6 +16,7 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
         Result,
         Ui,
@@ -44,25 +45,25 @@ async fn contract_instrospection_works(mut ui: Ui) -> Result<()> {
     assert_eq!(
         ui.execute_rpc(Call::new(&addr, "is_caller_contract"))
             .await?,
-        "false"
+        ArgValue::Bool(false)
     );
     // the `is_caller_contract` method is called indirectly from this contract method.
     assert_eq!(
         ui.execute_rpc(Call::new(&addr, "calls_is_caller_contract"))
             .await?,
-        "true"
+        ArgValue::Bool(true)
     );
 
     // the method is called directly via the ui.
     assert_eq!(
         ui.execute_rpc(Call::new(&addr, "is_caller_origin")).await?,
-        "true"
+        ArgValue::Bool(true)
     );
     // the `is_caller_origin` method is called indirectly from this contract method.
     assert_eq!(
         ui.execute_rpc(Call::new(&addr, "calls_is_caller_origin"))
             .await?,
-        "false"
+        ArgValue::Bool(false)
     );
 
     Ok(())
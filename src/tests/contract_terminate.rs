@@ -43,8 +43,8 @@ async fn contract_terminate_works(mut ui: Ui) -> Result<()> {
         .await
         .expect("failed to execute transaction");
     assert!(events.contains("system.KilledAccount"));
-    assert!(events.contains("balances.Withdraw"));
-    assert!(events.contains("contracts.Terminated"));
+    assert_eq!(events.count("contracts.Terminated"), 1);
+    events.assert_in_order(&["balances.Withdraw", "contracts.Terminated"]);
 
     // then
     let err = ui
@@ -52,10 +52,15 @@ async fn contract_terminate_works(mut ui: Ui) -> Result<()> {
         .await
         .expect_err("successfully executed transaction, but expected it to fail");
     match err {
-        TransactionError::ExtrinsicFailed(events) => {
-            assert!(events.contains("contracts.ContractNotFound"))
+        TransactionError::Module(error, events) => {
+            assert!(
+                error.is("contracts.ContractNotFound"),
+                "expected `contracts.ContractNotFound`, but found {}",
+                error
+            );
+            assert!(events.contains("system.ExtrinsicFailed"));
         }
-        err => panic!("encountered unexpected {:?}", err),
+        err => return Err(err.into()),
     }
     Ok(())
 }
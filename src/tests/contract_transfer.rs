@@ -16,6 +16,8 @@
 
 use crate::{
     uis::{
+        Balance,
+        BalanceDelta,
         Call,
         Result,
         Ui,
@@ -35,34 +37,38 @@ async fn contract_must_transfer_value_to_sender(mut ui: Ui) -> Result<()> {
     let contract_file =
         cargo_contract::build(&manifest_path).expect("contract build failed");
     let contract_addr = ui
-        .execute_upload(Upload::new(contract_file).push_initial_value("value", "100"))
+        .execute_upload(
+            Upload::new(contract_file).push_initial_value("value", Balance::units(100)?),
+        )
         .await?;
 
-    let balance_before = ui.balance_postfix("EVE".to_string()).await?;
+    let contract_balance = ui.balance(&contract_addr).await?;
+    assert!(contract_balance.free >= Balance::units(100)?);
+    let balance_before = ui.balance_snapshot("EVE").await?;
 
     // when
-    let _events = ui
+    let events = ui
         .execute_transaction(
             Call::new(&contract_addr, "give_me")
-                .push_value("value", "100")
+                .push_value("value", Balance::units(100)?)
                 .caller("EVE"),
         )
         .await
         .expect("failed to execute transaction");
 
     // then
-    let balance_after = ui.balance_postfix("EVE".to_string()).await?;
-    log::info!("balance before: {}", balance_before);
-    log::info!("balance after: {}", balance_after);
-    assert_eq!(balance_after - balance_before, 1);
-    assert!(utils::node_log_contains(
-        "requested value: 100000000000000\n"
-    ));
+    ui.assert_balance_delta(
+        &balance_before,
+        BalanceDelta::Received(Balance::units(100)?),
+    )
+    .await?;
+    assert!(events
+        .debug_message()
+        .contains("requested value: 100000000000000\n"));
     Ok(())
 }
 
-#[waterfall_test(example = "contract-transfer")]
-#[cfg_attr(feature = "polkadot-js-ui", ignore)]
+#[waterfall_test(example = "contract-transfer", skip = "polkadot-js")]
 async fn transfer_exactly_ten_to_contract(mut ui: Ui) -> Result<()> {
     // given
     let manifest_path = utils::example_path("contract-transfer/Cargo.toml");
@@ -71,17 +77,16 @@ async fn transfer_exactly_ten_to_contract(mut ui: Ui) -> Result<()> {
     let contract_addr = ui.execute_upload(Upload::new(contract_file)).await?;
 
     // when
-    let result = ui
+    let events = ui
         .execute_transaction(
             Call::new(&contract_addr, "was_it_ten")
                 .caller("DAVE")
-                //.payment("10", "pico"),
-                .payment("0.000000000010", "Unit"),
+                .payment(Balance::plancks(10)),
         )
-        .await;
+        .await
+        .expect("failed to execute transaction");
 
     // then
-    assert!(result.is_ok());
-    assert!(utils::node_log_contains("received payment: 10\n"));
+    assert!(events.debug_message().contains("received payment: 10\n"));
     Ok(())
 }
@@ -16,6 +16,7 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
         Result,
         Ui,
@@ -43,23 +44,23 @@ async fn data_structures_works(mut ui: Ui) -> Result<()> {
     assert_eq!(
         ui.execute_rpc(
             Call::new(&addr, "overwrite_key")
-                .push_value("key", "1")
-                .push_value("value", "true")
+                .push_value("key", 1u32)
+                .push_value("value", true)
         )
         .await?,
-        "None"
+        ArgValue::none()
     );
     ui.execute_transaction(
         Call::new(&addr, "overwrite_key")
-            .push_value("key", "1")
-            .push_value("value", "true"),
+            .push_value("key", 1u32)
+            .push_value("value", true),
     )
     .await
     .expect("failed to execute transaction");
     assert_eq!(
-        ui.execute_rpc(Call::new(&addr, "remove_key").push_value("key", "1"))
+        ui.execute_rpc(Call::new(&addr, "remove_key").push_value("key", 1u32))
             .await?,
-        "2"
+        ArgValue::some(2u32)
     );
 
     Ok(())
@@ -16,7 +16,9 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
+        ContractBundle,
         Result,
         Ui,
         Upload,
@@ -24,7 +26,6 @@ use crate::{
     utils::{
         self,
         cargo_contract,
-        extract_hash_from_contract_bundle,
     },
 };
 use lang_macro::waterfall_test;
@@ -35,17 +36,17 @@ async fn delegator_works(mut ui: Ui) -> Result<()> {
     let accumulator_path =
         cargo_contract::build(&utils::example_path("delegator/accumulator/Cargo.toml"))
             .expect("accumulator build failed");
-    let accumulator_hash = extract_hash_from_contract_bundle(&accumulator_path);
+    let accumulator_hash = ContractBundle::load(&accumulator_path)?.source.hash;
 
     let adder_path =
         cargo_contract::build(&utils::example_path("delegator/adder/Cargo.toml"))
             .expect("adder build failed");
-    let adder_hash = extract_hash_from_contract_bundle(&adder_path);
+    let adder_hash = ContractBundle::load(&adder_path)?.source.hash;
 
     let subber_path =
         cargo_contract::build(&utils::example_path("delegator/subber/Cargo.toml"))
             .expect("subber build failed");
-    let subber_hash = extract_hash_from_contract_bundle(&subber_path);
+    let subber_hash = ContractBundle::load(&subber_path)?.source.hash;
 
     let delegator_path =
         cargo_contract::build(&utils::example_path("delegator/Cargo.toml"))
@@ -59,37 +60,40 @@ async fn delegator_works(mut ui: Ui) -> Result<()> {
     let delegator_addr = ui
         .execute_upload(
             Upload::new(delegator_path)
-                .push_initial_value("accumulatorCodeHash", &accumulator_hash)
-                .push_initial_value("adderCodeHash", &adder_hash)
-                .push_initial_value("subberCodeHash", &subber_hash),
+                .push_initial_value(
+                    "accumulatorCodeHash",
+                    ArgValue::hex(&accumulator_hash),
+                )
+                .push_initial_value("adderCodeHash", ArgValue::hex(&adder_hash))
+                .push_initial_value("subberCodeHash", ArgValue::hex(&subber_hash)),
         )
         .await?;
 
     // then
     assert_eq!(
         ui.execute_rpc(Call::new(&delegator_addr, "get")).await?,
-        "0"
+        ArgValue::Int(0)
     );
     ui.execute_transaction(
-        Call::new(&delegator_addr, "change").push_value("by: i32", "13"),
+        Call::new(&delegator_addr, "change").push_value("by: i32", 13i32),
     )
     .await
     .expect("failed to execute transaction");
     assert_eq!(
         ui.execute_rpc(Call::new(&delegator_addr, "get")).await?,
-        "13"
+        ArgValue::Int(13)
     );
     ui.execute_transaction(Call::new(&delegator_addr, "switch"))
         .await
         .expect("failed to execute transaction");
     ui.execute_transaction(
-        Call::new(&delegator_addr, "change").push_value("by: i32", "3"),
+        Call::new(&delegator_addr, "change").push_value("by: i32", 3i32),
     )
     .await
     .expect("failed to execute transaction");
     assert_eq!(
         ui.execute_rpc(Call::new(&delegator_addr, "get")).await?,
-        "10"
+        ArgValue::Int(10)
     );
     Ok(())
 }
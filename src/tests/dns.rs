@@ -16,8 +16,10 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
         Result,
+        TransactionError,
         Ui,
         Upload,
     },
@@ -40,20 +42,22 @@ async fn dns_works(mut ui: Ui) -> Result<()> {
         .await?;
 
     // when registering and setting an address and name
-    let name = "0x0000000000000000000000000000000000000000000000000000000000000001";
-    let owner = "EVE";
+    let name = ArgValue::hex(
+        "0x0000000000000000000000000000000000000000000000000000000000000001",
+    );
+    let owner = ArgValue::account("EVE");
     ui.execute_transaction(
         Call::new(&contract_addr, "register")
             .caller("ALICE")
-            .push_value("name", name),
+            .push_value("name", name.clone()),
     )
     .await
     .expect("failed to execute `register` transaction");
     ui.execute_transaction(
         Call::new(&contract_addr, "set_address")
             .caller("ALICE")
-            .push_value("name", name)
-            .push_value("newAddress", owner),
+            .push_value("name", name.clone())
+            .push_value("newAddress", owner.clone()),
     )
     .await
     .expect("failed to execute `set_address` transaction");
@@ -63,42 +67,44 @@ async fn dns_works(mut ui: Ui) -> Result<()> {
         ui.execute_rpc(
             Call::new(&contract_addr, "get_address")
                 .caller("EVE")
-                .push_value("name", name)
+                .push_value("name", name.clone())
         )
         .await?,
         owner
     );
 
     // when trying to set the address from a different caller (BOB) the transaction must fail
-    let owner2 = "DAVE";
-    // TODO: enable after error forwarding has been implemented in ink! with
-    // https://github.com/paritytech/ink/issues/641
-    assert!(
-        true || ui
-            .execute_transaction(
-                Call::new(&contract_addr, "set_address")
-                    .caller("BOB")
-                    .push_value("name", name)
-                    .push_value("newAddress", owner2)
-            )
-            .await
-            .is_err()
-    );
+    let owner2 = ArgValue::account("DAVE");
+    let err = ui
+        .execute_transaction(
+            Call::new(&contract_addr, "set_address")
+                .caller("BOB")
+                .push_value("name", name.clone())
+                .push_value("newAddress", owner2.clone()),
+        )
+        .await
+        .expect_err("transaction must fail with `CallerIsNotOwner`");
+    match err {
+        TransactionError::ContractReverted(err) => {
+            assert_eq!(err, ArgValue::variant("CallerIsNotOwner"))
+        }
+        err => return Err(err.into()),
+    }
 
     // but if the owner is transferred to BOB he must be able to set the address
     ui.execute_transaction(
         Call::new(&contract_addr, "transfer")
             .caller("ALICE")
-            .push_value("name", name)
-            .push_value("to: AccountId", "BOB"),
+            .push_value("name", name.clone())
+            .push_value("to: AccountId", ArgValue::account("BOB")),
     )
     .await
     .expect("failed to execute `transfer` to BOB transaction");
     ui.execute_transaction(
         Call::new(&contract_addr, "set_address")
             .caller("BOB")
-            .push_value("name", name)
-            .push_value("newAddress", owner2),
+            .push_value("name", name.clone())
+            .push_value("newAddress", owner2.clone()),
     )
     .await
     .expect("failed to execute `set_address` transaction from BOB");
@@ -106,7 +112,7 @@ async fn dns_works(mut ui: Ui) -> Result<()> {
         ui.execute_rpc(
             Call::new(&contract_addr, "get_address")
                 .caller("EVE")
-                .push_value("name", name)
+                .push_value("name", name.clone())
         )
         .await?,
         owner2
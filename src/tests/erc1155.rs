@@ -16,8 +16,11 @@
 
 use crate::{
     uis::{
+        ArgValue,
+        Balance,
         Call,
         Result,
+        TransactionError,
         Ui,
         Upload,
     },
@@ -42,7 +45,7 @@ async fn erc1155(mut ui: Ui) -> Result<()> {
     ui.execute_transaction(
         Call::new(&contract_addr, "create")
             .caller("BOB")
-            .push_value("value", "123"), // initial_supply
+            .push_value("value", Balance::units(123)?), // initial_supply
     )
     .await
     .expect("failed to execute transaction");
@@ -50,17 +53,17 @@ async fn erc1155(mut ui: Ui) -> Result<()> {
     let balance = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::balance_of")
-                .push_value("owner", "BOB")
-                .push_value("tokenId", "1"),
+                .push_value("owner", ArgValue::account("BOB"))
+                .push_value("tokenId", 1u128),
         )
         .await?;
-    assert!(balance == "123,000,000,000,000" || balance == "123.0000 Unit");
+    assert_eq!(balance, Balance::units(123)?);
 
     ui.execute_transaction(
         Call::new(&contract_addr, "mint")
             .caller("CHARLIE")
-            .push_value("tokenId", "1")
-            .push_value("value", "341"), // initial_supply
+            .push_value("tokenId", 1u128)
+            .push_value("value", Balance::units(341)?), // initial_supply
     )
     .await
     .expect("failed to execute transaction");
@@ -68,40 +71,46 @@ async fn erc1155(mut ui: Ui) -> Result<()> {
     let balance = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::balance_of")
-                .push_value("owner", "CHARLIE")
-                .push_value("tokenId", "1"),
+                .push_value("owner", ArgValue::account("CHARLIE"))
+                .push_value("tokenId", 1u128),
         )
         .await?;
-    assert!(balance == "341,000,000,000,000" || balance == "341.0000 Unit");
+    assert_eq!(balance, Balance::units(341)?);
 
     let balance = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::balance_of_batch")
-                .add_item("owners", "BOB")
-                .add_item("owners", "CHARLIE")
-                .add_item("tokenIds", "0")
-                .add_item("tokenIds", "1"),
+                .add_item("owners", ArgValue::account("BOB"))
+                .add_item("owners", ArgValue::account("CHARLIE"))
+                .add_item("tokenIds", 0u128)
+                .add_item("tokenIds", 1u128),
         )
         .await?;
-    assert!(
+    assert_eq!(
+        balance,
         // [ BOB TokenId 0, BOB TokenId 1, CHARLIE TokenId 0, CHARLIE TokenId 1 ]
-        balance == "[ 0 123,000,000,000,000 0 341,000,000,000,000 ]"
+        ArgValue::Vec(vec![
+            Balance::units(0)?.into(),
+            Balance::units(123)?.into(),
+            Balance::units(0)?.into(),
+            Balance::units(341)?.into(),
+        ])
     );
 
     let is_approved_for_all = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::is_approved_for_all")
-                .push_value("owner", "CHARLIE")
-                .push_value("operator", "DAVE"),
+                .push_value("owner", ArgValue::account("CHARLIE"))
+                .push_value("operator", ArgValue::account("DAVE")),
         )
         .await?;
-    assert_eq!(is_approved_for_all, "false");
+    assert_eq!(is_approved_for_all, ArgValue::Bool(false));
 
     ui.execute_transaction(
         Call::new(&contract_addr, "erc1155::set_approval_for_all")
             .caller("CHARLIE")
-            .push_value("operator", "DAVE")
-            .push_value("approved", "true"),
+            .push_value("operator", ArgValue::account("DAVE"))
+            .push_value("approved", true),
     )
     .await
     .expect("failed to execute transaction");
@@ -109,19 +118,19 @@ async fn erc1155(mut ui: Ui) -> Result<()> {
     let is_approved_for_all = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::is_approved_for_all")
-                .push_value("owner", "CHARLIE")
-                .push_value("operator", "DAVE"),
+                .push_value("owner", ArgValue::account("CHARLIE"))
+                .push_value("operator", ArgValue::account("DAVE")),
         )
         .await?;
-    assert_eq!(is_approved_for_all, "true");
+    assert_eq!(is_approved_for_all, ArgValue::Bool(true));
 
     ui.execute_transaction(
         Call::new(&contract_addr, "erc1155::safe_transfer_from")
             .caller("DAVE")
-            .push_value("from: AccountId", "CHARLIE")
-            .push_value("to: AccountId", "ALICE")
-            .push_value("tokenId", "1")
-            .push_value("value", "41"),
+            .push_value("from: AccountId", ArgValue::account("CHARLIE"))
+            .push_value("to: AccountId", ArgValue::account("ALICE"))
+            .push_value("tokenId", 1u128)
+            .push_value("value", Balance::units(41)?),
     )
     .await
     .expect("failed to execute transaction");
@@ -129,25 +138,25 @@ async fn erc1155(mut ui: Ui) -> Result<()> {
     let balance = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::balance_of")
-                .push_value("owner", "CHARLIE")
-                .push_value("tokenId", "1"),
+                .push_value("owner", ArgValue::account("CHARLIE"))
+                .push_value("tokenId", 1u128),
         )
         .await?;
-    assert!(balance == "300,000,000,000,000" || balance == "300.0000 Unit");
+    assert_eq!(balance, Balance::units(300)?);
 
     let balance = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::balance_of")
-                .push_value("owner", "ALICE")
-                .push_value("tokenId", "1"),
+                .push_value("owner", ArgValue::account("ALICE"))
+                .push_value("tokenId", 1u128),
         )
         .await?;
-    assert!(balance == "41,000,000,000,000" || balance == "41.0000 Unit");
+    assert_eq!(balance, Balance::units(41)?);
 
     ui.execute_transaction(
         Call::new(&contract_addr, "create")
             .caller("ALICE")
-            .push_value("value", "99"),
+            .push_value("value", Balance::units(99)?),
     )
     .await
     .expect("failed to execute transaction");
@@ -155,12 +164,12 @@ async fn erc1155(mut ui: Ui) -> Result<()> {
     ui.execute_transaction(
         Call::new(&contract_addr, "erc1155::safe_batch_transfer_from")
             .caller("ALICE")
-            .push_value("from: AccountId", "ALICE")
-            .push_value("to: AccountId", "FERDIE")
-            .add_item("tokenIds", "1")
-            .add_item("tokenIds", "2")
-            .add_item("values", "41000000000000")
-            .add_item("values", "99000000000000"),
+            .push_value("from: AccountId", ArgValue::account("ALICE"))
+            .push_value("to: AccountId", ArgValue::account("FERDIE"))
+            .add_item("tokenIds", 1u128)
+            .add_item("tokenIds", 2u128)
+            .add_item("values", Balance::units(41)?)
+            .add_item("values", Balance::units(99)?),
     )
     .await
     .expect("failed to execute transaction");
@@ -168,26 +177,26 @@ async fn erc1155(mut ui: Ui) -> Result<()> {
     let balance = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::balance_of")
-                .push_value("owner", "FERDIE")
-                .push_value("tokenId", "1"),
+                .push_value("owner", ArgValue::account("FERDIE"))
+                .push_value("tokenId", 1u128),
         )
         .await?;
-    assert!(balance == "41,000,000,000,000" || balance == "41.0000 Unit");
+    assert_eq!(balance, Balance::units(41)?);
 
     let balance = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::balance_of")
-                .push_value("owner", "FERDIE")
-                .push_value("tokenId", "2"),
+                .push_value("owner", ArgValue::account("FERDIE"))
+                .push_value("tokenId", 2u128),
         )
         .await?;
-    assert!(balance == "99,000,000,000,000" || balance == "99.0000 Unit");
+    assert_eq!(balance, Balance::units(99)?);
 
     ui.execute_transaction(
         Call::new(&contract_addr, "erc1155::set_approval_for_all")
             .caller("CHARLIE")
-            .push_value("operator", "DAVE")
-            .push_value("approved", "false"),
+            .push_value("operator", ArgValue::account("DAVE"))
+            .push_value("approved", false),
     )
     .await
     .expect("failed to execute transaction");
@@ -195,28 +204,29 @@ async fn erc1155(mut ui: Ui) -> Result<()> {
     let is_approved_for_all = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::is_approved_for_all")
-                .push_value("owner", "CHARLIE")
-                .push_value("operator", "DAVE"),
+                .push_value("owner", ArgValue::account("CHARLIE"))
+                .push_value("operator", ArgValue::account("DAVE")),
         )
         .await?;
-    assert_eq!(is_approved_for_all, "false");
+    assert_eq!(is_approved_for_all, ArgValue::Bool(false));
 
-    // TODO
-    //   Has to be ignored until https://github.com/paritytech/ink/issues/641 makes `Result::Err`
-    //   visible in the UI.
-    assert!(
-        true || ui
-            .execute_transaction(
-                Call::new(&contract_addr, "erc1155::safe_transfer_from")
-                    .caller("DAVE")
-                    .push_value("from: AccountId", "CHARLIE")
-                    .push_value("to: AccountId", "ALICE")
-                    .push_value("tokenId", "1")
-                    .push_value("value", "41")
-            )
-            .await
-            .is_err()
-    );
+    let err = ui
+        .execute_transaction(
+            Call::new(&contract_addr, "erc1155::safe_transfer_from")
+                .caller("DAVE")
+                .push_value("from: AccountId", ArgValue::account("CHARLIE"))
+                .push_value("to: AccountId", ArgValue::account("ALICE"))
+                .push_value("tokenId", 1u128)
+                .push_value("value", Balance::units(41)?),
+        )
+        .await
+        .expect_err("transaction must fail with `NotApproved`");
+    match err {
+        TransactionError::ContractReverted(err) => {
+            assert_eq!(err, ArgValue::variant("NotApproved"))
+        }
+        err => return Err(err.into()),
+    }
 
     Ok(())
 }
@@ -235,17 +245,17 @@ async fn erc1155_approvals(mut ui: Ui) -> Result<()> {
     let is_approved_for_all = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::is_approved_for_all")
-                .push_value("owner", "CHARLIE")
-                .push_value("operator", "DAVE"),
+                .push_value("owner", ArgValue::account("CHARLIE"))
+                .push_value("operator", ArgValue::account("DAVE")),
         )
         .await?;
-    assert_eq!(is_approved_for_all, "false");
+    assert_eq!(is_approved_for_all, ArgValue::Bool(false));
 
     ui.execute_transaction(
         Call::new(&contract_addr, "erc1155::set_approval_for_all")
             .caller("CHARLIE")
-            .push_value("operator", "DAVE")
-            .push_value("approved", "true"),
+            .push_value("operator", ArgValue::account("DAVE"))
+            .push_value("approved", true),
     )
     .await
     .expect("failed to execute transaction");
@@ -253,17 +263,17 @@ async fn erc1155_approvals(mut ui: Ui) -> Result<()> {
     let is_approved_for_all = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::is_approved_for_all")
-                .push_value("owner", "CHARLIE")
-                .push_value("operator", "DAVE"),
+                .push_value("owner", ArgValue::account("CHARLIE"))
+                .push_value("operator", ArgValue::account("DAVE")),
         )
         .await?;
-    assert_eq!(is_approved_for_all, "true");
+    assert_eq!(is_approved_for_all, ArgValue::Bool(true));
 
     ui.execute_transaction(
         Call::new(&contract_addr, "erc1155::set_approval_for_all")
             .caller("CHARLIE")
-            .push_value("operator", "DAVE")
-            .push_value("approved", "false"),
+            .push_value("operator", ArgValue::account("DAVE"))
+            .push_value("approved", false),
     )
     .await
     .expect("failed to execute transaction");
@@ -271,10 +281,10 @@ async fn erc1155_approvals(mut ui: Ui) -> Result<()> {
     let is_approved_for_all = ui
         .execute_rpc(
             Call::new(&contract_addr, "erc1155::is_approved_for_all")
-                .push_value("owner", "CHARLIE")
-                .push_value("operator", "DAVE"),
+                .push_value("owner", ArgValue::account("CHARLIE"))
+                .push_value("operator", ArgValue::account("DAVE")),
         )
         .await?;
-    assert_eq!(is_approved_for_all, "false");
+    assert_eq!(is_approved_for_all, ArgValue::Bool(false));
     Ok(())
 }
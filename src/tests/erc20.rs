@@ -16,8 +16,15 @@
 
 use crate::{
     uis::{
+        keyring::{
+            self,
+            KeyType,
+        },
+        ArgValue,
+        Balance,
         Call,
         Result,
+        TransactionError,
         Ui,
         Upload,
     },
@@ -39,31 +46,84 @@ async fn erc20(mut ui: Ui) -> Result<()> {
         .execute_upload(
             Upload::new(contract_file)
                 .caller("BOB")
-                .push_initial_value("totalSupply", "1000"),
+                .push_initial_value("totalSupply", Balance::units(1000)?),
         )
         .await?;
     let total_supply = ui
         .execute_rpc(Call::new(&contract_addr, "total_supply"))
         .await?;
-    assert!(total_supply == "1,000,000,000,000,000" || total_supply == "1.0000 kUnit", "total_supply");
+    assert_eq!(total_supply, Balance::units(1000)?, "total_supply");
     let balance = ui
-        .execute_rpc(Call::new(&contract_addr, "balance_of").push_value("owner", "BOB"))
+        .execute_rpc(
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("BOB")),
+        )
         .await?;
-    assert!(balance == "1,000,000,000,000,000" || balance == "1.0000 kUnit", "balance pre");
+    assert_eq!(balance, Balance::units(1000)?, "balance pre");
 
-    ui.execute_transaction(
-        Call::new(&contract_addr, "transfer")
-            .caller("BOB")
-            .push_value("to: AccountId", "ALICE")
-            .push_value("value", "500"),
-    )
-    .await
-    .expect("failed to execute transaction");
+    let events = ui
+        .execute_transaction(
+            Call::new(&contract_addr, "transfer")
+                .caller("BOB")
+                .push_value("to: AccountId", ArgValue::account("ALICE"))
+                .push_value("value", Balance::units(500)?),
+        )
+        .await
+        .expect("failed to execute transaction");
+    assert_eq!(events.contract_events().len(), 1);
+    let transfer = events
+        .find_contract_event("Transfer")
+        .expect("no `Transfer` event emitted");
+    assert_eq!(transfer.contract(), contract_addr);
+    assert_eq!(
+        transfer.field("from"),
+        Some(&ArgValue::some(ArgValue::account("BOB")))
+    );
+    assert_eq!(
+        transfer.field("to"),
+        Some(&ArgValue::some(ArgValue::account("ALICE")))
+    );
+    assert_eq!(transfer.field("value"), Some(&Balance::units(500)?.into()));
+    // the event signature, followed by `from` and `to`
+    assert_eq!(transfer.topics().len(), 3);
 
     let balance = ui
-        .execute_rpc(Call::new(&contract_addr, "balance_of").push_value("owner", "ALICE"))
+        .execute_rpc(
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("ALICE")),
+        )
         .await?;
-    assert!(balance == "500,000,000,000,000" || balance == "500.0000 Unit", "balance post");
+    assert_eq!(balance, Balance::units(500)?, "balance post");
+
+    // accounts other than the dev accounts can be registered for the test
+    let oscar = keyring::register("OSCAR", "//Oscar", KeyType::Ed25519)?;
+    let events = ui
+        .execute_transaction(
+            Call::new(&contract_addr, "transfer")
+                .caller("ALICE")
+                .push_value("to: AccountId", ArgValue::account("OSCAR"))
+                .push_value("value", Balance::units(100)?),
+        )
+        .await
+        .expect("failed to execute transaction");
+    let transfer = events
+        .find_contract_event("Transfer")
+        .expect("no `Transfer` event emitted");
+    assert_eq!(
+        transfer.field("to"),
+        Some(&ArgValue::some(ArgValue::account("OSCAR")))
+    );
+    let balance = ui
+        .execute_rpc(
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account(&oscar.to_string())),
+        )
+        .await?;
+    assert_eq!(
+        balance,
+        Balance::units(100)?,
+        "balance of registered account"
+    );
 
     Ok(())
 }
@@ -79,86 +139,109 @@ async fn erc20_allowances(mut ui: Ui) -> Result<()> {
         .execute_upload(
             Upload::new(contract_file)
                 .caller("BOB")
-                .push_initial_value("totalSupply", "1000"),
+                .push_initial_value("totalSupply", Balance::units(1000)?),
         )
         .await?;
 
     // Alice tries to transfer tokens on behalf ob Bob
-    // TODO
-    //   Has to be ignored until https://github.com/paritytech/ink/issues/641 makes `Result::Err`
-    //   visible in the UI.
-    assert!(
-        true || ui
-            .execute_transaction(
-                Call::new(&contract_addr, "transfer_from")
-                    .caller("ALICE")
-                    .push_value("from: AccountId", "BOB")
-                    .push_value("to: AccountId", "ALICE")
-                    .push_value("value", "400"),
-            )
-            .await
-            .is_err()
-    );
+    let err = ui
+        .execute_transaction(
+            Call::new(&contract_addr, "transfer_from")
+                .caller("ALICE")
+                .push_value("from: AccountId", ArgValue::account("BOB"))
+                .push_value("to: AccountId", ArgValue::account("ALICE"))
+                .push_value("value", Balance::units(400)?),
+        )
+        .await
+        .expect_err("transaction must fail with `InsufficientAllowance`");
+    match err {
+        TransactionError::ContractReverted(err) => {
+            assert_eq!(err, ArgValue::variant("InsufficientAllowance"))
+        }
+        err => return Err(err.into()),
+    }
 
     // Bob approves Alice being able to withdraw up the `value` amount on his behalf.
-    ui.execute_transaction(
-        Call::new(&contract_addr, "approve")
-            .caller("BOB")
-            .push_value("spender", "ALICE")
-            .push_value("value", "600"),
-    )
-    .await
-    .expect("`approve` must succeed");
+    let events = ui
+        .execute_transaction(
+            Call::new(&contract_addr, "approve")
+                .caller("BOB")
+                .push_value("spender", ArgValue::account("ALICE"))
+                .push_value("value", Balance::units(600)?),
+        )
+        .await
+        .expect("`approve` must succeed");
+    let approval = events
+        .find_contract_event("Approval")
+        .expect("no `Approval` event emitted");
+    assert_eq!(
+        approval.fields(),
+        &[
+            ("owner".to_string(), ArgValue::account("BOB")),
+            ("spender".to_string(), ArgValue::account("ALICE")),
+            ("value".to_string(), Balance::units(600)?.into()),
+        ]
+    );
     let allowance = ui
         .execute_rpc(
             Call::new(&contract_addr, "allowance")
-                .push_value("owner", "BOB")
-                .push_value("spender", "ALICE"),
+                .push_value("owner", ArgValue::account("BOB"))
+                .push_value("spender", ArgValue::account("ALICE")),
         )
         .await?;
-    assert!(allowance == "600,000,000,000,000" || allowance == "600.0000 Unit", "allowance");
+    assert_eq!(allowance, Balance::units(600)?, "allowance");
 
     // Alice tries again to transfer tokens on behalf ob Bob
     ui.execute_transaction(
         Call::new(&contract_addr, "transfer_from")
             .caller("ALICE")
-            .push_value("from: AccountId", "BOB")
-            .push_value("to: AccountId", "ALICE")
-            .push_value("value", "400"),
+            .push_value("from: AccountId", ArgValue::account("BOB"))
+            .push_value("to: AccountId", ArgValue::account("ALICE"))
+            .push_value("value", Balance::units(400)?),
     )
     .await
     .expect("second `transfer_from` must succeed");
     let balance = ui
-        .execute_rpc(Call::new(&contract_addr, "balance_of").push_value("owner", "ALICE"))
+        .execute_rpc(
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("ALICE")),
+        )
         .await?;
-    assert!(balance == "400,000,000,000,000" || balance == "400.0000 Unit");
+    assert_eq!(balance, Balance::units(400)?);
     let balance = ui
-        .execute_rpc(Call::new(&contract_addr, "balance_of").push_value("owner", "BOB"))
+        .execute_rpc(
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("BOB")),
+        )
         .await?;
-    assert!(balance == "600,000,000,000,000" || balance == "600.0000 Unit");
+    assert_eq!(balance, Balance::units(600)?);
 
     // Alice tries to transfer even more tokens on behalf ob Bob, this time exhausting the allowance
-    // TODO
-    //   Has to be ignored until https://github.com/paritytech/ink/issues/641 makes `Result::Err`
-    //   visible in the UI.
-    assert!(
-        true || ui
-            .execute_transaction(
-                Call::new(&contract_addr, "transfer_from")
-                    .caller("ALICE")
-                    .push_value("from: AccountId", "BOB")
-                    .push_value("to: AccountId", "ALICE")
-                    .push_value("value", "201"),
-            )
-            .await
-            .is_err()
-    );
+    let err = ui
+        .execute_transaction(
+            Call::new(&contract_addr, "transfer_from")
+                .caller("ALICE")
+                .push_value("from: AccountId", ArgValue::account("BOB"))
+                .push_value("to: AccountId", ArgValue::account("ALICE"))
+                .push_value("value", Balance::units(201)?),
+        )
+        .await
+        .expect_err("transaction must fail with `InsufficientAllowance`");
+    match err {
+        TransactionError::ContractReverted(err) => {
+            assert_eq!(err, ArgValue::variant("InsufficientAllowance"))
+        }
+        err => return Err(err.into()),
+    }
 
     // Balance of Bob must have stayed the same
     let balance = ui
-        .execute_rpc(Call::new(&contract_addr, "balance_of").push_value("owner", "BOB"))
+        .execute_rpc(
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("BOB")),
+        )
         .await?;
-    assert!(balance == "600,000,000,000,000" || balance == "600.0000 Unit");
+    assert_eq!(balance, Balance::units(600)?);
 
     Ok(())
 }
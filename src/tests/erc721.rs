@@ -16,6 +16,7 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
         Result,
         Ui,
@@ -40,154 +41,166 @@ async fn erc721(mut ui: Ui) -> Result<()> {
     ui.execute_transaction(
         Call::new(&contract_addr, "mint")
             .caller("ALICE")
-            .push_value("id", "123"),
+            .push_value("id", 123u32),
     )
     .await
     .expect("`mint` must succeed");
     assert_eq!(
         ui.execute_rpc(
             Call::new(&contract_addr, "balance_of")
-                .push_value("owner", "ALICE")
+                .push_value("owner", ArgValue::account("ALICE"))
                 .caller("ALICE")
         )
         .await?,
-        "1"
+        ArgValue::UInt(1)
     );
     assert_eq!(
-        ui.execute_rpc(Call::new(&contract_addr, "owner_of").push_value("id", "123"))
+        ui.execute_rpc(Call::new(&contract_addr, "owner_of").push_value("id", 123u32))
             .await?,
-        "ALICE"
+        ArgValue::some(ArgValue::account("ALICE"))
     );
 
     ui.execute_transaction(
         Call::new(&contract_addr, "transfer")
             .caller("ALICE")
-            .push_value("destination", "BOB")
-            .push_value("id", "123"),
+            .push_value("destination", ArgValue::account("BOB"))
+            .push_value("id", 123u32),
     )
     .await
     .expect("`transfer` must succeed");
     assert_eq!(
         ui.execute_rpc(
-            Call::new(&contract_addr, "balance_of").push_value("owner", "ALICE")
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("ALICE"))
         )
         .await?,
-        "0"
+        ArgValue::UInt(0)
     );
     assert_eq!(
         ui.execute_rpc(
-            Call::new(&contract_addr, "balance_of").push_value("owner", "BOB")
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("BOB"))
         )
         .await?,
-        "1"
+        ArgValue::UInt(1)
     );
     assert_eq!(
-        ui.execute_rpc(Call::new(&contract_addr, "owner_of").push_value("id", "123"))
+        ui.execute_rpc(Call::new(&contract_addr, "owner_of").push_value("id", 123u32))
             .await?,
-        "BOB"
+        ArgValue::some(ArgValue::account("BOB"))
     );
     ui.execute_transaction(
         Call::new(&contract_addr, "approve")
             .caller("BOB")
-            .push_value("to: AccountId", "CHARLIE")
-            .push_value("id", "123"),
+            .push_value("to: AccountId", ArgValue::account("CHARLIE"))
+            .push_value("id", 123u32),
     )
     .await
     .expect("`approve` must succeed");
     assert_eq!(
-        ui.execute_rpc(Call::new(&contract_addr, "get_approved").push_value("id", "123"))
-            .await?,
-        "CHARLIE"
+        ui.execute_rpc(
+            Call::new(&contract_addr, "get_approved").push_value("id", 123u32)
+        )
+        .await?,
+        ArgValue::some(ArgValue::account("CHARLIE"))
     );
     assert_eq!(
         ui.execute_rpc(
-            Call::new(&contract_addr, "balance_of").push_value("owner", "ALICE")
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("ALICE"))
         )
         .await?,
-        "0"
+        ArgValue::UInt(0)
     );
 
     assert_eq!(
         ui.execute_rpc(
-            Call::new(&contract_addr, "balance_of").push_value("owner", "BOB")
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("BOB"))
         )
         .await?,
-        "1"
+        ArgValue::UInt(1)
     );
     assert_eq!(
         ui.execute_rpc(
-            Call::new(&contract_addr, "balance_of").push_value("owner", "CHARLIE")
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("CHARLIE"))
         )
         .await?,
-        "0"
+        ArgValue::UInt(0)
     );
     assert_eq!(
-        ui.execute_rpc(Call::new(&contract_addr, "owner_of").push_value("id", "123"))
+        ui.execute_rpc(Call::new(&contract_addr, "owner_of").push_value("id", 123u32))
             .await?,
-        "BOB"
+        ArgValue::some(ArgValue::account("BOB"))
     );
 
     assert_eq!(
         ui.execute_rpc(
-            Call::new(&contract_addr, "balance_of").push_value("owner", "DAVE")
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("DAVE"))
         )
         .await?,
-        "0"
+        ArgValue::UInt(0)
     );
 
     ui.execute_transaction(
         Call::new(&contract_addr, "transfer_from")
             .caller("CHARLIE")
-            .push_value("from: AccountId", "BOB")
-            .push_value("to: AccountId", "DAVE")
-            .push_value("id", "123"),
+            .push_value("from: AccountId", ArgValue::account("BOB"))
+            .push_value("to: AccountId", ArgValue::account("DAVE"))
+            .push_value("id", 123u32),
     )
     .await
     .expect("`transfer_from` must succeed");
     assert_eq!(
         ui.execute_rpc(
-            Call::new(&contract_addr, "balance_of").push_value("owner", "BOB")
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("BOB"))
         )
         .await?,
-        "0"
+        ArgValue::UInt(0)
     );
     assert_eq!(
         ui.execute_rpc(
-            Call::new(&contract_addr, "balance_of").push_value("owner", "CHARLIE")
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("CHARLIE"))
         )
         .await?,
-        "0"
+        ArgValue::UInt(0)
     );
     assert_eq!(
         ui.execute_rpc(
-            Call::new(&contract_addr, "balance_of").push_value("owner", "DAVE")
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("DAVE"))
         )
         .await?,
-        "1"
+        ArgValue::UInt(1)
     );
     assert_eq!(
-        ui.execute_rpc(Call::new(&contract_addr, "owner_of").push_value("id", "123"))
+        ui.execute_rpc(Call::new(&contract_addr, "owner_of").push_value("id", 123u32))
             .await?,
-        "DAVE"
+        ArgValue::some(ArgValue::account("DAVE"))
     );
     ui.execute_transaction(
         Call::new(&contract_addr, "burn")
             .caller("DAVE")
-            .push_value("id", "123"),
+            .push_value("id", 123u32),
     )
     .await
     .expect("`burn` must succeed");
     assert_eq!(
         ui.execute_rpc(
-            Call::new(&contract_addr, "balance_of").push_value("owner", "DAVE")
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("DAVE"))
         )
         .await?,
-        "0"
+        ArgValue::UInt(0)
     );
     assert_eq!(
-        ui.execute_rpc(Call::new(&contract_addr, "owner_of").push_value("id", "123"))
+        ui.execute_rpc(Call::new(&contract_addr, "owner_of").push_value("id", 123u32))
             .await?,
-        "None"
+        ArgValue::none()
     );
 
     Ok(())
@@ -205,51 +218,51 @@ async fn erc721_operator_approvals(mut ui: Ui) -> Result<()> {
     ui.execute_transaction(
         Call::new(&contract_addr, "mint")
             .caller("ALICE")
-            .push_value("id", "123"),
+            .push_value("id", 123u32),
     )
     .await
     .expect("`mint` must succeed");
     ui.execute_transaction(
         Call::new(&contract_addr, "mint")
             .caller("ALICE")
-            .push_value("id", "321"),
+            .push_value("id", 321u32),
     )
     .await
     .expect("`mint` must succeed");
     ui.execute_transaction(
         Call::new(&contract_addr, "set_approval_for_all")
             .caller("ALICE")
-            .push_value("to: AccountId", "BOB")
-            .push_value("approved", "true"),
+            .push_value("to: AccountId", ArgValue::account("BOB"))
+            .push_value("approved", true),
     )
     .await
     .expect("`approve_for_all` must succeed");
     assert_eq!(
         ui.execute_rpc(
             Call::new(&contract_addr, "is_approved_for_all")
-                .push_value("owner", "ALICE")
-                .push_value("operator", "BOB")
+                .push_value("owner", ArgValue::account("ALICE"))
+                .push_value("operator", ArgValue::account("BOB"))
         )
         .await?,
-        "true"
+        ArgValue::Bool(true)
     );
 
     // when
     ui.execute_transaction(
         Call::new(&contract_addr, "transfer_from")
             .caller("BOB")
-            .push_value("from: AccountId", "ALICE")
-            .push_value("to: AccountId", "CHARLIE")
-            .push_value("id", "123"),
+            .push_value("from: AccountId", ArgValue::account("ALICE"))
+            .push_value("to: AccountId", ArgValue::account("CHARLIE"))
+            .push_value("id", 123u32),
     )
     .await
     .expect("`transfer` must succeed");
     ui.execute_transaction(
         Call::new(&contract_addr, "transfer_from")
             .caller("BOB")
-            .push_value("from: AccountId", "ALICE")
-            .push_value("to: AccountId", "CHARLIE")
-            .push_value("id", "321"),
+            .push_value("from: AccountId", ArgValue::account("ALICE"))
+            .push_value("to: AccountId", ArgValue::account("CHARLIE"))
+            .push_value("id", 321u32),
     )
     .await
     .expect("`transfer` must succeed");
@@ -257,17 +270,19 @@ async fn erc721_operator_approvals(mut ui: Ui) -> Result<()> {
     // then
     assert_eq!(
         ui.execute_rpc(
-            Call::new(&contract_addr, "balance_of").push_value("owner", "ALICE")
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("ALICE"))
         )
         .await?,
-        "0"
+        ArgValue::UInt(0)
     );
     assert_eq!(
         ui.execute_rpc(
-            Call::new(&contract_addr, "balance_of").push_value("owner", "CHARLIE")
+            Call::new(&contract_addr, "balance_of")
+                .push_value("owner", ArgValue::account("CHARLIE"))
         )
         .await?,
-        "2"
+        ArgValue::UInt(2)
     );
 
     Ok(())
@@ -16,6 +16,7 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
         Result,
         Ui,
@@ -23,7 +24,10 @@ use crate::{
     },
     utils::{
         self,
-        cargo_contract,
+        cargo_contract::{
+            self,
+            BuildOptions,
+        },
     },
 };
 use lang_macro::waterfall_test;
@@ -38,7 +42,7 @@ async fn flipper_works(mut ui: Ui) -> Result<()> {
     let contract_addr = ui.execute_upload(Upload::new(contract_file)).await?;
     assert_eq!(
         ui.execute_rpc(Call::new(&contract_addr, "get")).await?,
-        "false"
+        ArgValue::Bool(false)
     );
 
     // when
@@ -51,7 +55,7 @@ async fn flipper_works(mut ui: Ui) -> Result<()> {
     // then
     assert_eq!(
         ui.execute_rpc(Call::new(&contract_addr, "get")).await?,
-        "true"
+        ArgValue::Bool(true)
     );
     Ok(())
 }
@@ -71,7 +75,38 @@ async fn flipper_default_constructor(mut ui: Ui) -> Result<()> {
     // then
     assert_eq!(
         ui.execute_rpc(Call::new(&contract_addr, "get")).await?,
-        "false"
+        ArgValue::Bool(false)
+    );
+    Ok(())
+}
+
+#[waterfall_test(example = "flipper")]
+async fn flipper_variant_works(mut ui: Ui) -> Result<()> {
+    // given
+    let manifest_path = utils::example_path("flipper/Cargo.toml");
+    // the variant must not overwrite the artifacts of the default build
+    let target_dir = utils::example_path("flipper")
+        .canonicalize()?
+        .join("target/waterfall-variant");
+    let options = BuildOptions::new()
+        .release()
+        .feature("e2e-tests")
+        .optimization_passes("0")
+        .keep_debug_symbols()
+        .target_dir(target_dir);
+    let contract_file = cargo_contract::build_with(&manifest_path, &options)
+        .expect("contract build failed");
+    let contract_addr = ui.execute_upload(Upload::new(contract_file)).await?;
+
+    // when
+    ui.execute_transaction(Call::new(&contract_addr, "flip"))
+        .await
+        .expect("failed to execute transaction");
+
+    // then
+    assert_eq!(
+        ui.execute_rpc(Call::new(&contract_addr, "get")).await?,
+        ArgValue::Bool(true)
     );
     Ok(())
 }
@@ -16,6 +16,7 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
         Result,
         Ui,
@@ -36,22 +37,22 @@ async fn incrementer_works(mut ui: Ui) -> Result<()> {
         cargo_contract::build(&manifest_path).expect("contract build failed");
 
     let contract_addr = ui
-        .execute_upload(Upload::new(contract_file).push_initial_value("initValue", "13"))
+        .execute_upload(Upload::new(contract_file).push_initial_value("initValue", 13i32))
         .await?;
     assert_eq!(
         ui.execute_rpc(Call::new(&contract_addr, "get")).await?,
-        "13"
+        ArgValue::Int(13)
     );
 
     // when
-    ui.execute_transaction(Call::new(&contract_addr, "inc").push_value("by: i32", "14"))
+    ui.execute_transaction(Call::new(&contract_addr, "inc").push_value("by: i32", 14i32))
         .await
         .expect("failed to execute transaction");
 
     // then
     assert_eq!(
         ui.execute_rpc(Call::new(&contract_addr, "get")).await?,
-        "27"
+        ArgValue::Int(27)
     );
     Ok(())
 }
@@ -69,6 +70,9 @@ async fn incrementer_default_constructor(mut ui: Ui) -> Result<()> {
         .await?;
 
     // then
-    assert_eq!(ui.execute_rpc(Call::new(&contract_addr, "get")).await?, "0");
+    assert_eq!(
+        ui.execute_rpc(Call::new(&contract_addr, "get")).await?,
+        ArgValue::Int(0)
+    );
     Ok(())
 }
@@ -16,6 +16,8 @@
 
 use crate::{
     uis::{
+        ArgValue,
+        Balance,
         Call,
         Result,
         Ui,
@@ -44,24 +46,30 @@ async fn multisig_works_with_flipper_transaction(mut ui: Ui) -> Result<()> {
         .execute_upload(
             Upload::new(contract_file)
                 .caller("ALICE")
-                .push_initial_value("requirement", "2")
-                .add_item("owners", "ALICE")
-                .add_item("owners", "BOB")
-                .add_item("owners", "EVE"),
+                .push_initial_value("requirement", 2u32)
+                .add_item("owners", ArgValue::account("ALICE"))
+                .add_item("owners", ArgValue::account("BOB"))
+                .add_item("owners", ArgValue::account("EVE")),
         )
         .await?;
 
     ui.execute_transaction(
         Call::new(&contract_addr, "submit_transaction")
-                .caller("ALICE")
-                .push_value("callee", &flipper_contract_addr)
-                .push_value("selector", "0x633aa551") // `flip`
-                .push_value("input", "0x00")
-                .push_value("transferredValue", "0"),
+            .caller("ALICE")
+            .push_value(
+                "transaction",
+                ArgValue::Struct(vec![
+                    ("callee".into(), ArgValue::account(&flipper_contract_addr)),
+                    // `flip`
+                    ("selector".into(), ArgValue::from([0x63, 0x3a, 0xa5, 0x51])),
+                    ("input".into(), ArgValue::Bytes(Vec::new())),
+                    ("transferredValue".into(), Balance::plancks(0).into()),
+                ]),
+            ),
     )
     .await
     .expect("failed to `submit_transaction`");
-    let id = "0";
+    let id = 0u32;
     ui.execute_transaction(
         Call::new(&contract_addr, "confirm_transaction")
             .caller("ALICE")
@@ -81,7 +89,7 @@ async fn multisig_works_with_flipper_transaction(mut ui: Ui) -> Result<()> {
     assert_eq!(
         ui.execute_rpc(Call::new(&flipper_contract_addr, "get"))
             .await?,
-        "false"
+        ArgValue::Bool(false)
     );
 
     // when
@@ -96,7 +104,7 @@ async fn multisig_works_with_flipper_transaction(mut ui: Ui) -> Result<()> {
     assert_eq!(
         ui.execute_rpc(Call::new(&flipper_contract_addr, "get"))
             .await?,
-        "true"
+        ArgValue::Bool(true)
     );
 
     Ok(())
@@ -118,23 +126,29 @@ async fn multisig_works_with_payable_transaction(mut ui: Ui) -> Result<()> {
         .execute_upload(
             Upload::new(contract_file)
                 .caller("ALICE")
-                .push_initial_value("requirement", "2")
-                .add_item("owners", "ALICE")
-                .add_item("owners", "BOB")
-                .add_item("owners", "EVE"),
+                .push_initial_value("requirement", 2u32)
+                .add_item("owners", ArgValue::account("ALICE"))
+                .add_item("owners", ArgValue::account("BOB"))
+                .add_item("owners", ArgValue::account("EVE")),
         )
         .await?;
 
     let call = Call::new(&contract_addr, "submit_transaction")
-            .caller("ALICE")
-            .push_value("callee", &contract_transfer_addr)
-            .push_value("selector", "0xcafebabe") // `was_it_ten`
-            .push_value("input", "0x00")
-            .push_value("transferredValue", "10");
+        .caller("ALICE")
+        .push_value(
+            "transaction",
+            ArgValue::Struct(vec![
+                ("callee".into(), ArgValue::account(&contract_transfer_addr)),
+                // `was_it_ten`
+                ("selector".into(), ArgValue::from([0xca, 0xfe, 0xba, 0xbe])),
+                ("input".into(), ArgValue::Bytes(Vec::new())),
+                ("transferredValue".into(), Balance::plancks(10).into()),
+            ]),
+        );
     ui.execute_transaction(call)
         .await
         .expect("failed to `submit_transaction`");
-    let id = "0";
+    let id = 0u32;
     ui.execute_transaction(
         Call::new(&contract_addr, "confirm_transaction")
             .caller("ALICE")
@@ -152,18 +166,18 @@ async fn multisig_works_with_payable_transaction(mut ui: Ui) -> Result<()> {
     .expect("failed to `confirm_transaction`");
 
     // when
-    ui.execute_transaction(
-        Call::new(&contract_addr, "invoke_transaction")
-            .caller("ALICE")
-            .push_value("transId", id)
-            //.payment("10", "pico"),
-            .payment("0.000000000010", "Unit"),
-    )
-    .await
-    .expect("failed to `invoke_transaction`");
+    let events = ui
+        .execute_transaction(
+            Call::new(&contract_addr, "invoke_transaction")
+                .caller("ALICE")
+                .push_value("transId", id)
+                .payment(Balance::plancks(10)),
+        )
+        .await
+        .expect("failed to `invoke_transaction`");
 
     // then
-    assert!(utils::node_log_contains("received payment: 10\n"));
+    assert!(events.debug_message().contains("received payment: 10\n"));
 
     Ok(())
 }
@@ -16,6 +16,7 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
         Result,
         Ui,
@@ -42,7 +43,7 @@ async fn rand_extension(mut ui: Ui) -> Result<()> {
         ui.execute_rpc(Call::new(&contract_addr, "get"))
             .await
             .expect("failed to execute rpc"),
-        "<empty>"
+        ArgValue::Bytes(vec![0; 32])
     );
     let _events = ui
         .execute_transaction(Call::new(&contract_addr, "update"))
@@ -54,7 +55,7 @@ async fn rand_extension(mut ui: Ui) -> Result<()> {
         ui.execute_rpc(Call::new(&contract_addr, "get"))
             .await
             .expect("failed to execute rpc"),
-        "<empty>"
+        ArgValue::Bytes(vec![0; 32])
     );
     Ok(())
 }
@@ -16,7 +16,9 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
+        ContractBundle,
         Result,
         Ui,
         Upload,
@@ -35,18 +37,21 @@ async fn delegator_works(mut ui: Ui) -> Result<()> {
     let contract_path =
         cargo_contract::build(&utils::example_path("seal-code-hash/Cargo.toml"))
             .expect("contract build failed");
-    let bundle_hash = utils::extract_hash_from_contract_bundle(&contract_path);
+    let bundle_hash = ContractBundle::load(&contract_path)?.source.hash;
 
     // when
     let addr = ui.execute_upload(Upload::new(contract_path)).await?;
 
     // then
     let deployed_hash = ui
-        .execute_rpc(Call::new(&addr, "code_hash").push_value("account_id", &addr))
+        .execute_rpc(
+            Call::new(&addr, "code_hash")
+                .push_value("account_id", ArgValue::account(&addr)),
+        )
         .await?;
     let own_code_hash = ui.execute_rpc(Call::new(&addr, "own_code_hash")).await?;
     assert_eq!(own_code_hash, deployed_hash);
-    assert_eq!(own_code_hash, bundle_hash);
+    assert_eq!(own_code_hash, ArgValue::hex(&bundle_hash));
 
     Ok(())
 }
@@ -16,6 +16,7 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
         Result,
         Ui,
@@ -60,14 +61,11 @@ async fn seal_ecdsa_recover(mut ui: Ui) -> Result<()> {
     let recovered_pk = ui
         .execute_rpc(
             Call::new(&addr, "recover")
-                .push_value("signature", &format!("0x{}", hex::encode(SIGNATURE)))
-                .push_value("message_hash", &format!("0x{}", hex::encode(MESSSAGE_HASH))),
+                .push_value("signature", SIGNATURE)
+                .push_value("message_hash", MESSSAGE_HASH),
         )
         .await?;
-    assert_eq!(
-        recovered_pk,
-        format!("0x{}", hex::encode(EXPECTED_COMPRESSED_PUBLIC_KEY))
-    );
+    assert_eq!(recovered_pk, ArgValue::from(EXPECTED_COMPRESSED_PUBLIC_KEY));
 
     Ok(())
 }
@@ -95,12 +93,9 @@ async fn seal_ecdsa_to_eth_address(mut ui: Ui) -> Result<()> {
     ];
 
     let eth_addr = ui
-        .execute_rpc(
-            Call::new(&addr, "to_eth_address")
-                .push_value("pub_key", &format!("0x{}", hex::encode(PUB_KEY))),
-        )
+        .execute_rpc(Call::new(&addr, "to_eth_address").push_value("pub_key", PUB_KEY))
         .await?;
-    assert_eq!(eth_addr, format!("0x{}", hex::encode(EXPECTED_ETH_ADDRESS)));
+    assert_eq!(eth_addr, ArgValue::from(EXPECTED_ETH_ADDRESS));
 
     Ok(())
 }
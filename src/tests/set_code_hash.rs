@@ -16,7 +16,9 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
+        ContractBundle,
         Result,
         Ui,
         Upload,
@@ -45,8 +47,9 @@ async fn set_code_hash_works(mut ui: Ui) -> Result<()> {
     );
     let updated_incrementer_bundle =
         cargo_contract::build(&manifest_path).expect("contract build failed");
-    let updated_incrementer_hash =
-        utils::extract_hash_from_contract_bundle(&updated_incrementer_bundle);
+    let updated_incrementer_hash = ContractBundle::load(&updated_incrementer_bundle)?
+        .source
+        .hash;
     let _updated_incrementer_addr = ui
         .execute_upload(Upload::new(updated_incrementer_bundle.clone()))
         .await?;
@@ -56,11 +59,11 @@ async fn set_code_hash_works(mut ui: Ui) -> Result<()> {
         .expect("failed to `submit_transaction`");
     assert_eq!(
         ui.execute_rpc(Call::new(&incrementer_addr, "get")).await?,
-        "1"
+        ArgValue::UInt(1)
     );
 
     let call = Call::new(&incrementer_addr, "set_code")
-        .push_value("code_hash", &updated_incrementer_hash);
+        .push_value("code_hash", ArgValue::hex(&updated_incrementer_hash));
     ui.execute_transaction(call).await.expect("must work");
 
     ui.execute_transaction(Call::new(&incrementer_addr, "inc"))
@@ -68,7 +71,7 @@ async fn set_code_hash_works(mut ui: Ui) -> Result<()> {
         .expect("failed to `submit_transaction`");
     assert_eq!(
         ui.execute_rpc(Call::new(&incrementer_addr, "get")).await?,
-        "5"
+        ArgValue::UInt(5)
     );
 
     Ok(())
@@ -16,8 +16,11 @@
 
 use crate::{
     uis::{
+        ArgValue,
+        Balance,
         Call,
         Result,
+        TransactionError,
         Ui,
         Upload,
     },
@@ -39,25 +42,26 @@ async fn trait_erc20(mut ui: Ui) -> Result<()> {
         .execute_upload(
             Upload::new(contract_file)
                 .caller("BOB")
-                .push_initial_value("totalSupply", "1000"),
+                .push_initial_value("totalSupply", Balance::units(1000)?),
         )
         .await?;
     let total_supply = ui
         .execute_rpc(Call::new(&contract_addr, "baseErc20::total_supply"))
         .await?;
-    assert!(total_supply == "1,000,000,000,000,000" || total_supply == "1.0000 kUnit");
+    assert_eq!(total_supply, Balance::units(1000)?);
     let balance = ui
         .execute_rpc(
-            Call::new(&contract_addr, "baseErc20::balanceOf").push_value("owner", "BOB"),
+            Call::new(&contract_addr, "baseErc20::balanceOf")
+                .push_value("owner", ArgValue::account("BOB")),
         )
         .await?;
-    assert!(balance == "1,000,000,000,000,000" || balance == "1.0000 kUnit");
+    assert_eq!(balance, Balance::units(1000)?);
 
     ui.execute_transaction(
         Call::new(&contract_addr, "baseErc20::transfer")
             .caller("BOB")
-            .push_value("to: AccountId", "ALICE")
-            .push_value("value", "500"),
+            .push_value("to: AccountId", ArgValue::account("ALICE"))
+            .push_value("value", Balance::units(500)?),
     )
     .await
     .expect("failed to execute transaction");
@@ -65,10 +69,10 @@ async fn trait_erc20(mut ui: Ui) -> Result<()> {
     let balance = ui
         .execute_rpc(
             Call::new(&contract_addr, "baseErc20::balanceOf")
-                .push_value("owner", "ALICE"),
+                .push_value("owner", ArgValue::account("ALICE")),
         )
         .await?;
-    assert!(balance == "500,000,000,000,000" || balance == "500.0000 Unit");
+    assert_eq!(balance, Balance::units(500)?);
 
     Ok(())
 }
@@ -84,93 +88,97 @@ async fn trait_erc20_allowances(mut ui: Ui) -> Result<()> {
         .execute_upload(
             Upload::new(contract_file)
                 .caller("BOB")
-                .push_initial_value("totalSupply", "1000"),
+                .push_initial_value("totalSupply", Balance::units(1000)?),
         )
         .await?;
 
     // Alice tries to transfer tokens on behalf ob Bob
-    // TODO
-    //   Has to be ignored until https://github.com/paritytech/ink/issues/641 makes `Result::Err`
-    //   visible in the UI.
-    assert!(
-        true || ui
-            .execute_transaction(
-                Call::new(&contract_addr, "baseErc20::transferFrom")
-                    .caller("ALICE")
-                    .push_value("from: AccountId", "BOB")
-                    .push_value("to: AccountId", "ALICE")
-                    .push_value("value", "400"),
-            )
-            .await
-            .is_err()
-    );
+    let err = ui
+        .execute_transaction(
+            Call::new(&contract_addr, "baseErc20::transferFrom")
+                .caller("ALICE")
+                .push_value("from: AccountId", ArgValue::account("BOB"))
+                .push_value("to: AccountId", ArgValue::account("ALICE"))
+                .push_value("value", Balance::units(400)?),
+        )
+        .await
+        .expect_err("transaction must fail with `InsufficientAllowance`");
+    match err {
+        TransactionError::ContractReverted(err) => {
+            assert_eq!(err, ArgValue::variant("InsufficientAllowance"))
+        }
+        err => return Err(err.into()),
+    }
 
     // Bob approves Alice being able to withdraw up the `value` amount on his behalf.
     ui.execute_transaction(
         Call::new(&contract_addr, "baseErc20::approve")
             .caller("BOB")
-            .push_value("spender", "ALICE")
-            .push_value("value", "600"),
+            .push_value("spender", ArgValue::account("ALICE"))
+            .push_value("value", Balance::units(600)?),
     )
     .await
     .expect("`approve` must succeed");
     let allowance = ui
         .execute_rpc(
             Call::new(&contract_addr, "baseErc20::allowance")
-                .push_value("owner", "BOB")
-                .push_value("spender", "ALICE"),
+                .push_value("owner", ArgValue::account("BOB"))
+                .push_value("spender", ArgValue::account("ALICE")),
         )
         .await?;
-    assert!(allowance == "600,000,000,000,000" || allowance == "600.0000 Unit");
+    assert_eq!(allowance, Balance::units(600)?);
 
     // Alice tries again to transfer tokens on behalf ob Bob
     ui.execute_transaction(
         Call::new(&contract_addr, "baseErc20::transferFrom")
             .caller("ALICE")
-            .push_value("from: AccountId", "BOB")
-            .push_value("to: AccountId", "ALICE")
-            .push_value("value", "400"),
+            .push_value("from: AccountId", ArgValue::account("BOB"))
+            .push_value("to: AccountId", ArgValue::account("ALICE"))
+            .push_value("value", Balance::units(400)?),
     )
     .await
     .expect("second `transferFrom` must succeed");
     let balance = ui
         .execute_rpc(
             Call::new(&contract_addr, "baseErc20::balanceOf")
-                .push_value("owner", "ALICE"),
+                .push_value("owner", ArgValue::account("ALICE")),
         )
         .await?;
-    assert!(balance == "400,000,000,000,000" || balance == "400.0000 Unit");
+    assert_eq!(balance, Balance::units(400)?);
     let balance = ui
         .execute_rpc(
-            Call::new(&contract_addr, "baseErc20::balanceOf").push_value("owner", "BOB"),
+            Call::new(&contract_addr, "baseErc20::balanceOf")
+                .push_value("owner", ArgValue::account("BOB")),
         )
         .await?;
-    assert!(balance == "600,000,000,000,000" || balance == "600.0000 Unit");
+    assert_eq!(balance, Balance::units(600)?);
 
     // Alice tries to transfer even more tokens on behalf ob Bob, this time exhausting the allowance
-    // TODO
-    //   Has to be ignored until https://github.com/paritytech/ink/issues/641 makes `Result::Err`
-    //   visible in the UI.
-    assert!(
-        true || ui
-            .execute_transaction(
-                Call::new(&contract_addr, "baseErc20::transferFrom")
-                    .caller("ALICE")
-                    .push_value("from: AccountId", "BOB")
-                    .push_value("to: AccountId", "ALICE")
-                    .push_value("value", "201"),
-            )
-            .await
-            .is_err()
-    );
+    let err = ui
+        .execute_transaction(
+            Call::new(&contract_addr, "baseErc20::transferFrom")
+                .caller("ALICE")
+                .push_value("from: AccountId", ArgValue::account("BOB"))
+                .push_value("to: AccountId", ArgValue::account("ALICE"))
+                .push_value("value", Balance::units(201)?),
+        )
+        .await
+        .expect_err("transaction must fail with `InsufficientAllowance`");
+    match err {
+        TransactionError::ContractReverted(err) => {
+            assert_eq!(err, ArgValue::variant("InsufficientAllowance"))
+        }
+        err => return Err(err.into()),
+    }
 
     // Balance of Bob must have stayed the same
     let balance = ui
         .execute_rpc(
-            Call::new(&contract_addr, "baseErc20::balanceOf").push_value("owner", "BOB"),
+            Call::new(&contract_addr, "baseErc20::balanceOf")
+                .push_value("owner", ArgValue::account("BOB")),
         )
         .await?;
-    assert!(balance == "600,000,000,000,000" || balance == "600.0000 Unit");
+    assert_eq!(balance, Balance::units(600)?);
 
     Ok(())
 }
@@ -16,6 +16,7 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
         Result,
         Ui,
@@ -39,7 +40,7 @@ async fn trait_flipper_works(mut ui: Ui) -> Result<()> {
     assert_eq!(
         ui.execute_rpc(Call::new(&contract_addr, "flip::get"))
             .await?,
-        "false"
+        ArgValue::Bool(false)
     );
 
     // when
@@ -51,7 +52,7 @@ async fn trait_flipper_works(mut ui: Ui) -> Result<()> {
     assert_eq!(
         ui.execute_rpc(Call::new(&contract_addr, "flip::get"))
             .await?,
-        "true"
+        ArgValue::Bool(true)
     );
     Ok(())
 }
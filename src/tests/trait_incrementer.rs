@@ -16,6 +16,7 @@
 
 use crate::{
     uis::{
+        ArgValue,
         Call,
         Result,
         Ui,
@@ -36,16 +37,16 @@ async fn trait_incrementer_works(mut ui: Ui) -> Result<()> {
         cargo_contract::build(&manifest_path).expect("contract build failed");
 
     let contract_addr = ui
-        .execute_upload(Upload::new(contract_file).push_initial_value("initValue", "13"))
+        .execute_upload(Upload::new(contract_file).push_initial_value("initValue", 13u64))
         .await?;
     assert_eq!(
         ui.execute_rpc(Call::new(&contract_addr, "increment::get"))
             .await?,
-        "13"
+        ArgValue::UInt(13)
     );
 
     // when
-    ui.execute_transaction(Call::new(&contract_addr, "incBy").push_value("delta", "14"))
+    ui.execute_transaction(Call::new(&contract_addr, "incBy").push_value("delta", 14u64))
         .await
         .expect("failed to execute transaction");
 
@@ -53,7 +54,7 @@ async fn trait_incrementer_works(mut ui: Ui) -> Result<()> {
     assert_eq!(
         ui.execute_rpc(Call::new(&contract_addr, "increment::get"))
             .await?,
-        "27"
+        ArgValue::UInt(27)
     );
     ui.execute_transaction(Call::new(&contract_addr, "reset::reset"))
         .await
@@ -61,7 +62,7 @@ async fn trait_incrementer_works(mut ui: Ui) -> Result<()> {
     assert_eq!(
         ui.execute_rpc(Call::new(&contract_addr, "increment::get"))
             .await?,
-        "0"
+        ArgValue::UInt(0)
     );
     Ok(())
 }
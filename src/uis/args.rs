@@ -0,0 +1,316 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed values for the arguments of constructors and messages.
+//!
+//! Each backend renders these into its own form widgets, e.g. a boolean is
+//! a dropdown in the browser UIs, but a SCALE encoded byte for the RPC backend.
+
+use crate::uis::{
+    keyring,
+    Balance,
+    Result,
+    Token,
+};
+
+/// A typed value, either an argument of a constructor or message, or the
+/// decoded return value of a message.
+#[derive(Clone, Debug)]
+pub enum ArgValue {
+    /// A boolean.
+    Bool(bool),
+    /// An unsigned integer of any width.
+    UInt(u128),
+    /// A signed integer of any width.
+    Int(i128),
+    /// An amount of the chain's token.
+    Balance(Balance),
+    /// An account, either the name of an account in the keyring (e.g. `ALICE`) or
+    /// an SS58 address.
+    Account(String),
+    /// A byte array or byte vector, e.g. a `Hash`.
+    Bytes(Vec<u8>),
+    /// A string.
+    Str(String),
+    /// An `Option`.
+    Option(Option<Box<ArgValue>>),
+    /// A vector of values.
+    Vec(Vec<ArgValue>),
+    /// A struct with named fields.
+    Struct(Vec<(String, ArgValue)>),
+    /// A variant of an enum, with its (unnamed) fields.
+    Enum(String, Vec<ArgValue>),
+}
+
+impl ArgValue {
+    /// Returns an account argument for `account`, which is either the name of a dev
+    /// account (e.g. `ALICE`) or an SS58 address.
+    pub fn account(account: &str) -> Self {
+        ArgValue::Account(account.to_string())
+    }
+
+    /// Returns a bytes argument for the `0x` prefixed `hex` string.
+    ///
+    /// # Panics
+    ///
+    /// If `hex` is not valid hex.
+    pub fn hex(hex: &str) -> Self {
+        let bytes = hex::decode(hex.trim_start_matches("0x"))
+            .unwrap_or_else(|err| panic!("{:?} is not valid hex: {:?}", hex, err));
+        ArgValue::Bytes(bytes)
+    }
+
+    /// Returns `Some(value)`.
+    pub fn some(value: impl Into<ArgValue>) -> Self {
+        ArgValue::Option(Some(Box::new(value.into())))
+    }
+
+    /// Returns `None`.
+    pub fn none() -> Self {
+        ArgValue::Option(None)
+    }
+
+    /// Returns the variant `name` of an enum without fields, e.g. an error
+    /// such as `InsufficientBalance`.
+    pub fn variant(name: &str) -> Self {
+        ArgValue::Enum(name.to_string(), Vec::new())
+    }
+
+    /// Returns `true` if the value is one of the dev accounts, which the browser
+    /// UIs offer in an account dropdown.
+    pub(crate) fn is_dev_account(&self) -> bool {
+        match self {
+            ArgValue::Account(name) => keyring::is_dev_account(name),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the value is chosen from a dropdown in the browser UIs.
+    pub(crate) fn is_choice(&self) -> bool {
+        matches!(self, ArgValue::Bool(_) | ArgValue::Enum(_, _))
+    }
+
+    /// Renders the value as it is entered into a form field of the browser UIs,
+    /// for a chain with `token`.
+    ///
+    /// Fails for vectors and structs which are nested into other values, the
+    /// UIs have no single field for them.
+    pub(crate) fn render(&self, token: &Token) -> Result<String> {
+        let rendered = match self {
+            ArgValue::Bool(true) => String::from("Yes"),
+            ArgValue::Bool(false) => String::from("No"),
+            // The browser UIs expect balance inputs in units of the chain's token.
+            ArgValue::Balance(balance) => balance.in_units(token.decimals),
+            ArgValue::Option(Some(value)) => value.render(token)?,
+            ArgValue::Option(None) => String::new(),
+            other => other.render_item()?,
+        };
+        Ok(rendered)
+    }
+
+    /// Renders the value as it is entered into an item of a vector field in the
+    /// browser UIs. In contrast to top-level fields, balances are not scaled there.
+    pub(crate) fn render_item(&self) -> Result<String> {
+        let rendered = match self {
+            ArgValue::Bool(val) => val.to_string(),
+            ArgValue::UInt(val) => val.to_string(),
+            ArgValue::Int(val) => val.to_string(),
+            ArgValue::Balance(balance) => balance.as_plancks().to_string(),
+            // The UIs only know the dev accounts by name, other accounts are
+            // entered by their address.
+            ArgValue::Account(account) if keyring::is_dev_account(account) => {
+                account.clone()
+            }
+            ArgValue::Account(account) => {
+                keyring::resolve_account(account)
+                    .map(|account_id| account_id.to_string())
+                    .unwrap_or_else(|_| account.clone())
+            }
+            ArgValue::Bytes(bytes) => format!("0x{}", hex::encode(bytes)),
+            ArgValue::Str(val) => val.clone(),
+            ArgValue::Option(Some(value)) => value.render_item()?,
+            ArgValue::Option(None) => String::new(),
+            ArgValue::Enum(variant, _) => variant.clone(),
+            ArgValue::Vec(_) | ArgValue::Struct(_) => {
+                return Err(
+                    format!("{:?} can not be entered into a single field", self).into()
+                )
+            }
+        };
+        Ok(rendered)
+    }
+}
+
+macro_rules! impl_from_int {
+    ( $variant:ident: $( $ty:ty ),* ) => {
+        $(
+            impl From<$ty> for ArgValue {
+                fn from(val: $ty) -> Self {
+                    ArgValue::$variant(val.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(UInt: u8, u16, u32, u64, u128);
+impl_from_int!(Int: i8, i16, i32, i64, i128);
+
+impl From<bool> for ArgValue {
+    fn from(val: bool) -> Self {
+        ArgValue::Bool(val)
+    }
+}
+
+impl From<Balance> for ArgValue {
+    fn from(balance: Balance) -> Self {
+        ArgValue::Balance(balance)
+    }
+}
+
+impl PartialEq for ArgValue {
+    /// Compares the values structurally, but an unsigned integer equals the
+    /// balance of as many plancks. Outcomes only hold balances where the type
+    /// in the contract metadata is named `Balance`, e.g. not within the
+    /// `MessageResult` of ink! 4.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ArgValue::Bool(a), ArgValue::Bool(b)) => a == b,
+            (ArgValue::UInt(a), ArgValue::UInt(b)) => a == b,
+            (ArgValue::Int(a), ArgValue::Int(b)) => a == b,
+            (ArgValue::Balance(a), ArgValue::Balance(b)) => a == b,
+            (ArgValue::UInt(plancks), ArgValue::Balance(balance))
+            | (ArgValue::Balance(balance), ArgValue::UInt(plancks)) => {
+                balance.as_plancks() == *plancks
+            }
+            (ArgValue::Account(a), ArgValue::Account(b)) => a == b,
+            (ArgValue::Bytes(a), ArgValue::Bytes(b)) => a == b,
+            (ArgValue::Str(a), ArgValue::Str(b)) => a == b,
+            (ArgValue::Option(a), ArgValue::Option(b)) => a == b,
+            (ArgValue::Vec(a), ArgValue::Vec(b)) => a == b,
+            (ArgValue::Struct(a), ArgValue::Struct(b)) => a == b,
+            (ArgValue::Enum(a, a_fields), ArgValue::Enum(b, b_fields)) => {
+                a == b && a_fields == b_fields
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ArgValue {}
+
+impl PartialEq<Balance> for ArgValue {
+    fn eq(&self, balance: &Balance) -> bool {
+        *self == ArgValue::Balance(*balance)
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for ArgValue {
+    fn from(bytes: [u8; N]) -> Self {
+        ArgValue::Bytes(bytes.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for ArgValue {
+    fn from(bytes: Vec<u8>) -> Self {
+        ArgValue::Bytes(bytes)
+    }
+}
+
+/// Flattens `values` and `items` into the separate inputs of the browser UIs.
+///
+/// The fields of structs become separate fields and the elements of vectors become
+/// items. Returns the fields and the items.
+#[allow(clippy::type_complexity)]
+pub(crate) fn form_inputs(
+    values: &[(String, ArgValue)],
+    items: &[(String, ArgValue)],
+) -> (Vec<(String, ArgValue)>, Vec<(String, ArgValue)>) {
+    let mut fields = Vec::new();
+    let mut all_items = Vec::new();
+    for (key, value) in values {
+        flatten(key, value, false, &mut fields, &mut all_items);
+    }
+    all_items.extend(items.iter().cloned());
+    (fields, all_items)
+}
+
+/// Flattens `value` into `fields` and `items`, `nested` tells if `value` is the
+/// field of a struct.
+fn flatten(
+    key: &str,
+    value: &ArgValue,
+    nested: bool,
+    fields: &mut Vec<(String, ArgValue)>,
+    items: &mut Vec<(String, ArgValue)>,
+) {
+    match value {
+        ArgValue::Struct(struct_fields) => {
+            for (field, value) in struct_fields {
+                flatten(field, value, true, fields, items);
+            }
+        }
+        ArgValue::Vec(elements) => {
+            items.extend(
+                elements
+                    .iter()
+                    .map(|element| (key.to_string(), element.clone())),
+            )
+        }
+        // The UIs only scale the balance arguments of a message to units, a
+        // balance within a struct is entered in plancks.
+        ArgValue::Balance(balance) if nested => {
+            fields.push((key.to_string(), ArgValue::UInt(balance.as_plancks())))
+        }
+        other => fields.push((key.to_string(), other.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balances_within_structs_are_entered_in_plancks() {
+        // given
+        let token = Token::default();
+        let values = [
+            (String::from("value"), Balance::plancks(10).into()),
+            (
+                String::from("transaction"),
+                ArgValue::Struct(vec![(
+                    String::from("transferredValue"),
+                    Balance::plancks(10).into(),
+                )]),
+            ),
+        ];
+
+        // when
+        let (fields, _) = form_inputs(&values, &[]);
+        let rendered = fields
+            .iter()
+            .map(|(key, value)| Ok((key.as_str(), value.render(&token)?)))
+            .collect::<Result<Vec<_>>>()
+            .expect("rendering failed");
+
+        // then
+        assert_eq!(
+            rendered,
+            [
+                ("value", Balance::plancks(10).in_units(token.decimals)),
+                ("transferredValue", String::from("10")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,462 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Balances of the chain's token.
+//!
+//! The UIs display balances in many shapes, e.g. as comma-grouped plancks
+//! (`1,000,000,000,000,000`) or in units of the token with an SI prefix
+//! (`1.0000 kUnit`). A [`Balance`] is always held in plancks and can be parsed
+//! from and formatted into each of those shapes.
+
+use crate::uis::Result;
+use std::{
+    fmt,
+    str::FromStr,
+    sync::OnceLock,
+};
+
+/// The SI prefixes which the UIs use for balances, with their exponent.
+const SI_PREFIXES: [(char, i32); 11] = [
+    ('f', -15),
+    ('p', -12),
+    ('n', -9),
+    ('µ', -6),
+    ('m', -3),
+    ('k', 3),
+    ('M', 6),
+    ('G', 9),
+    ('T', 12),
+    ('P', 15),
+    ('E', 18),
+];
+
+/// The token of the chain which the tests run against, it is known once the
+/// first `Ui` connected to the node.
+static CHAIN_TOKEN: OnceLock<Token> = OnceLock::new();
+
+/// The token of a chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    /// The number of decimals, i.e. one unit of the token are `10^decimals` plancks.
+    pub decimals: u32,
+    /// The symbol of the token, e.g. `Unit`.
+    pub symbol: String,
+}
+
+impl Token {
+    /// Creates a new `Token` with `decimals` and `symbol`.
+    pub fn new(decimals: u32, symbol: &str) -> Self {
+        Self {
+            decimals,
+            symbol: symbol.to_string(),
+        }
+    }
+
+    /// Returns the token of the chain which the tests run against.
+    ///
+    /// Fails before the first `Ui` connected to the node.
+    pub fn chain() -> Result<Self> {
+        CHAIN_TOKEN.get().cloned().ok_or_else(|| {
+            "the chain's token is not known before the first `Ui` connected to the node"
+                .into()
+        })
+    }
+
+    /// Sets the token of the chain which the tests run against.
+    pub(crate) fn set_chain(token: &Token) {
+        let chain = CHAIN_TOKEN.get_or_init(|| token.clone());
+        if chain != token {
+            log::warn!(
+                "ignoring token {:?}, the tests already run against a chain with {:?}",
+                token,
+                chain
+            );
+        }
+    }
+}
+
+impl Default for Token {
+    /// The token of the `substrate-contracts-node`, as the UIs display it.
+    fn default() -> Self {
+        Self::new(12, "Unit")
+    }
+}
+
+/// An amount of the chain's token, held in plancks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Balance(u128);
+
+impl Balance {
+    /// Returns a balance of `plancks`.
+    pub const fn plancks(plancks: u128) -> Self {
+        Self(plancks)
+    }
+
+    /// Returns a balance of `units` of the chain's token.
+    ///
+    /// Fails before the chain's token is known, see [`Token::chain`], or if the
+    /// balance overflows.
+    pub fn units(units: u128) -> Result<Self> {
+        let decimals = Token::chain()?.decimals;
+        10u128
+            .checked_pow(decimals)
+            .and_then(|unit| units.checked_mul(unit))
+            .map(Self)
+            .ok_or_else(|| {
+                format!(
+                    "{} units with {} decimals overflow a balance",
+                    units, decimals
+                )
+                .into()
+            })
+    }
+
+    /// Returns the balance in plancks.
+    pub const fn as_plancks(&self) -> u128 {
+        self.0
+    }
+
+    /// Parses `text` as a balance of `token`.
+    ///
+    /// Amounts with a unit are in units of the token, possibly with an SI prefix
+    /// and a fractional part (e.g. `1.0000 kUnit` or `0.000000000010 Unit`).
+    /// Amounts without a unit are in plancks (e.g. `1,000,000`).
+    pub fn parse(text: &str, token: &Token) -> Result<Self> {
+        let text = text.trim();
+        let end = text
+            .find(|c: char| !c.is_ascii_digit() && c != ',' && c != '.')
+            .unwrap_or(text.len());
+        let (amount, unit) = text.split_at(end);
+        let unit = unit.trim();
+        if amount.is_empty() {
+            return Err(format!("{:?} is not a balance", text).into())
+        }
+        if unit.is_empty() {
+            return Ok(Self(to_plancks(amount, 0)?))
+        }
+        let decimals = token.decimals as i32 + unit_exponent(unit, token)?;
+        if decimals >= 0 {
+            return Ok(Self(to_plancks(amount, decimals as u32)?))
+        }
+        // The unit is smaller than a planck, e.g. `pUnit` of a token with less than
+        // 12 decimals.
+        let plancks_per_unit = 10u128.pow(decimals.unsigned_abs());
+        let amount = to_plancks(amount, 0)?;
+        if amount % plancks_per_unit != 0 {
+            return Err(format!("{:?} is not a whole number of plancks", text).into())
+        }
+        Ok(Self(amount / plancks_per_unit))
+    }
+
+    /// Formats the balance in units of a token with `decimals`, without trailing
+    /// zeros, e.g. `0.00000000001`. This is what the UIs expect as input.
+    pub fn in_units(&self, decimals: u32) -> String {
+        let (integer, fraction) = match 10u128.checked_pow(decimals) {
+            Some(unit) => (self.0 / unit, self.0 % unit),
+            // a unit is more than any balance
+            None => (0, self.0),
+        };
+        if fraction == 0 {
+            return integer.to_string()
+        }
+        let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+        format!("{}.{}", integer, fraction.trim_end_matches('0'))
+    }
+
+    /// Formats the balance with an SI prefix and four decimals, the way the
+    /// `polkadot-js` UI displays it, e.g. `1.0000 kUnit`.
+    pub fn format_si(&self, token: &Token) -> String {
+        let digits = self.0.to_string().len() as i32;
+        let exponent = if self.0 == 0 {
+            0
+        } else {
+            ((digits - 1 - token.decimals as i32).div_euclid(3) * 3).clamp(-15, 18)
+        };
+        let prefix = SI_PREFIXES
+            .iter()
+            .find(|(_, si)| *si == exponent)
+            .map(|(prefix, _)| prefix.to_string())
+            .unwrap_or_default();
+        let decimals = token.decimals as i32 + exponent;
+        let split = if decimals < 0 {
+            10u128
+                .checked_pow(decimals.unsigned_abs())
+                .and_then(|scale| self.0.checked_mul(scale))
+                .map(|integer| (integer, 0))
+        } else {
+            10u128.checked_pow(decimals as u32).map(|unit| {
+                // the remainder times `10_000` might overflow, e.g. at the `E`
+                // prefix of a token with 18 decimals
+                let remainder = self.0 % unit;
+                let fraction = if unit >= 10_000 {
+                    remainder / (unit / 10_000)
+                } else {
+                    remainder * 10_000 / unit
+                };
+                (self.0 / unit, fraction)
+            })
+        };
+        let Some((integer, fraction)) = split else {
+            // the prefixed unit does not fit into a balance, the decimals of
+            // the token are too many for an SI prefix
+            return format!("{} {}", self.in_units(token.decimals), token.symbol)
+        };
+        format!(
+            "{}.{:04} {}{}",
+            group_digits(&integer.to_string()),
+            fraction,
+            prefix,
+            token.symbol
+        )
+    }
+}
+
+/// The balance of an account, as stored in the `System::Account` storage.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AccountBalance {
+    /// The balance which can be used for transfers and fees.
+    pub free: Balance,
+    /// The balance which is reserved, e.g. for storage deposits.
+    pub reserved: Balance,
+    /// The part of the free balance which can not be transferred.
+    pub frozen: Balance,
+}
+
+/// The change of an account's free balance, net of the transaction fees which the
+/// account paid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BalanceDelta {
+    /// The account received this balance.
+    Received(Balance),
+    /// The account spent this balance, e.g. as payment for a contract call.
+    Spent(Balance),
+}
+
+impl From<u128> for Balance {
+    fn from(plancks: u128) -> Self {
+        Self(plancks)
+    }
+}
+
+impl FromStr for Balance {
+    type Err = Box<dyn std::error::Error + Send + Sync>;
+
+    /// Parses `s` as a balance of the chain's token, see [`Balance::parse`].
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s, &Token::chain()?)
+    }
+}
+
+impl fmt::Display for Balance {
+    /// Displays the balance in units of the chain's token, e.g. `500 Unit`, or
+    /// in plancks before the token is known.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match Token::chain() {
+            Ok(token) => write!(f, "{} {}", self.in_units(token.decimals), token.symbol),
+            Err(_) => write!(f, "{} plancks", self.0),
+        }
+    }
+}
+
+/// Returns the exponent of the SI prefix of `unit`, e.g. `3` for `kUnit`.
+fn unit_exponent(unit: &str, token: &Token) -> Result<i32> {
+    if unit.eq_ignore_ascii_case(&token.symbol) {
+        return Ok(0)
+    }
+    let mut chars = unit.chars();
+    let prefix = chars.next().expect("unit is not empty");
+    let exponent = SI_PREFIXES
+        .iter()
+        .find(|(si, _)| *si == prefix)
+        .map(|(_, exponent)| *exponent);
+    match exponent {
+        Some(exponent) if chars.as_str().eq_ignore_ascii_case(&token.symbol) => {
+            Ok(exponent)
+        }
+        _ => Err(format!("unknown unit {:?} of token {:?}", unit, token.symbol).into()),
+    }
+}
+
+/// Returns the number of `plancks` for `amount` units of a token with `decimals`.
+///
+/// The amount may contain a fractional part (e.g. `0.000000000010`) and
+/// comma separators.
+fn to_plancks(amount: &str, decimals: u32) -> Result<u128> {
+    let amount = amount.replace(',', "");
+    let (integer, fraction) = amount.split_once('.').unwrap_or((&amount, ""));
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals as usize {
+        return Err(format!("{:?} has more than {} decimals", amount, decimals).into())
+    }
+    let integer: u128 = if integer.is_empty() {
+        0
+    } else {
+        integer.parse()?
+    };
+    let scale = decimals - fraction.len() as u32;
+    let fraction: u128 = if fraction.is_empty() {
+        0
+    } else {
+        fraction.parse()?
+    };
+    let scaled_fraction = 10u128
+        .checked_pow(scale)
+        .and_then(|scale| fraction.checked_mul(scale));
+    10u128
+        .checked_pow(decimals)
+        .and_then(|unit| integer.checked_mul(unit))
+        .zip(scaled_fraction)
+        .and_then(|(integer, fraction)| integer.checked_add(fraction))
+        .ok_or_else(|| format!("{:?} overflows a balance", amount).into())
+}
+
+/// Groups the digits of `number` in blocks of three, separated by commas.
+fn group_digits(number: &str) -> String {
+    let mut grouped = String::new();
+    for (index, digit) in number.chars().enumerate() {
+        if index > 0 && (number.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts_without_unit_are_parsed_as_plancks() {
+        // given
+        let token = Token::default();
+
+        // when
+        let balance = Balance::parse("1,000,000", &token).expect("parsing failed");
+
+        // then
+        assert_eq!(balance, Balance::plancks(1_000_000));
+    }
+
+    #[test]
+    fn amounts_with_unit_are_parsed_in_units_of_the_token() {
+        // given
+        let token = Token::default();
+
+        // when
+        let parse = |text| Balance::parse(text, &token).expect("parsing failed");
+
+        // then
+        assert_eq!(
+            parse("1.0000 kUnit"),
+            Balance::plancks(1_000_000_000_000_000)
+        );
+        assert_eq!(parse("500 Unit"), Balance::plancks(500_000_000_000_000));
+        assert_eq!(parse("1.05 Unit"), Balance::plancks(1_050_000_000_000));
+        assert_eq!(parse("0.000000000010 Unit"), Balance::plancks(10));
+        assert_eq!(parse("1.0000 pUnit"), Balance::plancks(1));
+    }
+
+    #[test]
+    fn invalid_amounts_are_rejected() {
+        // given
+        let token = Token::default();
+
+        // when
+        let parse = |text| Balance::parse(text, &token);
+
+        // then
+        assert!(parse("").is_err());
+        assert!(parse("Unit").is_err());
+        assert!(parse("1 DOT").is_err());
+        assert!(parse("0.0000000000001 Unit").is_err());
+        assert!(parse("0.1 fUnit").is_err());
+        assert!(parse("1 fUnit").is_err());
+        assert!(parse("340282366920938463463374607431768211456").is_err());
+        assert!(parse("1,000,000,000 EUnit").is_err());
+    }
+
+    #[test]
+    fn balances_are_formatted_in_units_without_trailing_zeros() {
+        // given
+        let decimals = Token::default().decimals;
+
+        // when
+        let in_units = |plancks| Balance::plancks(plancks).in_units(decimals);
+
+        // then
+        assert_eq!(in_units(0), "0");
+        assert_eq!(in_units(10), "0.00000000001");
+        assert_eq!(in_units(5_000_000_000_000), "5");
+        assert_eq!(in_units(1_050_000_000_000), "1.05");
+        assert_eq!(
+            Balance::plancks(1).in_units(40),
+            format!("0.{}1", "0".repeat(39))
+        );
+    }
+
+    #[test]
+    fn balances_are_formatted_with_si_prefix_and_truncated_to_four_decimals() {
+        // given
+        let token = Token::default();
+
+        // when
+        let format_si = |plancks| Balance::plancks(plancks).format_si(&token);
+
+        // then
+        assert_eq!(format_si(0), "0.0000 Unit");
+        assert_eq!(format_si(1), "1.0000 pUnit");
+        assert_eq!(format_si(10), "10.0000 pUnit");
+        assert_eq!(format_si(1_000_000_000_000), "1.0000 Unit");
+        assert_eq!(format_si(1_000_000_000_000_000), "1.0000 kUnit");
+        assert_eq!(format_si(1_234_567_000_000_000_000), "1.2345 MUnit");
+        assert_eq!(format_si(999_999_999_999), "999.9999 mUnit");
+        assert_eq!(format_si(10u128.pow(38)), "100,000,000.0000 EUnit");
+    }
+
+    #[test]
+    fn balances_of_tokens_with_many_decimals_do_not_overflow() {
+        // given
+        let ether = Token::new(18, "ETH");
+        let huge = Token::new(60, "HUGE");
+
+        // when
+        let balance = Balance::plancks(100 * 10u128.pow(36) + 5 * 10u128.pow(35) - 1);
+
+        // then
+        assert_eq!(balance.format_si(&ether), "100.4999 EETH");
+        assert_eq!(
+            Balance::plancks(1).format_si(&huge),
+            format!("0.{}1 HUGE", "0".repeat(59))
+        );
+    }
+
+    #[test]
+    fn formatted_balances_are_parsed_back() {
+        // given
+        let token = Token::new(10, "DOT");
+        let balances = [0, 1, 12_345, 10_000_000_000, 123_400_000_000_000_000];
+
+        for plancks in balances {
+            // when
+            let balance = Balance::plancks(plancks);
+            let si = Balance::parse(&balance.format_si(&token), &token);
+            let units = Balance::parse(&format!("{} DOT", balance.in_units(10)), &token);
+
+            // then
+            assert_eq!(si.expect("parsing failed"), balance);
+            assert_eq!(units.expect("parsing failed"), balance);
+        }
+    }
+}
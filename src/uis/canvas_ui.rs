@@ -14,12 +14,17 @@
 
 use crate::{
     uis::{
+        args,
+        decode,
+        retry,
+        wait,
+        ArgValue,
+        Browser,
         Call,
         ContractsUi,
-        Event,
         Events,
         Result,
-        TransactionError,
+        Token,
         TransactionResult,
         Upload,
     },
@@ -30,59 +35,290 @@ use crate::{
 };
 use async_trait::async_trait;
 use fantoccini::Locator;
-use rand::Rng;
 use regex::Regex;
+use std::{
+    ops::{
+        Deref,
+        DerefMut,
+    },
+    path::Path,
+    time::{
+        Duration,
+        Instant,
+    },
+};
 
-#[async_trait]
-impl ContractsUi for crate::uis::Ui {
-    /// Returns the balance postfix numbers.
-    async fn balance_postfix(&mut self, account: String) -> Result<u128> {
-        // The `canvas-ui` doesn't display the balance, so we need to piggy-back
-        // on `polkadot-js`.
-        let log_id = format!("{} {}", test_name(), account.clone());
-        log::info!("[{}] getting balance_postfix for {:?}", log_id, account);
-        self.client
-            .goto(&format!(
-                "https://polkadot.js.org/apps/?rpc=ws%3A%2F%2F127.0.0.1%3A{}#/accounts",
-                utils::node_port()
-            ))
-            .await?;
+/// How long to wait for the "Skip Intro" button, which is not always displayed.
+const INTRO_TIMEOUT: Duration = Duration::from_secs(2);
 
-        // Firefox might not load if the website at that address is already open, hence we refresh
-        // just to be sure that it's a clean, freshly loaded page in front of us.
-        self.client.refresh().await?;
+/// After how long the "Call" button is clicked again, if the outcome of an RPC
+/// call is not yet displayed.
+const CALL_AGAIN_AFTER: Duration = Duration::from_secs(15);
 
-        log::info!("[{}] waiting for page to become visible", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//div[@class = 'menuSection']"))
+/// The notification which the UI displays once an extrinsic has a final status.
+const OUTCOME: Locator<'static> = Locator::XPath(
+    "//*[contains(text(),'Dismiss') or contains(text(),'usurped') or contains(text(),'Priority is too low')]",
+);
+
+/// Drives the `canvas-ui` in the browser.
+pub struct CanvasUi(Browser);
+
+impl CanvasUi {
+    /// Creates a new `CanvasUi` instance for a chain with `token`, spawning a
+    /// browser session.
+    pub async fn new(token: Token) -> Result<Self> {
+        Ok(Self(Browser::new(token).await?))
+    }
+}
+
+impl Deref for CanvasUi {
+    type Target = Browser;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for CanvasUi {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[async_trait]
+impl ContractsUi for CanvasUi {
+    /// Uploads the contract behind `contract_path`.
+    ///
+    /// # Developer Note
+    ///
+    /// This method must not make any assumptions about the state of the Ui before
+    /// the method is invoked. It must e.g. open the upload page right at the start.
+    async fn execute_upload(
+        &mut self,
+        upload_input: Upload,
+    ) -> TransactionResult<String> {
+        let addr = self
+            .retry
+            .run_if("upload", retry::is_retryable_in_browser, || {
+                self.upload(&upload_input)
+            })
             .await?;
+        self.contracts.load(&addr, &upload_input.contract_path)?;
+        Ok(addr)
+    }
 
-        std::thread::sleep(std::time::Duration::from_secs(2));
+    /// Executes the RPC call `call`.
+    ///
+    /// # Developer Note
+    ///
+    /// This method must not make any assumptions about the state of the Ui before
+    /// the method is invoked. It must e.g. open the upload page right at the start.
+    async fn execute_rpc(&mut self, call: Call) -> Result<ArgValue> {
+        let log_id = format!("{} {}", test_name(), call.method.clone());
 
-        let path = format!(
-            "//div[. = '{}']/ancestor::tr//span[@class = 'ui--FormatBalance-postfix']",
-            account
+        let url = format!("{}{}/0", url("execute/"), call.contract_address);
+        log::info!(
+            "[{}] opening url for rpc {:?}: {:?}",
+            log_id,
+            call.method,
+            url
         );
-        let balance = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(&path))
-            .await?
-            .text()
-            .await?;
-        log::info!("[{}] extracted balance {:?} for account", log_id, balance);
-        Ok(balance.parse::<u128>().expect("failed parsing"))
+        self.client.goto(url.as_str()).await?;
+
+        // hack to get around a failure of the ui for the multisig tests.
+        // the ui fails displaying the flipper contract execution page, but
+        // it strangely works if loaded again after some time.
+        let listbox = Locator::XPath(
+            "//*[contains(text(),'Message to Send')]/ancestor::div[1]/div",
+        );
+        let client = &self.client;
+        let url = url.as_str();
+        let log_id = log_id.as_str();
+        wait::wait_until(
+            "execution page displayed",
+            wait::timeout(),
+            Duration::from_secs(2),
+            || {
+                async move {
+                    if client.find(listbox).await.is_ok() {
+                        return Ok(Some(()))
+                    }
+                    log::info!("[{}] loading {} again", log_id, url);
+                    client.refresh().await?;
+                    client.goto(url).await?;
+                    Ok(None)
+                }
+            },
+        )
+        .await?;
+
+        // open listbox for methods
+        log::info!("[{}] click listbox", log_id);
+        self.click(Locator::XPath(
+            "//*[contains(text(),'Message to Send')]/ancestor::div[1]/div",
+        ))
+        .await?;
+
+        // click `method`
+        log::info!("[{}] choose {:?}", log_id, call.method);
+        let path = format!("//*[contains(text(),'Message to Send')]/ancestor::div[1]/div//*[text() = '{}']", call.method);
+        self.click(Locator::XPath(&path)).await?;
+
+        // Open listbox
+        log::info!("[{}] open listbox for rpc vs. transaction", log_id);
+        let path = "//*[contains(text(),'Send as RPC call')]/ancestor::div[1]/ancestor::div[1]/ancestor::div[1]";
+        self.click(Locator::XPath(path)).await?;
+
+        // Send as RPC call
+        log::info!("[{}] select 'Send as RPC call'", log_id);
+        let path = "//*[contains(text(),'Send as RPC call')]/ancestor::div[1]";
+        self.click(Locator::XPath(path)).await?;
+
+        // possibly set max gas
+        if let Some(max_gas) = &call.max_gas_allowed {
+            // click checkbox
+            log::info!(
+                "[{}] unset 'use estimated gas' checkbox if it exists",
+                log_id
+            );
+            let path = "//*[contains(text(),'use estimated gas')]/ancestor::div[1]/div";
+            let checkbox = self.client.find(Locator::XPath(path)).await;
+
+            if let Ok(checkbox) = checkbox {
+                log::info!(
+                    "[{}] unsetting 'use estimated gas' checkbox - it exists",
+                    log_id
+                );
+                checkbox.click().await?;
+            }
+
+            log::info!("[{}] entering max gas {:?}", log_id, max_gas);
+            let path = "//*[contains(text(),'Max Gas Allowed')]/ancestor::div[1]/div//input[@type = 'text']";
+            self.fill(Locator::XPath(path), max_gas).await?;
+        }
+
+        // possibly add values
+        let (fields, items) = args::form_inputs(&call.values, &call.items);
+        for (key, arg) in fields {
+            let mut value = arg.render(&self.token)?;
+            if arg.is_choice() {
+                log::info!("[{}] opening dropdown list '{}'", log_id, key);
+                let path =
+                    format!("//label/*[contains(text(),'{}')]/ancestor::div[1]", key);
+                self.click(Locator::XPath(&path)).await?;
+
+                log::info!("[{}] choosing option '{}''", log_id, value);
+                let path = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]//*/div[@role = 'option']/span[text() = '{}']", key, value);
+                self.click(Locator::XPath(&path)).await?;
+            } else {
+                log::info!("[{}] entering {:?} into {:?}", log_id, &value, &key);
+                let path = format!(
+                    "//*[contains(text(),'{}')]/ancestor::div[1]/div//input[@type = 'text']",
+                    key
+                );
+                value.push('\n');
+                self.fill(Locator::XPath(&path), &value).await?
+            }
+        }
+
+        // possibly add items
+        for (key, value) in items.iter() {
+            let value = value.render_item()?;
+            log::info!("[{}] adding item '{}' for '{}'", log_id, value, key);
+            let add_item = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]/ancestor::div[1]/*/button[contains(text(), 'Add item')]", key);
+            self.click(Locator::XPath(&add_item)).await?;
+
+            let last_item = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]/ancestor::div[1]/*/div[@class = 'ui--Params-Content']/div[last()]//input", key);
+            // we need to clear a possible default input from the field
+            self.fill(Locator::XPath(&last_item), &value).await?;
+        }
+
+        // click call
+        log::info!("[{}] click rpc call", log_id);
+        let call_button = Locator::XPath("//button[contains(text(),'Call')]");
+        self.click(call_button).await?;
+        let outcome = Locator::XPath("//div[@class = 'outcomes']/*[1]//div[@class = 'ui--output monospace']/div[1]");
+        let client = &self.client;
+        let mut last_click = Instant::now();
+        let txt = wait::wait_until(
+            "rpc call outcome",
+            call.outcome_timeout(),
+            wait::poll_interval(),
+            || {
+                let click_again = last_click.elapsed() > CALL_AGAIN_AFTER;
+                if click_again {
+                    last_click = Instant::now();
+                }
+                async move {
+                    if let Ok(el) = client.find(outcome).await {
+                        return Ok(Some(el.text().await?))
+                    }
+                    if click_again {
+                        log::info!("[{}] click rpc call again", log_id);
+                        client.find(call_button).await?.click().await?;
+                    }
+                    Ok(None)
+                }
+            },
+        )
+        .await?;
+        log::info!("[{}] found rpc call outcome text {}", log_id, txt);
+
+        let metadata = self.contracts.get(&call.contract_address)?;
+        let value = decode::parse_outcome(
+            metadata,
+            metadata.message(&call.method)?,
+            &txt,
+            &self.token,
+        )?;
+        log::info!("[{}] outcome value is {:?}", log_id, value);
+        Ok(value)
     }
 
-    /// Uploads the contract behind `contract_path`.
+    /// Executes the transaction `call`.
     ///
     /// # Developer Note
     ///
     /// This method must not make any assumptions about the state of the Ui before
     /// the method is invoked. It must e.g. open the upload page right at the start.
-    async fn execute_upload(&mut self, upload_input: Upload) -> Result<String> {
-        let log_id = format!("{}", test_name());
+    async fn execute_transaction(&mut self, call: Call) -> TransactionResult<Events> {
+        self.retry
+            .run_if(
+                &format!("transaction {:?}", call.method),
+                retry::is_retryable_in_browser,
+                || self.transaction(&call),
+            )
+            .await
+    }
+
+    /// Updating the metadata of an existing contract is not supported by the `canvas-ui`.
+    async fn update_metadata(
+        &mut self,
+        contract_addr: &str,
+        new_abi: &Path,
+    ) -> Result<String> {
+        Err(format!(
+            "updating the metadata of {} to {:?} is not supported by the canvas-ui",
+            contract_addr, new_abi
+        )
+        .into())
+    }
+
+    /// Closes the browser session.
+    async fn shutdown(&mut self) -> Result<()> {
+        self.0.shutdown().await
+    }
+
+    async fn save_failure_artifacts(&mut self, dir: &Path) -> Result<()> {
+        self.0.save_failure_artifacts(dir).await
+    }
+}
+
+impl CanvasUi {
+    /// Uploads the contract of `upload_input` once, without retrying, and
+    /// returns its address.
+    async fn upload(&self, upload_input: &Upload) -> TransactionResult<String> {
+        let log_id = test_name().to_string();
         log::info!(
             "[{}] opening url for upload of {}: {:?}",
             log_id,
@@ -98,21 +334,18 @@ impl ContractsUi for crate::uis::Ui {
         self.client.goto(&url("upload")).await?;
 
         log::info!("[{}] waiting for settings to become visible", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//*[contains(text(),'Local Node')]"))
+        self.wait_for_element(Locator::XPath("//*[contains(text(),'Local Node')]"))
             .await?;
 
-        // We should get rid of this `sleep`. The problem is that the "Skip Intro" button
-        // sometimes appears after a bit of time and sometimes it doesn't (if it was already
-        // clicked away during the session).
-        std::thread::sleep(std::time::Duration::from_secs(2));
-
+        // The "Skip Intro" button sometimes appears after a bit of time and sometimes
+        // it doesn't (if it was already clicked away during the session).
         log::info!("[{}] click skip intro button, if it is available", log_id);
-        if let Ok(skip_button) = self
-            .client
-            .find(Locator::XPath("//button[contains(text(),'Skip Intro')]"))
-            .await
+        if let Some(skip_button) = self
+            .wait_for_optional_element(
+                Locator::XPath("//button[contains(text(),'Skip Intro')]"),
+                INTRO_TIMEOUT,
+            )
+            .await?
         {
             log::info!("[{}] found skip button", log_id);
             skip_button.click().await?;
@@ -126,13 +359,10 @@ impl ContractsUi for crate::uis::Ui {
         }
 
         log::info!("[{}] click upload", log_id);
-        self.client
-            .find(Locator::XPath(
-                "//*[contains(text(),'Upload & Instantiate Contract')]",
-            ))
-            .await?
-            .click()
-            .await?;
+        self.click(Locator::XPath(
+            "//*[contains(text(),'Upload & Instantiate Contract')]",
+        ))
+        .await?;
 
         log::info!("[{}] injecting jquery", log_id);
         // The `inject` script will retry to load jQuery every 10 seconds.
@@ -164,13 +394,10 @@ impl ContractsUi for crate::uis::Ui {
                     }\
                 })();",
         );
-        self.client.execute(&*inject, Vec::new()).await?;
+        self.client.execute(&inject, Vec::new()).await?;
 
         log::info!("[{}] waiting for jquery", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::Css("#jquery-ready"))
-            .await?;
+        self.wait_for_element(Locator::Css("#jquery-ready")).await?;
 
         log::info!("[{}] click combobox", log_id);
         self.client
@@ -183,50 +410,39 @@ impl ContractsUi for crate::uis::Ui {
             .await?;
 
         log::info!("[{}] set input {:?}", log_id, upload_input.contract_path);
-        let mut upload = self
-            .client
-            .find(Locator::Css(".ui--InputFile input"))
-            .await?;
-        upload
-            .send_keys(&upload_input.contract_path.display().to_string())
-            .await?;
+        self.send_keys(
+            Locator::Css(".ui--InputFile input"),
+            &upload_input.contract_path.display().to_string(),
+        )
+        .await?;
         self.client
             .execute("$(\".ui--InputFile input\").trigger('change')", Vec::new())
             .await?;
 
         log::info!("[{}] click details", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                "//*[contains(text(),'Constructor Details')]",
-            ))
-            .await?
-            .click()
-            .await?;
+        self.click(Locator::XPath(
+            "//*[contains(text(),'Constructor Details')]",
+        ))
+        .await?;
 
         if let Some(caller) = &upload_input.caller {
             // open listbox for accounts
             log::info!("[{}] click listbox for accounts", log_id);
-            self.client
-                .wait()
-                .for_element(Locator::XPath(
-                    "//*[contains(text(),'instantiation account')]/ancestor::div[1]/div",
-                ))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(
+                "//*[contains(text(),'instantiation account')]/ancestor::div[1]/div",
+            ))
+            .await?;
 
             // choose caller
             log::info!("[{}] choose {:?}", log_id, caller);
             let path = format!("//div[@name = '{}']", caller.to_lowercase());
-            self.client
-                .find(Locator::XPath(&path))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(&path)).await?;
         }
 
-        for (key, value) in upload_input.initial_values.iter() {
+        let (fields, items) =
+            args::form_inputs(&upload_input.initial_values, &upload_input.items);
+        for (key, value) in fields.iter() {
+            let value = value.render(&self.token)?;
             log::info!(
                 "[{}] inserting '{}' into input field '{}'",
                 log_id,
@@ -237,47 +453,33 @@ impl ContractsUi for crate::uis::Ui {
                 "//label/*[contains(text(),'{}')]/ancestor::div[1]//*/input",
                 key
             );
-            let mut input = self.client.find(Locator::XPath(&path)).await?;
             // we need to clear a possible default input from the field
-            input.clear().await?;
-            input.send_keys(&value).await?;
+            self.fill(Locator::XPath(&path), &value).await?;
         }
 
-        for (key, value) in upload_input.items.iter() {
+        for (key, value) in items.iter() {
+            let value = value.render_item()?;
             log::info!("[{}] adding item '{}' for '{}'", log_id, value, key);
             let add_item = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]/ancestor::div[1]/*/button[contains(text(), 'Add item')]", key);
-            self.client
-                .find(Locator::XPath(&add_item))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(&add_item)).await?;
 
             let last_item = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]/ancestor::div[1]/*/div[@class = 'ui--Params-Content']/div[last()]//input", key);
-            let mut input = self.client.find(Locator::XPath(&last_item)).await?;
             // we need to clear a possible default input from the field
-            input.clear().await?;
-            input.send_keys(&value).await?;
+            self.fill(Locator::XPath(&last_item), &value).await?;
         }
 
         if let Some(ref constructor) = upload_input.constructor {
             log::info!("[{}] click constructor list box", log_id);
-            self.client
-                .wait().for_element(Locator::XPath(
+            self.click(Locator::XPath(
                     "//label/*[contains(text(),'Instantiation Constructor')]/ancestor::div[1]//*/div[@role='listbox']"
-                ))
-                .await?.click().await?;
+                )).await?;
 
             log::info!("[{}] click constructor option {}", log_id, constructor);
             let path = format!(
                 "//span[@class = 'ui--MessageSignature-name' and contains(text(),'{}')]",
                 constructor
             );
-            self.client
-                .wait()
-                .for_element(Locator::XPath(&path))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(&path)).await?;
         }
 
         // the react toggle button cannot be clicked if it is not in view
@@ -287,29 +489,17 @@ impl ContractsUi for crate::uis::Ui {
                 Vec::new(),
             )
             .await?;
-        std::thread::sleep(std::time::Duration::from_secs(3));
 
         log::info!("[{}] check 'Unique Instantiation Salt' checkbox", log_id);
         let path = "//*[contains(text(),'Unique Instantiation Salt')]/ancestor::div[1]//div[contains(@class,'ui--Toggle')]/div";
-        self.client
-            .find(Locator::XPath(path))
-            .await?
-            .click()
-            .await?;
+        self.click(Locator::XPath(path)).await?;
 
         log::info!("[{}] click instantiate", log_id);
-        self.client
-            .find(Locator::XPath("//button[contains(text(),'Instantiate')]"))
-            .await?
-            .click()
+        self.click(Locator::XPath("//button[contains(text(),'Instantiate')]"))
             .await?;
 
         log::info!("[{}] click sign and submit", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//button[contains(text(),'Sign & Submit')]"))
-            .await?
-            .click()
+        self.click(Locator::XPath("//button[contains(text(),'Sign & Submit')]"))
             .await?;
 
         log::info!(
@@ -317,48 +507,8 @@ impl ContractsUi for crate::uis::Ui {
             log_id
         );
 
-        let mut res;
-        for retry in 0..21 {
-            std::thread::sleep(std::time::Duration::from_secs(3));
-            res = self.client.find(
-                Locator::XPath("//*[contains(text(),'Dismiss') or contains(text(),'usurped') or contains(text(),'Priority is too low')]")
-            ).await;
-            if res.is_ok() {
-                log::info!("[{}] upload: success on try {}", log_id, retry,);
-                break
-            } else {
-                log::info!(
-                    "[{}] upload: try {} - waiting for either success or failure notification",
-                    log_id,
-                    retry,
-                );
-
-                let statuses = self
-                    .client
-                    .find_all(Locator::XPath(
-                        "//div[contains(@class, 'ui--Status')]//div[@class = 'desc' or @class = 'header']",
-                    ))
-                    .await?;
-                log::info!(
-                    "[{}] upload retry: found {} status messages",
-                    log_id,
-                    statuses.len(),
-                );
-                for mut el in statuses {
-                    log::info!("[{}] upload retry, text: {:?}", log_id, el.text().await?);
-                }
-
-                if retry == 20 {
-                    log::info!(
-                        "[{}] timed out on waiting for upload! next recursion.",
-                        log_id,
-                    );
-                    return self.execute_upload(upload_input.clone()).await
-                } else {
-                    log::info!("[{}] timed out on waiting for upload! sleeping.", log_id,);
-                }
-            }
-        }
+        self.wait_for_status(OUTCOME, upload_input.outcome_timeout())
+            .await?;
 
         // extract all status messages
         let statuses = self
@@ -369,7 +519,7 @@ impl ContractsUi for crate::uis::Ui {
             .await?;
         log::info!("[{}] found {} status messages", log_id, statuses.len(),);
         let mut statuses_processed = Vec::new();
-        for mut el in statuses {
+        for el in statuses {
             log::info!("[{}] text {:?}", log_id, el.text().await?);
             let header = el
                 .find(Locator::XPath("div[@class = 'header']"))
@@ -387,47 +537,21 @@ impl ContractsUi for crate::uis::Ui {
                 header,
                 status,
             );
-            statuses_processed.push(Event { header, status });
-        }
-        let events = Events::new(statuses_processed);
-
-        if events.contains("Priority is too low") {
-            log::info!(
-                "[{}] found priority too low during upload! trying again!",
-                log_id
-            );
-            return self.execute_upload(upload_input.clone()).await
-        } else if events.contains("usurped") {
-            log::info!("[{}] found usurped for upload! trying again!", log_id);
-            return self.execute_upload(upload_input.clone()).await
-        } else {
-            log::info!(
-                "[{}] did not find priority too low in {} status messages",
-                log_id,
-                events.events.len()
-            );
+            statuses_processed.push((header, status));
         }
-        assert!(
-            events.contains("system.ExtrinsicSuccess"),
-            "uploading contract must succeed"
-        );
+        let events = Events::from_messages(statuses_processed);
+        log::info!("[{}] found the events {}", log_id, events);
+        events.into_outcome()?;
 
         log::info!("[{}] dismiss notifications", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//*[contains(text(),'Dismiss')]"))
-            .await?
-            .click()
+        self.click(Locator::XPath("//*[contains(text(),'Dismiss')]"))
             .await?;
 
         log::info!("[{}] click execute", log_id);
-        self.client
-            .find(Locator::XPath(
-                "//button[contains(text(),'Execute Contract')]",
-            ))
-            .await?
-            .click()
-            .await?;
+        self.click(Locator::XPath(
+            "//button[contains(text(),'Execute Contract')]",
+        ))
+        .await?;
 
         let re = Regex::new("/execute/([0-9a-zA-Z]+)/0").expect("invalid regex");
         let client_url = self.client.current_url().await?;
@@ -441,220 +565,8 @@ impl ContractsUi for crate::uis::Ui {
         Ok(String::from(addr))
     }
 
-    /// Executes the RPC call `call`.
-    ///
-    /// # Developer Note
-    ///
-    /// This method must not make any assumptions about the state of the Ui before
-    /// the method is invoked. It must e.g. open the upload page right at the start.
-    async fn execute_rpc(&mut self, call: Call) -> Result<String> {
-        let log_id = format!("{} {}", test_name(), call.method.clone());
-
-        let url = format!("{}{}/0", url("execute/"), call.contract_address);
-        log::info!(
-            "[{}] opening url for rpc {:?}: {:?}",
-            log_id,
-            call.method,
-            url
-        );
-        self.client.goto(url.as_str()).await?;
-
-        // hack to get around a failure of the ui for the multisig tests.
-        // the ui fails displaying the flipper contract execution page, but
-        // it strangely works if tried again after some time.
-        log::info!("[{}] sleep for {}", log_id, url);
-        std::thread::sleep(std::time::Duration::from_secs(2));
-
-        self.client.refresh().await?;
-        self.client.goto(url.as_str()).await?;
-
-        // open listbox for methods
-        log::info!("[{}] click listbox", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                "//*[contains(text(),'Message to Send')]/ancestor::div[1]/div",
-            ))
-            .await?
-            .click()
-            .await?;
-
-        // click `method`
-        log::info!("[{}] choose {:?}", log_id, call.method);
-        let path = format!("//*[contains(text(),'Message to Send')]/ancestor::div[1]/div//*[text() = '{}']", call.method);
-        self.client
-            .find(Locator::XPath(&path))
-            .await?
-            .click()
-            .await?;
-
-        // Open listbox
-        log::info!("[{}] open listbox for rpc vs. transaction", log_id);
-        let path = "//*[contains(text(),'Send as RPC call')]/ancestor::div[1]/ancestor::div[1]/ancestor::div[1]";
-        self.client
-            .find(Locator::XPath(path))
-            .await?
-            .click()
-            .await?;
-
-        // Send as RPC call
-        log::info!("[{}] select 'Send as RPC call'", log_id);
-        let path = "//*[contains(text(),'Send as RPC call')]/ancestor::div[1]";
-        self.client
-            .find(Locator::XPath(path))
-            .await?
-            .click()
-            .await?;
-
-        // possibly set max gas
-        if let Some(max_gas) = call.max_gas_allowed {
-            // click checkbox
-            log::info!(
-                "[{}] unset 'use estimated gas' checkbox if it exists",
-                log_id
-            );
-            let path = "//*[contains(text(),'use estimated gas')]/ancestor::div[1]/div";
-            let checkbox = self.client.find(Locator::XPath(path)).await;
-
-            if let Ok(checkbox) = checkbox {
-                log::info!(
-                    "[{}] unsetting 'use estimated gas' checkbox - it exists",
-                    log_id
-                );
-                checkbox.click().await?;
-            }
-
-            log::info!("[{}] entering max gas {:?}", log_id, max_gas);
-            let path = "//*[contains(text(),'Max Gas Allowed')]/ancestor::div[1]/div//input[@type = 'text']";
-            self.client
-                .find(Locator::XPath(path))
-                .await?
-                .clear()
-                .await?;
-            self.client
-                .find(Locator::XPath(path))
-                .await?
-                .send_keys(&max_gas)
-                .await?;
-        }
-
-        // possibly add values
-        for (key, value) in call.values {
-            // if the value is `Yes` or `No` we assume it's a listbox with a boolean
-            let mut value = transform_value(&value);
-            if value == "Yes" || value == "No" {
-                log::info!("[{}] opening dropdown list '{}'", log_id, key);
-                let path =
-                    format!("//label/*[contains(text(),'{}')]/ancestor::div[1]", key);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
-
-                log::info!("[{}] choosing option '{}''", log_id, value);
-                let path = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]//*/div[@role = 'option']/span[text() = '{}']", key, value);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
-            } else {
-                log::info!("[{}] entering {:?} into {:?}", log_id, &value, &key);
-                let path = format!(
-                    "//*[contains(text(),'{}')]/ancestor::div[1]/div//input[@type = 'text']",
-                    key
-                );
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .clear()
-                    .await?;
-                value.push('\n');
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .send_keys(&value)
-                    .await?
-            }
-        }
-
-        // possibly add items
-        for (key, value) in call.items.iter() {
-            log::info!("[{}] adding item '{}' for '{}'", log_id, value, key);
-            let add_item = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]/ancestor::div[1]/*/button[contains(text(), 'Add item')]", key);
-            self.client
-                .find(Locator::XPath(&add_item))
-                .await?
-                .click()
-                .await?;
-
-            let last_item = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]/ancestor::div[1]/*/div[@class = 'ui--Params-Content']/div[last()]//input", key);
-            let mut input = self.client.find(Locator::XPath(&last_item)).await?;
-            // we need to clear a possible default input from the field
-            input.clear().await?;
-            input.send_keys(&value).await?;
-        }
-
-        // click call
-        let mut txt = None;
-        log::info!("[{}] click rpc call", log_id);
-        self.client
-            .find(Locator::XPath("//button[contains(text(),'Call')]"))
-            .await?
-            .click()
-            .await?;
-        for waited in 0..21 {
-            log::info!("[{}] waiting for rpc call outcome {}", log_id, waited);
-            std::thread::sleep(std::time::Duration::from_secs(3));
-            let el = self.client.find(Locator::XPath("//div[@class = 'outcomes']/*[1]//div[@class = 'ui--output monospace']/div[1]")).await;
-            if let Ok(mut el) = el {
-                log::info!("[{}] found rpc call outcome", log_id);
-                txt = Some(el.text().await?);
-                log::info!(
-                    "[{}] found rpc call outcome text {}",
-                    log_id,
-                    txt.clone().expect("txt exists here")
-                );
-                break
-            }
-
-            if waited % 5 == 0 {
-                // if txt.is_none() {
-                // let html = self.client.find(Locator::XPath("//div[@class = 'outcomes']")).await?.html(true).await?;
-                // if html.contains("Error: OutOfGas") {
-                // panic!("[{}] An `OutOfGas` error occurred for this RPC", log_id);
-                // }
-                // }
-                log::info!("[{}] click rpc call again in {}", log_id, waited);
-                self.client
-                    .find(Locator::XPath("//button[contains(text(),'Call')]"))
-                    .await?
-                    .click()
-                    .await?;
-            }
-        }
-        let mut txt = txt.expect("[{}] no outcome txt found after retrying!");
-
-        // wait for outcomes
-        log::info!("outcomes value {:?}", txt);
-        if txt == "0x000000…00000000" {
-            txt = String::from("<empty>");
-        }
-        txt = txt
-            .trim_start_matches("Some(\n")
-            .trim_end_matches("\n)")
-            .to_string();
-        Ok(txt)
-    }
-
-    /// Executes the transaction `call`.
-    ///
-    /// # Developer Note
-    ///
-    /// This method must not make any assumptions about the state of the Ui before
-    /// the method is invoked. It must e.g. open the upload page right at the start.
-    async fn execute_transaction(&mut self, call: Call) -> TransactionResult<Events> {
+    /// Executes the transaction `call` once, without retrying.
+    async fn transaction(&self, call: &Call) -> TransactionResult<Events> {
         let log_id = format!("{} {}", test_name(), call.method.clone());
         let url = url(&format!("execute/{}/0", call.contract_address));
         log::info!(
@@ -668,99 +580,61 @@ impl ContractsUi for crate::uis::Ui {
 
         // open listbox for methods
         log::info!("[{}] click listbox", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                "//*[contains(text(),'Message to Send')]/ancestor::div[1]/div",
-            ))
-            .await?
-            .click()
-            .await?;
+        self.click(Locator::XPath(
+            "//*[contains(text(),'Message to Send')]/ancestor::div[1]/div",
+        ))
+        .await?;
 
         // click `method`
         log::info!("[{}] choose {:?}", log_id, call.method);
         let path = format!("//*[contains(text(),'Message to Send')]/ancestor::div[1]/div//*[text() = '{}']", call.method);
-        self.client
-            .find(Locator::XPath(&path))
-            .await?
-            .click()
-            .await?;
+        self.click(Locator::XPath(&path)).await?;
 
         // Open listbox
         log::info!("[{}] open listbox for rpc vs. transaction", log_id);
         let path = "//*[contains(text(),'Send as transaction')]/ancestor::div[1]/ancestor::div[1]/ancestor::div[1]";
-        self.client
-            .find(Locator::XPath(path))
-            .await?
-            .click()
-            .await?;
+        self.click(Locator::XPath(path)).await?;
 
         // Send as transaction
         log::info!("[{}] select 'Send as transaction'", log_id);
         let path = "//*[contains(text(),'Send as transaction')]/ancestor::div[1]";
-        self.client
-            .find(Locator::XPath(path))
-            .await?
-            .click()
-            .await?;
+        self.click(Locator::XPath(path)).await?;
 
         if let Some(caller) = &call.caller {
             // open listbox for accounts
             log::info!("[{}] click listbox for accounts", log_id);
-            self.client
-                .wait()
-                .for_element(Locator::XPath(
-                    "//*[contains(text(),'Call from Account')]/ancestor::div[1]/div",
-                ))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(
+                "//*[contains(text(),'Call from Account')]/ancestor::div[1]/div",
+            ))
+            .await?;
 
             // choose caller
             log::info!("[{}] choose {:?}", log_id, caller);
             let path = format!("//*[contains(text(),'Call from Account')]/ancestor::div[1]//div[@name = '{}']", caller.to_lowercase());
-            self.client
-                .find(Locator::XPath(&path))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(&path)).await?;
         }
 
         // Possibly add payment
         if let Some(payment) = &call.payment {
+            // The payment is entered in units of the token, without SI prefix
+            let token = self.token.clone();
+            let payment = payment.in_units(token.decimals);
+
             // Open listbox
             log::info!("[{}] open listbox for payment units", log_id);
-            let path = format!("//*[contains(text(),'{}')]/ancestor::div[1]/ancestor::div[1]/ancestor::div[1]", payment.unit);
-            self.client
-                .find(Locator::XPath(&path))
-                .await?
-                .click()
-                .await?;
+            let path = format!("//*[contains(text(),'{}')]/ancestor::div[1]/ancestor::div[1]/ancestor::div[1]", token.symbol);
+            self.click(Locator::XPath(&path)).await?;
 
-            log::info!("[{}] click payment unit option {}", log_id, payment.unit);
+            log::info!("[{}] click payment unit option {}", log_id, token.symbol);
             let path = format!(
                 "//div[@role='option']/span[contains(text(),'{}')]/ancestor::div[1]",
-                payment.unit
+                token.symbol
             );
-            self.client
-                .wait()
-                .for_element(Locator::XPath(&path))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(&path)).await?;
 
-            log::info!("[{}] entering payment {:?}", log_id, payment.payment);
+            log::info!("[{}] entering payment {:?}", log_id, payment);
             let path = "//*[contains(text(),'Payment')]/ancestor::div[1]/div//input[@type = 'text']";
-            self.client
-                .find(Locator::XPath(path))
-                .await?
-                .clear()
-                .await?;
-            self.client
-                .find(Locator::XPath(path))
-                .await?
-                .send_keys(&payment.payment)
-                .await?;
+            self.fill(Locator::XPath(path), &payment).await?;
         }
 
         // possibly set max gas
@@ -768,160 +642,65 @@ impl ContractsUi for crate::uis::Ui {
             // click checkbox
             log::info!("[{}] unset 'use estimated gas' checkbox", log_id);
             let path = "//*[contains(text(),'use estimated gas')]/ancestor::div[1]/div";
-            self.client
-                .find(Locator::XPath(path))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(path)).await?;
 
             log::info!("[{}] entering max gas {:?}", log_id, max_gas);
             let path = "//*[contains(text(),'Max Gas Allowed')]/ancestor::div[1]/div//input[@type = 'text']";
-            self.client
-                .find(Locator::XPath(path))
-                .await?
-                .clear()
-                .await?;
-            self.client
-                .find(Locator::XPath(path))
-                .await?
-                .send_keys(&max_gas)
-                .await?;
+            self.fill(Locator::XPath(path), max_gas).await?;
         }
 
         // possibly add values
-        for (key, value) in &call.values {
-            // if the value is `Yes` or `No` we assume it's a listbox with a boolean
-            let mut value = transform_value(&value);
-            if value == "Yes" || value == "No" {
+        let (fields, items) = args::form_inputs(&call.values, &call.items);
+        for (key, arg) in &fields {
+            let mut value = arg.render(&self.token)?;
+            if arg.is_choice() {
                 log::info!("[{}] opening dropdown list '{}'", log_id, key);
                 let path =
                     format!("//label/*[contains(text(),'{}')]/ancestor::div[1]", key);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                self.click(Locator::XPath(&path)).await?;
 
                 log::info!("[{}] choosing option '{}''", log_id, value);
                 let path = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]//*/div[@role = 'option']/span[text() = '{}']", key, value);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                self.click(Locator::XPath(&path)).await?;
             } else {
                 log::info!("[{}] entering {:?} into {:?}", log_id, &value, &key);
                 let path = format!(
                     "//*[contains(text(),'Message to Send')]/ancestor::div[1]/following-sibling::div[1]//*[contains(text(),'{}')]/ancestor::div[1]/div//input[@type = 'text']",
                     key
                 );
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .clear()
-                    .await?;
                 value.push('\n');
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .send_keys(&value)
-                    .await?;
+                self.fill(Locator::XPath(&path), &value).await?;
             }
         }
 
         // possibly add items
-        for (key, value) in call.items.iter() {
+        for (key, value) in items.iter() {
+            let value = value.render_item()?;
             log::info!("[{}] adding item '{}' for '{}'", log_id, value, key);
             let add_item = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]/ancestor::div[1]/*/button[contains(text(), 'Add item')]", key);
-            self.client
-                .find(Locator::XPath(&add_item))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(&add_item)).await?;
 
             let last_item = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]/ancestor::div[1]/*/div[@class = 'ui--Params-Content']/div[last()]//input", key);
-            let mut input = self.client.find(Locator::XPath(&last_item)).await?;
             // we need to clear a possible default input from the field
-            input.clear().await?;
-            input.send_keys(&value).await?;
+            self.fill(Locator::XPath(&last_item), &value).await?;
         }
 
         // click call
         log::info!("[{}] transaction click call", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//button[contains(text(),'Call')]"))
-            .await?
-            .click()
+        self.click(Locator::XPath("//button[contains(text(),'Call')]"))
             .await?;
 
         // click sign and submit
         log::info!("[{}] sign and submit", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//button[contains(text(),'Sign & Submit')]"))
-            .await?
-            .click()
+        self.click(Locator::XPath("//button[contains(text(),'Sign & Submit')]"))
             .await?;
 
         log::info!(
             "[{}] transaction: waiting for either success or failure notification",
             log_id
         );
-        let mut res;
-        for retry in 0..21 {
-            std::thread::sleep(std::time::Duration::from_secs(3));
-            res = self.client.find(
-                Locator::XPath("//*[contains(text(),'Dismiss') or contains(text(),'usurped') or contains(text(),'Priority is too low')]")
-            ).await;
-            if res.is_ok() {
-                log::info!(
-                    "[{}] transaction: success on try {} for {:?}",
-                    log_id,
-                    retry,
-                    call.method
-                );
-                break
-            } else {
-                log::info!(
-                    "[{}] transaction: try {} - waiting for either success or failure notification {:?}",
-                    log_id,
-                    retry,
-                    call.method
-                );
-
-                let statuses = self
-                    .client
-                    .find_all(Locator::XPath(
-                        "//div[contains(@class, 'ui--Status')]//div[@class = 'desc' or @class = 'header']",
-                    ))
-                    .await?;
-                log::info!(
-                    "[{}] transaction retry: found {:?} status messages for {:?}",
-                    log_id,
-                    statuses.len(),
-                    call.method
-                );
-                for mut el in statuses {
-                    log::info!("transaction retry, text: {:?}", el.text().await?);
-                }
-
-                if retry == 20 {
-                    log::info!(
-                        "[{}] timed out on waiting for {:?} transaction! next recursion.",
-                        log_id,
-                        call.method
-                    );
-                    return self.execute_transaction(call.clone()).await
-                } else {
-                    log::info!(
-                        "[{}] timed out on waiting for {:?} transaction! sleeping.",
-                        log_id,
-                        call.method
-                    );
-                }
-            }
-        }
+        self.wait_for_status(OUTCOME, call.outcome_timeout())
+            .await?;
 
         // extract all status messages
         let statuses = self
@@ -932,7 +711,7 @@ impl ContractsUi for crate::uis::Ui {
             .await?;
         log::info!("[{}] found {:?} status messages", log_id, statuses.len());
         let mut statuses_processed = Vec::new();
-        for mut el in statuses {
+        for el in statuses {
             let header = el
                 .find(Locator::XPath("div[@class = 'header']"))
                 .await?
@@ -949,46 +728,12 @@ impl ContractsUi for crate::uis::Ui {
                 header,
                 status
             );
-            statuses_processed.push(Event { header, status });
-        }
-        let events = Events::new(statuses_processed);
-
-        if events.contains("Priority is too low") {
-            log::info!(
-                "[{}] found priority too low during transaction execution of {:?}! trying again!",
-                log_id,
-                call.method
-            );
-            return self.execute_transaction(call.clone()).await
-        } else if events.contains("usurped") {
-            log::info!(
-                "[{}] found usurped for transaction {:?}! trying again!",
-                log_id,
-                call.method
-            );
-            {
-                let mut rng = rand::thread_rng();
-                let rand = rng.gen_range(0..30_000);
-                log::info!("[{}] sleeping for rand {:?} after usurped", log_id, rand);
-                std::thread::sleep(std::time::Duration::from_millis(rand));
-            }
-            return self.execute_transaction(call.clone()).await
-        } else {
-            log::info!(
-                "[{}] did not find priority too low in {:?} status messages",
-                log_id,
-                events.events.len()
-            );
+            statuses_processed.push((header, status));
         }
+        let events = Events::from_messages(statuses_processed);
+        log::info!("[{}] found the events {}", log_id, events);
 
-        let success = events.contains("system.ExtrinsicSuccess");
-        let failure = events.contains("system.ExtrinsicFailed");
-        match (success, failure) {
-            (true, false) => TransactionResult::Ok(events),
-            (false, true) => TransactionResult::Err(TransactionError::ExtrinsicFailed(events)),
-            (false, false) => panic!("ERROR: Neither 'ExtrinsicSuccess' nor 'ExtrinsicFailed' was found in status messages!"),
-            (true, true) => panic!("ERROR: Both 'ExtrinsicSuccess' nor 'ExtrinsicFailed' was found in status messages!"),
-        }
+        events.into_outcome()
     }
 }
 
@@ -1016,11 +761,3 @@ fn base_url() -> String {
 fn url(path: &str) -> String {
     format!("{}{}", base_url(), path)
 }
-
-fn transform_value(value: &str) -> String {
-    match value {
-        "true" => String::from("Yes"),
-        "false" => String::from("No"),
-        _ => value.to_string(),
-    }
-}
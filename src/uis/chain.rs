@@ -0,0 +1,729 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Queries of the chain state, independent of the backend which is used to
+//! interact with the contracts.
+//!
+//! The UIs only display rounded balances, so balances are always read from the
+//! `System::Account` storage of the node.
+
+use crate::{
+    uis::{
+        decode,
+        keyring::{
+            resolve_account,
+            Keypair,
+        },
+        metadata::Contracts,
+        rpc::{
+            self,
+            DispatchOutcome,
+        },
+        AccountBalance,
+        ArgValue,
+        Balance,
+        BalanceDelta,
+        Call,
+        ContractEvent,
+        Events,
+        ModuleError,
+        Result,
+        Token,
+        Upload,
+    },
+    utils,
+};
+use codec::Decode;
+use convert_case::{
+    Case,
+    Casing,
+};
+use lazy_static::lazy_static;
+use std::ops::RangeInclusive;
+use subxt::{
+    config::substrate::H256,
+    error::DispatchError,
+    events::{
+        EventDetails,
+        Phase,
+    },
+    ext::scale_value::{
+        self,
+        Composite,
+        Value,
+        ValueDef,
+    },
+    utils::AccountId32,
+    Metadata,
+    OnlineClient,
+    PolkadotConfig,
+};
+
+lazy_static! {
+    /// Serializes the transfers from the faucet of all tests, so that they
+    /// do not use the same nonce.
+    static ref FAUCET: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+/// The outcome of dry-running a contract call.
+pub(crate) struct DryRun {
+    /// The error with which the contract reverted, if it did.
+    ///
+    /// For a message which returns a `Result` this is the decoded `Err`, for
+    /// other messages the whole decoded output.
+    pub(crate) revert: Option<ArgValue>,
+    /// The debug output which the contract printed during the call, e.g. via
+    /// `ink::env::debug_println!`.
+    pub(crate) debug_message: String,
+    /// The gas (`ref_time`) which the call requires.
+    pub(crate) gas_required: u64,
+}
+
+/// An extrinsic, by the block which includes it and its index within the block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ExtrinsicId {
+    /// The number of the block.
+    pub(crate) block: u32,
+    /// The index of the extrinsic within the block.
+    pub(crate) index: u32,
+}
+
+/// Opens a connection to the node at [`utils::node_port`].
+pub(crate) async fn connect() -> Result<OnlineClient<PolkadotConfig>> {
+    utils::assert_node_running();
+
+    let url = format!("ws://127.0.0.1:{}", utils::node_port());
+    log::info!("connecting to node at {}", url);
+    Ok(OnlineClient::<PolkadotConfig>::from_url(&url).await?)
+}
+
+/// Returns the token of the chain, as announced in the `system_properties`.
+async fn token(client: &OnlineClient<PolkadotConfig>) -> Result<Token> {
+    let properties = client.rpc().system_properties().await?;
+    let default = Token::default();
+    let decimals = match properties.get("tokenDecimals") {
+        Some(serde_json::Value::Number(decimals)) => decimals.as_u64(),
+        Some(serde_json::Value::Array(decimals)) => {
+            decimals.first().and_then(|decimals| decimals.as_u64())
+        }
+        _ => None,
+    }
+    .map_or(default.decimals, |decimals| decimals as u32);
+    let symbol = match properties.get("tokenSymbol") {
+        Some(serde_json::Value::String(symbol)) => Some(symbol.as_str()),
+        Some(serde_json::Value::Array(symbols)) => {
+            symbols.first().and_then(|symbol| symbol.as_str())
+        }
+        _ => None,
+    }
+    .unwrap_or(&default.symbol);
+    Ok(Token::new(decimals, symbol))
+}
+
+/// The balance of an account at a certain block, taken before interacting with
+/// a contract. See [`crate::uis::Ui::assert_balance_delta`].
+#[derive(Clone, Debug)]
+pub struct BalanceSnapshot {
+    /// The account whose balance was taken.
+    account: AccountId32,
+    /// The number of the block at which the balance was taken.
+    block_number: u32,
+    /// The balance of the account at that block.
+    balance: AccountBalance,
+}
+
+/// A connection to the node which is used to query the chain state.
+pub(crate) struct Chain {
+    client: OnlineClient<PolkadotConfig>,
+    /// The chain's token, which the UIs display balances in.
+    token: Token,
+}
+
+impl Chain {
+    /// Creates a new `Chain` instance which is connected to the node.
+    pub(crate) async fn connect() -> Result<Self> {
+        let client = connect().await?;
+        let token = token(&client).await?;
+        log::info!("chain token is {:?}", token);
+        Token::set_chain(&token);
+        Ok(Self { client, token })
+    }
+
+    /// Returns the chain's token.
+    pub(crate) fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// Returns the current balance of `account`, which is either the name of an
+    /// account in the keyring (e.g. `ALICE`) or an SS58 address.
+    pub(crate) async fn balance(&self, account: &str) -> Result<AccountBalance> {
+        Ok(self.snapshot(account).await?.balance)
+    }
+
+    /// Takes a snapshot of the current balance of `account`.
+    pub(crate) async fn snapshot(&self, account: &str) -> Result<BalanceSnapshot> {
+        let account = resolve_account(account)?;
+        let (block_number, hash) = self.best_block().await?;
+        let balance = self.balance_at(&account, hash).await?;
+        Ok(BalanceSnapshot {
+            account,
+            block_number,
+            balance,
+        })
+    }
+
+    /// Returns the change of the free balance since `snapshot`, net of the
+    /// transaction fees which the account paid in the meantime.
+    pub(crate) async fn balance_delta(
+        &self,
+        snapshot: &BalanceSnapshot,
+    ) -> Result<BalanceDelta> {
+        let (block_number, hash) = self.best_block().await?;
+        let balance = self.balance_at(&snapshot.account, hash).await?;
+        let fees = self
+            .fees_paid(&snapshot.account, snapshot.block_number + 1..=block_number)
+            .await?;
+        log::info!(
+            "[{}] free balance of {} went from {} to {}, fees paid {}",
+            utils::test_name(),
+            snapshot.account,
+            snapshot.balance.free.format_si(&self.token),
+            balance.free.format_si(&self.token),
+            fees.format_si(&self.token)
+        );
+        let before = snapshot.balance.free.as_plancks();
+        let after = balance.free.as_plancks() + fees.as_plancks();
+        let delta = if after >= before {
+            BalanceDelta::Received(Balance::plancks(after - before))
+        } else {
+            BalanceDelta::Spent(Balance::plancks(before - after))
+        };
+        Ok(delta)
+    }
+
+    /// Transfers `amount` from `faucet` to each of `accounts` and waits until the
+    /// transfers are included in a block.
+    ///
+    /// Concurrently running tests share the faucet, so they fund their accounts
+    /// one after the other, each with consecutive nonces.
+    pub(crate) async fn fund(
+        &self,
+        faucet: &Keypair,
+        accounts: &[AccountId32],
+        amount: Balance,
+    ) -> Result<()> {
+        let _guard = FAUCET.lock().await;
+        let nonce = self.client.tx().account_nonce(&faucet.account_id()).await?;
+        let mut transfers = Vec::new();
+        for (index, account) in accounts.iter().enumerate() {
+            let tx = subxt::dynamic::tx(
+                "Balances",
+                "transfer_keep_alive",
+                vec![
+                    Value::unnamed_variant("Id", [Value::from_bytes(account.0)]),
+                    Value::u128(amount.as_plancks()),
+                ],
+            );
+            let progress = self
+                .client
+                .tx()
+                .create_signed_with_nonce(
+                    &tx,
+                    faucet,
+                    nonce + index as u64,
+                    Default::default(),
+                )?
+                .submit_and_watch()
+                .await?;
+            transfers.push(progress);
+        }
+        for progress in transfers {
+            progress
+                .wait_for_in_block()
+                .await?
+                .wait_for_success()
+                .await?;
+        }
+        log::info!(
+            "[{}] funded {} accounts with {} each",
+            utils::test_name(),
+            accounts.len(),
+            amount.format_si(&self.token)
+        );
+        Ok(())
+    }
+
+    /// Returns the number and hash of the best block.
+    async fn best_block(&self) -> Result<(u32, H256)> {
+        let block = self.client.blocks().at_latest().await?;
+        Ok((block.number(), block.hash()))
+    }
+
+    /// Returns the balance of `account` at the block with `hash`.
+    async fn balance_at(
+        &self,
+        account: &AccountId32,
+        hash: H256,
+    ) -> Result<AccountBalance> {
+        let query = subxt::dynamic::storage(
+            "System",
+            "Account",
+            vec![Value::from_bytes(account.0)],
+        );
+        let info = self
+            .client
+            .storage()
+            .at(hash)
+            .fetch_or_default(&query)
+            .await?
+            .to_value()?;
+        let data = field(&info, "data")
+            .ok_or("unable to read account data from `System::Account`")?;
+        let amount = |name: &str| {
+            field(data, name)
+                .and_then(|amount| amount.as_u128())
+                .map(Balance::plancks)
+        };
+        // Older runtimes split the frozen balance into `misc_frozen` and `fee_frozen`.
+        let frozen = amount("frozen").or_else(|| {
+            amount("misc_frozen")
+                .into_iter()
+                .chain(amount("fee_frozen"))
+                .max()
+        });
+        match (amount("free"), amount("reserved"), frozen) {
+            (Some(free), Some(reserved), Some(frozen)) => {
+                Ok(AccountBalance {
+                    free,
+                    reserved,
+                    frozen,
+                })
+            }
+            _ => Err(format!("unexpected account data {:?}", data).into()),
+        }
+    }
+
+    /// Returns the sum of the fees which `account` paid in the blocks `numbers`,
+    /// as recorded in the `TransactionPayment::TransactionFeePaid` events.
+    async fn fees_paid(
+        &self,
+        account: &AccountId32,
+        numbers: RangeInclusive<u32>,
+    ) -> Result<Balance> {
+        let mut fees = 0u128;
+        for event in self.events(numbers).await? {
+            if event.pallet_name() != "TransactionPayment"
+                || event.variant_name() != "TransactionFeePaid"
+            {
+                continue
+            }
+            // `actual_fee` already includes the `tip`.
+            let (who, actual_fee, _tip) =
+                <(AccountId32, u128, u128)>::decode(&mut event.field_bytes())?;
+            if who == *account {
+                fees += actual_fee;
+            }
+        }
+        Ok(Balance::plancks(fees))
+    }
+
+    /// Returns the module error with which `extrinsic` failed, e.g.
+    /// `contracts.ContractNotFound`.
+    ///
+    /// The error is decoded from the `System::ExtrinsicFailed` event of the
+    /// extrinsic. Returns `None` if the extrinsic did not fail, or failed with
+    /// another kind of dispatch error.
+    pub(crate) async fn module_error(
+        &self,
+        extrinsic: &ExtrinsicId,
+    ) -> Result<Option<ModuleError>> {
+        let events = self.extrinsic_events(extrinsic).await?;
+        let Some(failed) = events.iter().find(|event| {
+            event.pallet_name() == "System" && event.variant_name() == "ExtrinsicFailed"
+        }) else {
+            return Ok(None)
+        };
+        module_error(&decode_dispatch_error(
+            &self.client.metadata(),
+            failed.field_bytes(),
+        )?)
+    }
+
+    /// Dry-runs `call` against the current state.
+    pub(crate) async fn dry_run(
+        &self,
+        contracts: &Contracts,
+        call: &Call,
+    ) -> Result<DryRun> {
+        let metadata = contracts.get(&call.contract_address)?;
+        let message = metadata.message(&call.method)?;
+        let result = rpc::dry_run_message(&self.client, metadata, message, call).await?;
+        let debug_message = String::from_utf8_lossy(&result.debug_message).into_owned();
+        let gas_required = result.gas_required();
+        let output = match result.result {
+            DispatchOutcome::Ok(output) if output.is_revert() => output,
+            // A contract which traps does not revert, the extrinsic fails with
+            // a dispatch error instead.
+            DispatchOutcome::Ok(_) | DispatchOutcome::Err(_) => {
+                return Ok(DryRun {
+                    revert: None,
+                    debug_message,
+                    gas_required,
+                })
+            }
+        };
+        let err = match decode::decode_output(metadata, message, &output.data)? {
+            ArgValue::Enum(variant, mut fields)
+                if variant == "Err" && fields.len() == 1 =>
+            {
+                fields.remove(0)
+            }
+            other => other,
+        };
+        log::info!(
+            "[{}] dry-run of {:?} reverted with {:?}",
+            utils::test_name(),
+            call.method,
+            err
+        );
+        Ok(DryRun {
+            revert: Some(err),
+            debug_message,
+            gas_required,
+        })
+    }
+
+    /// Dry-runs `upload` against the current state and returns the label of the
+    /// constructor and the gas (`ref_time`) which the instantiation requires.
+    pub(crate) async fn estimate_upload(&self, upload: &Upload) -> Result<(String, u64)> {
+        rpc::estimate_upload(&self.client, upload).await
+    }
+
+    /// Returns the weight (`ref_time`) which `extrinsic` consumed.
+    ///
+    /// The weight is taken from the `System::ExtrinsicSuccess` event of the
+    /// extrinsic. Returns `None` if the extrinsic did not succeed.
+    pub(crate) async fn consumed_weight(
+        &self,
+        extrinsic: &ExtrinsicId,
+    ) -> Result<Option<u64>> {
+        let events = self.extrinsic_events(extrinsic).await?;
+        let Some(success) = events.iter().find(|event| {
+            event.pallet_name() == "System" && event.variant_name() == "ExtrinsicSuccess"
+        }) else {
+            return Ok(None)
+        };
+        let info = match success.field_values()? {
+            Composite::Named(fields) => {
+                fields
+                    .into_iter()
+                    .find(|(name, _)| name == "dispatch_info")
+                    .map(|(_, info)| info)
+            }
+            Composite::Unnamed(_) => None,
+        };
+        let weight = info
+            .as_ref()
+            .and_then(|info| field(info, "weight"))
+            .ok_or("unable to read the weight of `System::ExtrinsicSuccess`")?;
+        // Before weights v2 the weight was a plain number.
+        Ok(field(weight, "ref_time")
+            .unwrap_or(weight)
+            .as_u128()
+            .map(|ref_time| ref_time as u64))
+    }
+
+    /// Returns the number of the best block.
+    pub(crate) async fn best_block_number(&self) -> Result<u32> {
+        Ok(self.best_block().await?.0)
+    }
+
+    /// Returns the nonce of `account`, which its next extrinsic is signed with.
+    pub(crate) async fn nonce(&self, account: &AccountId32) -> Result<u64> {
+        Ok(self.client.tx().account_nonce(account).await?)
+    }
+
+    /// Returns the extrinsic in the blocks after `since` which `signer` signed
+    /// with the nonce `nonce`.
+    ///
+    /// The browser UIs do not tell which extrinsic they submitted, it is found by
+    /// its signer and the signer's nonce before the submission instead. The nonce
+    /// is unique per signer, so at most one extrinsic matches.
+    async fn find_extrinsic(
+        &self,
+        since: u32,
+        signer: &AccountId32,
+        nonce: u64,
+    ) -> Result<Option<ExtrinsicId>> {
+        let best = self.best_block_number().await?;
+        for number in since + 1..=best {
+            let hash = self.block_hash(number).await?;
+            let extrinsics = self.client.blocks().at(hash).await?.body().await?;
+            for extrinsic in extrinsics.extrinsics().iter() {
+                let extrinsic = extrinsic?;
+                let Some(address) = extrinsic.address_bytes() else {
+                    continue
+                };
+                // the address is a `MultiAddress`, which prefixes the account
+                if !address.ends_with(&signer.0) {
+                    continue
+                }
+                if self.signed_nonce(extrinsic.bytes(), address.len())? == nonce {
+                    return Ok(Some(ExtrinsicId {
+                        block: number,
+                        index: extrinsic.index(),
+                    }))
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the extrinsic in the blocks after `since` which instantiated the
+    /// contract `contract`, as recorded in its `Contracts::Instantiated` event.
+    async fn find_instantiation(
+        &self,
+        since: u32,
+        contract: &str,
+    ) -> Result<Option<ExtrinsicId>> {
+        let best = self.best_block_number().await?;
+        for number in since + 1..=best {
+            for event in self.block_events(number).await? {
+                if event.pallet_name() != "Contracts"
+                    || event.variant_name() != "Instantiated"
+                {
+                    continue
+                }
+                let (_deployer, instantiated) =
+                    <(AccountId32, AccountId32)>::decode(&mut event.field_bytes())?;
+                if instantiated.to_string() != contract {
+                    continue
+                }
+                if let Phase::ApplyExtrinsic(index) = event.phase() {
+                    return Ok(Some(ExtrinsicId {
+                        block: number,
+                        index,
+                    }))
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the extrinsic which `signer` submitted via a backend after the
+    /// block `since`, when its nonce was `nonce`.
+    ///
+    /// The rpc backend tells the extrinsic with the resulting `events`, for the
+    /// browser UIs it is searched on the chain.
+    pub(crate) async fn submitted_extrinsic(
+        &self,
+        events: Option<&Events>,
+        since: u32,
+        signer: &AccountId32,
+        nonce: u64,
+    ) -> Result<ExtrinsicId> {
+        if let Some(extrinsic) = events.and_then(Events::extrinsic) {
+            return Ok(extrinsic)
+        }
+        self.find_extrinsic(since, signer, nonce)
+            .await?
+            .ok_or_else(|| {
+                format!("no extrinsic of {} with the nonce {} found", signer, nonce)
+                    .into()
+            })
+    }
+
+    /// Returns the extrinsic which instantiated `contract` after the block
+    /// `since`.
+    pub(crate) async fn instantiation(
+        &self,
+        since: u32,
+        contract: &str,
+    ) -> Result<ExtrinsicId> {
+        self.find_instantiation(since, contract)
+            .await?
+            .ok_or_else(|| format!("no instantiation of {} found", contract).into())
+    }
+
+    /// Returns the nonce with which the extrinsic `bytes` was signed, the address
+    /// of the signer takes `address_len` bytes.
+    ///
+    /// The bytes start with the version, followed by the address, the signature
+    /// and the signed extensions, one of which is the nonce.
+    fn signed_nonce(&self, bytes: &[u8], address_len: usize) -> Result<u64> {
+        let metadata = self.client.metadata();
+        let extrinsic = metadata.extrinsic();
+        let nonce = extrinsic
+            .signed_extensions()
+            .iter()
+            .position(|extension| extension.identifier() == "CheckNonce")
+            .ok_or("the runtime has no `CheckNonce` signed extension")?;
+        let cursor = &mut bytes.get(1 + address_len..).unwrap_or_default();
+        let decode = |cursor: &mut &[u8], ty| {
+            scale_value::scale::decode_as_type(cursor, ty, metadata.types())
+                .map_err(|err| format!("unable to decode extrinsic: {:?}", err))
+        };
+        decode(cursor, extrinsic.signature_ty())?;
+        let extra = decode(cursor, extrinsic.extra_ty())?;
+        let nonce = match &extra.value {
+            ValueDef::Composite(extensions) => extensions.values().nth(nonce),
+            _ => None,
+        };
+        nonce
+            .and_then(Value::as_u128)
+            .map(|nonce| nonce as u64)
+            .ok_or_else(|| format!("unable to read the nonce from {:?}", extra).into())
+    }
+
+    /// Returns the events which `extrinsic` emitted, in the order in which they
+    /// were emitted.
+    async fn extrinsic_events(
+        &self,
+        extrinsic: &ExtrinsicId,
+    ) -> Result<Vec<EventDetails<PolkadotConfig>>> {
+        let mut events = self.block_events(extrinsic.block).await?;
+        events.retain(|event| event.phase() == Phase::ApplyExtrinsic(extrinsic.index));
+        Ok(events)
+    }
+
+    /// Returns the events which contracts emitted during `extrinsic`, as recorded
+    /// in the `Contracts::ContractEmitted` events.
+    ///
+    /// The events are decoded with the metadata in `contracts`, events of other
+    /// contracts are skipped.
+    pub(crate) async fn contract_events(
+        &self,
+        extrinsic: &ExtrinsicId,
+        contracts: &Contracts,
+    ) -> Result<Vec<ContractEvent>> {
+        let mut contract_events = Vec::new();
+        for event in self.extrinsic_events(extrinsic).await? {
+            if event.pallet_name() != "Contracts"
+                || event.variant_name() != "ContractEmitted"
+            {
+                continue
+            }
+            let (contract, data) =
+                <(AccountId32, Vec<u8>)>::decode(&mut event.field_bytes())?;
+            let contract = contract.to_string();
+            let metadata = match contracts.get(&contract) {
+                Ok(metadata) => metadata,
+                Err(_) => {
+                    log::info!(
+                        "[{}] skipping event of unknown contract {}",
+                        utils::test_name(),
+                        contract
+                    );
+                    continue
+                }
+            };
+            let topics: Vec<_> = event.topics().iter().map(|topic| topic.0).collect();
+            let contract_event =
+                decode::decode_event(metadata, &contract, &data, &topics)?;
+            log::info!(
+                "[{}] contract {} emitted {:?}",
+                utils::test_name(),
+                contract,
+                contract_event
+            );
+            contract_events.push(contract_event);
+        }
+        Ok(contract_events)
+    }
+
+    /// Returns the events of the blocks `numbers`, in the order in which they
+    /// were emitted.
+    async fn events(
+        &self,
+        numbers: RangeInclusive<u32>,
+    ) -> Result<Vec<EventDetails<PolkadotConfig>>> {
+        let mut details = Vec::new();
+        for number in numbers {
+            details.extend(self.block_events(number).await?);
+        }
+        Ok(details)
+    }
+
+    /// Returns the events of the block `number`, in the order in which they were
+    /// emitted.
+    async fn block_events(
+        &self,
+        number: u32,
+    ) -> Result<Vec<EventDetails<PolkadotConfig>>> {
+        let hash = self.block_hash(number).await?;
+        let events = self.client.blocks().at(hash).await?.events().await?;
+        let mut details = Vec::new();
+        for event in events.iter() {
+            details.push(event?);
+        }
+        Ok(details)
+    }
+
+    /// Returns the hash of the block `number`.
+    async fn block_hash(&self, number: u32) -> Result<H256> {
+        self.client
+            .rpc()
+            .block_hash(Some(number.into()))
+            .await?
+            .ok_or_else(|| format!("block {} not found", number).into())
+    }
+}
+
+/// Decodes the dispatch error at the start of `bytes`, e.g. of the fields of a
+/// `System::ExtrinsicFailed` event.
+///
+/// [`DispatchError::decode_from`] takes all remaining bytes for a module error, so
+/// `bytes` are cut to the length of the dispatch error first.
+pub(crate) fn decode_dispatch_error(
+    metadata: &Metadata,
+    bytes: &[u8],
+) -> Result<DispatchError> {
+    let ty = metadata
+        .dispatch_error_ty()
+        .ok_or("the runtime has no `DispatchError` type")?;
+    let cursor = &mut &*bytes;
+    scale_value::scale::decode_as_type(cursor, ty, metadata.types())
+        .map_err(|err| format!("unable to decode dispatch error: {:?}", err))?;
+    let len = bytes.len() - cursor.len();
+    Ok(DispatchError::decode_from(&bytes[..len], metadata.clone())?)
+}
+
+/// Returns the module error of `error`, e.g. `contracts.ContractNotFound`, or
+/// `None` if `error` is another kind of dispatch error.
+pub(crate) fn module_error(error: &DispatchError) -> Result<Option<ModuleError>> {
+    match error {
+        DispatchError::Module(error) => {
+            let details = error.details()?;
+            Ok(Some(ModuleError::new(
+                &details.pallet.name().to_case(Case::Camel),
+                &details.variant.name,
+                &details.variant.docs.join(" "),
+            )))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Returns the field `name` of the composite `value`.
+fn field<'a, T>(value: &'a Value<T>, name: &str) -> Option<&'a Value<T>> {
+    match &value.value {
+        ValueDef::Composite(Composite::Named(fields)) => {
+            fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value)
+        }
+        _ => None,
+    }
+}
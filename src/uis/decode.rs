@@ -0,0 +1,977 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoding of the return values of messages into an [`ArgValue`], guided by
+//! the return type in the contract metadata.
+//!
+//! The RPC backend decodes the SCALE encoded output of a dry-run, the browser
+//! UIs parse the text which they display as the outcome. Both yield the same
+//! value, so that the tests do not need to know which backend they run against:
+//!
+//! * `u128` values are balances if their type is named `Balance` (as in ink!'s
+//!   default environment), either by its path, by the display name in the spec
+//!   of an argument, or by the type name of the field which holds it. Balances
+//!   are normalized to plancks, regardless of whether the UI displays them as
+//!   plancks (`1,000,000,000,000,000`) or in units of the token
+//!   (`1.0000 kUnit`). All other `u128` values are unsigned integers.
+//! * `Option` and `Result` are kept structurally, as `ArgValue::Option` and as
+//!   the `Ok`/`Err` variant of an `ArgValue::Enum`. Only the `MessageResult`
+//!   with which ink! wraps every return value is unwrapped.
+//! * Byte arrays and newtypes around them (e.g. `Hash`) become `ArgValue::Bytes`.
+//! * Accounts in the keyring are returned by their name (e.g. `ALICE`), all
+//!   other accounts by their SS58 address.
+//!
+//! The events which contracts emit are decoded the same way, with the event spec
+//! from the contract metadata.
+
+use crate::uis::{
+    keyring,
+    metadata::{
+        byte_array_len,
+        is_account_id,
+        is_u8,
+        resolve,
+        ContractBundle,
+        EventSpec,
+        MessageSpec,
+    },
+    ArgValue,
+    Balance,
+    ContractEvent,
+    Result,
+    Token,
+};
+use scale_info::{
+    form::PortableForm,
+    Field,
+    PortableRegistry,
+    Type,
+    TypeDef,
+    TypeDefPrimitive,
+    TypeDefVariant,
+};
+use std::str::FromStr;
+use subxt::{
+    ext::scale_value::{
+        self,
+        Composite,
+        Primitive,
+        Value,
+        ValueDef,
+    },
+    utils::AccountId32,
+};
+
+/// The ways in which the UIs display `Option::None`.
+const NONE_OUTCOMES: [&str; 4] = ["None", "<none>", "null", "<empty>"];
+
+/// Decodes the SCALE encoded `output` of `message`.
+pub(crate) fn decode_output(
+    metadata: &ContractBundle,
+    message: &MessageSpec,
+    output: &[u8],
+) -> Result<ArgValue> {
+    let (ty, balance) = return_type(message)?;
+    let decoded =
+        scale_value::scale::decode_as_type(&mut &output[..], ty, &metadata.registry)
+            .map_err(|err| format!("unable to decode the rpc outcome: {:?}", err))?;
+    let value = from_scale(&metadata.registry, &decoded, balance)?;
+    unwrap_message_result(&metadata.registry, ty, value)
+}
+
+/// Parses the `outcome` of `message`, as displayed by one of the browser UIs.
+///
+/// `token` is the chain's token, it is needed for balances which the UI
+/// displays in units of the token.
+pub(crate) fn parse_outcome(
+    metadata: &ContractBundle,
+    message: &MessageSpec,
+    outcome: &str,
+    token: &Token,
+) -> Result<ArgValue> {
+    let (ty, balance) = return_type(message)?;
+    let mut parser = Parser {
+        registry: &metadata.registry,
+        token,
+        rest: outcome,
+    };
+    let value = parser.value(ty, balance)?;
+    if !parser.rest.trim().is_empty() {
+        return Err(format!(
+            "unable to parse the outcome {:?}, {:?} is left over",
+            outcome, parser.rest
+        )
+        .into())
+    }
+    unwrap_message_result(&metadata.registry, ty, value)
+}
+
+/// Decodes the `data` of an event which the contract at `contract_address`
+/// emitted with `topics`.
+pub(crate) fn decode_event(
+    metadata: &ContractBundle,
+    contract_address: &str,
+    data: &[u8],
+    topics: &[[u8; 32]],
+) -> Result<ContractEvent> {
+    let (spec, mut data) = event_spec(metadata, data, topics)?;
+    let mut fields = Vec::new();
+    for arg in &spec.args {
+        let decoded =
+            scale_value::scale::decode_as_type(&mut data, arg.ty.ty, &metadata.registry)
+                .map_err(|err| {
+                    format!(
+                        "unable to decode field {:?} of event {:?}: {:?}",
+                        arg.label, spec.label, err
+                    )
+                })?;
+        let balance = names_balance(&arg.ty.display_name);
+        fields.push((
+            arg.label.clone(),
+            from_scale(&metadata.registry, &decoded, balance)?,
+        ));
+    }
+    if !data.is_empty() {
+        return Err(format!(
+            "event {:?} has {} bytes left over after decoding",
+            spec.label,
+            data.len()
+        )
+        .into())
+    }
+    Ok(ContractEvent::new(
+        contract_address,
+        &spec.label,
+        fields,
+        topics.to_vec(),
+    ))
+}
+
+/// Returns the spec of the event with `data` and `topics`, together with the
+/// encoded fields of the event.
+///
+/// Since ink! 5 the first topic identifies the event, before the first byte of
+/// the data is the index of the event in the metadata.
+fn event_spec<'a, 'b>(
+    metadata: &'a ContractBundle,
+    data: &'b [u8],
+    topics: &[[u8; 32]],
+) -> Result<(&'a EventSpec, &'b [u8])> {
+    let events = &metadata.spec.events;
+    if events.iter().any(|event| event.signature_topic.is_some()) {
+        let topic = topics
+            .first()
+            .map(|topic| format!("0x{}", hex::encode(topic)))
+            .ok_or("event without signature topic")?;
+        let spec = events
+            .iter()
+            .find(|event| event.signature_topic.as_deref() == Some(&topic))
+            .ok_or_else(|| format!("no event with signature topic {} found", topic))?;
+        return Ok((spec, data))
+    }
+    let (index, data) = data.split_first().ok_or("event without data")?;
+    let spec = events
+        .get(*index as usize)
+        .ok_or_else(|| format!("no event with index {} found", index))?;
+    Ok((spec, data))
+}
+
+/// Returns the return type of `message`, and whether its display name is
+/// `Balance`.
+fn return_type(message: &MessageSpec) -> Result<(u32, bool)> {
+    message
+        .return_type
+        .as_ref()
+        .map(|return_type| (return_type.ty, names_balance(&return_type.display_name)))
+        .ok_or_else(|| format!("message {:?} has no return type", message.label).into())
+}
+
+/// Unwraps the `MessageResult` with which ink! wraps the return value of every
+/// message. A `LangError` is returned as an error.
+fn unwrap_message_result(
+    registry: &PortableRegistry,
+    ty: u32,
+    value: ArgValue,
+) -> Result<ArgValue> {
+    if !is_message_result(resolve(registry, ty)?, registry) {
+        return Ok(value)
+    }
+    match value {
+        ArgValue::Enum(variant, mut fields) if variant == "Ok" && fields.len() == 1 => {
+            Ok(fields.remove(0))
+        }
+        ArgValue::Enum(variant, fields) if variant == "Err" => {
+            Err(format!("the message failed with the `LangError` {:?}", fields).into())
+        }
+        other => Err(format!("{:?} is not a `MessageResult`", other).into()),
+    }
+}
+
+/// Returns `true` if `ty` is a `Result<T, ink::LangError>`.
+fn is_message_result(ty: &Type<PortableForm>, registry: &PortableRegistry) -> bool {
+    let is_result = ty.path.segments.last().map(String::as_str) == Some("Result");
+    let err_ty = ty
+        .type_params
+        .iter()
+        .find(|param| param.name == "E")
+        .and_then(|param| param.ty.as_ref())
+        .and_then(|err_ty| registry.resolve(err_ty.id));
+    is_result
+        && err_ty
+            .map(|err_ty| {
+                err_ty.path.segments.last().map(String::as_str) == Some("LangError")
+            })
+            .unwrap_or(false)
+}
+
+/// Returns `true` if `ty` is a `u128`, possibly in compact encoding.
+fn is_u128(registry: &PortableRegistry, ty: u32) -> bool {
+    match registry.resolve(ty).map(|ty| &ty.type_def) {
+        Some(TypeDef::Primitive(TypeDefPrimitive::U128)) => true,
+        Some(TypeDef::Compact(compact)) => is_u128(registry, compact.type_param.id),
+        _ => false,
+    }
+}
+
+/// Returns `true` if the last segment of the path `segments` is `Balance`.
+fn names_balance(segments: &[String]) -> bool {
+    segments.last().map(String::as_str) == Some("Balance")
+}
+
+/// Returns `true` if the type name `name` of a field is `Balance`, or a
+/// container of balances such as `Vec<Balance>` or `Option<Balance>`.
+fn is_balance_name(name: &str) -> bool {
+    match name.split_once('<') {
+        Some((_, params)) => params.strip_suffix('>').is_some_and(is_balance_name),
+        None => name.rsplit("::").next() == Some("Balance"),
+    }
+}
+
+/// Returns `true` if the values of `field` of `ty` are balances.
+///
+/// `balance` tells whether the values of `ty` are. Fields which are typed by a
+/// type parameter of `ty`, e.g. the `T` of `Option<T>`, inherit it.
+fn is_balance_field(
+    ty: &Type<PortableForm>,
+    field: &Field<PortableForm>,
+    balance: bool,
+) -> bool {
+    match field.type_name.as_deref() {
+        Some(name) if ty.type_params.iter().any(|param| param.name == name) => balance,
+        Some(name) => is_balance_name(name),
+        None => balance,
+    }
+}
+
+/// Returns the fields of `ty` if it is a struct, or those of its variant
+/// `variant` if it is an enum.
+fn fields_of<'a>(
+    ty: &'a Type<PortableForm>,
+    variant: Option<&str>,
+) -> &'a [Field<PortableForm>] {
+    match (&ty.type_def, variant) {
+        (TypeDef::Composite(composite), None) => &composite.fields,
+        (TypeDef::Variant(def), Some(name)) => {
+            def.variants
+                .iter()
+                .find(|variant| variant.name == name)
+                .map(|variant| variant.fields.as_slice())
+                .unwrap_or_default()
+        }
+        _ => &[],
+    }
+}
+
+/// Returns `true` if `ty` is an `Option`.
+fn is_option(ty: &Type<PortableForm>) -> bool {
+    ty.path.segments.last().map(String::as_str) == Some("Option")
+}
+
+/// Returns the value of the variant `name` of `ty` with `fields`.
+fn variant_value(
+    ty: &Type<PortableForm>,
+    name: &str,
+    mut fields: Vec<ArgValue>,
+) -> ArgValue {
+    match name {
+        "None" if is_option(ty) => ArgValue::Option(None),
+        "Some" if is_option(ty) && fields.len() == 1 => {
+            ArgValue::Option(Some(Box::new(fields.remove(0))))
+        }
+        _ => ArgValue::Enum(name.to_string(), fields),
+    }
+}
+
+/// Returns the account `account`, by its name if it is in the keyring.
+fn account_value(account: &AccountId32) -> ArgValue {
+    ArgValue::Account(keyring::account_name(account))
+}
+
+/// Converts the decoded `value` into an `ArgValue`.
+///
+/// `balance` tells whether the type of `value` is named `Balance` where it is
+/// used, e.g. by the display name of an argument.
+fn from_scale(
+    registry: &PortableRegistry,
+    value: &Value<u32>,
+    balance: bool,
+) -> Result<ArgValue> {
+    let ty = resolve(registry, value.context)?;
+    let balance = balance || names_balance(&ty.path.segments);
+    // the values of sequences, arrays and tuples inherit `balance`
+    let is_balance = |fields: &[Field<PortableForm>], index: usize| {
+        fields
+            .get(index)
+            .map_or(balance, |field| is_balance_field(ty, field, balance))
+    };
+    if is_account_id(ty) {
+        let bytes = bytes_of(registry, value)?;
+        let account = <[u8; 32]>::try_from(bytes.as_slice())
+            .map_err(|_| format!("{:?} is not a valid account id", bytes))?;
+        return Ok(account_value(&AccountId32(account)))
+    }
+    if byte_array_len(registry, value.context).is_some() {
+        return Ok(ArgValue::Bytes(bytes_of(registry, value)?))
+    }
+
+    let value = match &value.value {
+        ValueDef::Primitive(Primitive::Bool(val)) => ArgValue::Bool(*val),
+        ValueDef::Primitive(Primitive::U128(val))
+            if balance && is_u128(registry, value.context) =>
+        {
+            ArgValue::Balance(Balance::plancks(*val))
+        }
+        ValueDef::Primitive(Primitive::U128(val)) => ArgValue::UInt(*val),
+        ValueDef::Primitive(Primitive::I128(val)) => ArgValue::Int(*val),
+        ValueDef::Primitive(Primitive::String(val)) => ArgValue::Str(val.clone()),
+        ValueDef::Primitive(Primitive::Char(val)) => ArgValue::Str(val.to_string()),
+        ValueDef::Primitive(other) => {
+            return Err(format!("{:?} is not supported", other).into())
+        }
+        ValueDef::Variant(variant) => {
+            let specs = fields_of(ty, Some(&variant.name));
+            let fields = variant
+                .values
+                .values()
+                .enumerate()
+                .map(|(index, field)| {
+                    from_scale(registry, field, is_balance(specs, index))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            variant_value(ty, &variant.name, fields)
+        }
+        ValueDef::Composite(composite) => {
+            let is_struct = matches!(ty.type_def, TypeDef::Composite(_));
+            let specs = fields_of(ty, None);
+            match composite {
+                // newtypes are transparent
+                _ if is_struct && composite.len() == 1 => {
+                    let field = composite.values().next().expect("one field");
+                    from_scale(registry, field, balance || is_balance(specs, 0))?
+                }
+                Composite::Named(fields) if is_struct => {
+                    let fields = fields
+                        .iter()
+                        .enumerate()
+                        .map(|(index, (name, field))| {
+                            let value =
+                                from_scale(registry, field, is_balance(specs, index))?;
+                            Ok((name.clone(), value))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    ArgValue::Struct(fields)
+                }
+                _ => {
+                    let elements = composite
+                        .values()
+                        .enumerate()
+                        .map(|(index, element)| {
+                            from_scale(registry, element, is_balance(specs, index))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    ArgValue::Vec(elements)
+                }
+            }
+        }
+        ValueDef::BitSequence(_) => return Err("bit sequences are not supported".into()),
+    };
+    Ok(value)
+}
+
+/// Returns the bytes of `value`, which is a byte array or a newtype around one.
+fn bytes_of(registry: &PortableRegistry, value: &Value<u32>) -> Result<Vec<u8>> {
+    match &value.value {
+        ValueDef::Composite(composite) => {
+            let values: Vec<_> = composite.values().collect();
+            if values.len() == 1 && !is_u8(registry, values[0].context) {
+                return bytes_of(registry, values[0])
+            }
+            values
+                .iter()
+                .map(|value| {
+                    value
+                        .as_u128()
+                        .and_then(|byte| u8::try_from(byte).ok())
+                        .ok_or_else(|| format!("{:?} is not a byte", value).into())
+                })
+                .collect()
+        }
+        other => Err(format!("{:?} is not a byte array", other).into()),
+    }
+}
+
+/// Parses the text which the browser UIs display for a value.
+///
+/// The parser is guided by the expected type and accepts the notations of both
+/// UIs, e.g. `Some(5)`, `{ Ok: 5 }` or `[ 1 2 3 ]`.
+struct Parser<'a> {
+    registry: &'a PortableRegistry,
+    token: &'a Token,
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    /// Parses a value of type `ty`, `balance` tells whether the type is named
+    /// `Balance` where it is used.
+    fn value(&mut self, ty: u32, balance: bool) -> Result<ArgValue> {
+        let resolved = resolve(self.registry, ty)?;
+        let balance = balance || names_balance(&resolved.path.segments);
+        if is_account_id(resolved) {
+            return self.account()
+        }
+        if let Some(len) = byte_array_len(self.registry, ty) {
+            return self.bytes(len)
+        }
+
+        match &resolved.type_def {
+            TypeDef::Primitive(primitive) => self.primitive(primitive, balance),
+            TypeDef::Compact(compact) => self.value(compact.type_param.id, balance),
+            // newtypes are transparent
+            TypeDef::Composite(composite) if composite.fields.len() == 1 => {
+                let field = &composite.fields[0];
+                self.value(
+                    field.ty.id,
+                    balance || is_balance_field(resolved, field, balance),
+                )
+            }
+            TypeDef::Composite(composite)
+                if composite.fields.iter().all(|field| field.name.is_some()) =>
+            {
+                self.expect("{")?;
+                let mut fields = Vec::new();
+                for field in &composite.fields {
+                    let name = field.name.clone().expect("checked above");
+                    self.eat(",");
+                    self.expect(&name)?;
+                    self.expect(":")?;
+                    let balance = is_balance_field(resolved, field, balance);
+                    fields.push((name, self.value(field.ty.id, balance)?));
+                }
+                self.expect("}")?;
+                Ok(ArgValue::Struct(fields))
+            }
+            TypeDef::Composite(composite) => {
+                let types: Vec<_> = composite
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        (field.ty.id, is_balance_field(resolved, field, balance))
+                    })
+                    .collect();
+                self.tuple(&types)
+            }
+            TypeDef::Tuple(tuple) => {
+                let types: Vec<_> = tuple
+                    .fields
+                    .iter()
+                    .map(|field| (field.id, balance))
+                    .collect();
+                self.tuple(&types)
+            }
+            TypeDef::Array(array) => self.sequence(array.type_param.id, balance),
+            TypeDef::Sequence(sequence) => self.sequence(sequence.type_param.id, balance),
+            TypeDef::Variant(variant) => self.variant(resolved, variant, balance),
+            TypeDef::BitSequence(_) => Err("bit sequences are not supported".into()),
+        }
+    }
+
+    fn primitive(
+        &mut self,
+        primitive: &TypeDefPrimitive,
+        balance: bool,
+    ) -> Result<ArgValue> {
+        let value = match primitive {
+            TypeDefPrimitive::Bool => {
+                match self.token() {
+                    "true" | "Yes" => ArgValue::Bool(true),
+                    "false" | "No" => ArgValue::Bool(false),
+                    other => return Err(format!("{:?} is not a bool", other).into()),
+                }
+            }
+            TypeDefPrimitive::Str | TypeDefPrimitive::Char => {
+                self.skip_whitespace();
+                let value = match self.rest.strip_prefix('"') {
+                    Some(rest) => {
+                        let (value, rest) = rest
+                            .split_once('"')
+                            .ok_or("unterminated string in outcome")?;
+                        self.rest = rest;
+                        value
+                    }
+                    None => self.token(),
+                };
+                ArgValue::Str(value.to_string())
+            }
+            TypeDefPrimitive::U128 if balance => ArgValue::Balance(self.balance()?),
+            // the UIs might still display it as a balance
+            TypeDefPrimitive::U128 => ArgValue::UInt(self.balance()?.as_plancks()),
+            TypeDefPrimitive::U8
+            | TypeDefPrimitive::U16
+            | TypeDefPrimitive::U32
+            | TypeDefPrimitive::U64 => {
+                let token = self.token();
+                ArgValue::UInt(token.replace(',', "").parse().map_err(|err| {
+                    format!("{:?} is not an unsigned integer: {}", token, err)
+                })?)
+            }
+            TypeDefPrimitive::I8
+            | TypeDefPrimitive::I16
+            | TypeDefPrimitive::I32
+            | TypeDefPrimitive::I64
+            | TypeDefPrimitive::I128 => {
+                let token = self.token();
+                ArgValue::Int(token.replace(',', "").parse().map_err(|err| {
+                    format!("{:?} is not a signed integer: {}", token, err)
+                })?)
+            }
+            TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => {
+                return Err(format!("{:?} is not supported", primitive).into())
+            }
+        };
+        Ok(value)
+    }
+
+    /// Parses a balance, either in plancks or in units of the token with an
+    /// SI prefix (e.g. `1.0000 kUnit`).
+    fn balance(&mut self) -> Result<Balance> {
+        let amount = self.token();
+        let trimmed = self.rest.trim_start_matches(' ');
+        let unit: &str = trimmed
+            .split(|c: char| !c.is_alphabetic())
+            .next()
+            .unwrap_or_default();
+        if unit.is_empty() {
+            return Balance::parse(amount, self.token)
+        }
+        self.rest = &trimmed[unit.len()..];
+        Balance::parse(&format!("{} {}", amount, unit), self.token)
+    }
+
+    fn account(&mut self) -> Result<ArgValue> {
+        let token = self.token();
+        if keyring::is_dev_account(token) {
+            return Ok(ArgValue::Account(token.to_uppercase()))
+        }
+        let account = AccountId32::from_str(token)
+            .map_err(|err| format!("{:?} is not a valid account: {:?}", token, err))?;
+        Ok(account_value(&account))
+    }
+
+    /// Parses a byte array of `len` bytes or a byte vector if `len` is `None`.
+    fn bytes(&mut self, len: Option<usize>) -> Result<ArgValue> {
+        let token = self.token();
+        let bytes = if token == "<empty>" {
+            // the UIs display all-zero byte arrays as empty
+            vec![0; len.unwrap_or_default()]
+        } else if token.contains('…') {
+            // the UIs shorten long hex strings to e.g. `0x000000…00000000`
+            let len = len.ok_or("the UI displays a shortened byte vector")?;
+            let digits = token.trim_start_matches("0x").replace('…', "");
+            if !digits.trim_matches('0').is_empty() {
+                return Err(format!("the UI displays a shortened hash {:?}", token).into())
+            }
+            vec![0; len]
+        } else {
+            hex::decode(token.trim_start_matches("0x"))
+                .map_err(|err| format!("{:?} is not valid hex: {}", token, err))?
+        };
+        if let Some(len) = len {
+            if bytes.len() != len {
+                return Err(format!("{:?} is not an array of {} bytes", token, len).into())
+            }
+        }
+        Ok(ArgValue::Bytes(bytes))
+    }
+
+    /// Parses `[ a b ]`, `[a, b]` or `(a, b)` with elements of `types`, each
+    /// together with whether it is a balance.
+    fn tuple(&mut self, types: &[(u32, bool)]) -> Result<ArgValue> {
+        self.skip_whitespace();
+        if types.is_empty() && !self.rest.starts_with(['[', '(']) {
+            return Ok(ArgValue::Vec(Vec::new()))
+        }
+        let close = if self.eat("(") {
+            ")"
+        } else {
+            self.expect("[").map(|_| "]")?
+        };
+        let mut elements = Vec::new();
+        for (ty, balance) in types {
+            self.eat(",");
+            elements.push(self.value(*ty, *balance)?);
+        }
+        self.expect(close)?;
+        Ok(ArgValue::Vec(elements))
+    }
+
+    /// Parses `[ a b ]` or `[a, b]` with elements of type `ty`.
+    fn sequence(&mut self, ty: u32, balance: bool) -> Result<ArgValue> {
+        self.expect("[")?;
+        let mut elements = Vec::new();
+        while !self.eat("]") {
+            if self.rest.is_empty() {
+                return Err("unterminated sequence in outcome".into())
+            }
+            self.eat(",");
+            elements.push(self.value(ty, balance)?);
+        }
+        Ok(ArgValue::Vec(elements))
+    }
+
+    /// Parses `Name(a, b)`, `{ Name: a }` or `Name`.
+    ///
+    /// The UIs omit the `Some`/`Ok` around values, if there is no variant name
+    /// the value is hence parsed as the inner value of one of those.
+    fn variant(
+        &mut self,
+        ty: &Type<PortableForm>,
+        def: &TypeDefVariant<PortableForm>,
+        balance: bool,
+    ) -> Result<ArgValue> {
+        self.skip_whitespace();
+        if is_option(ty) {
+            for none in NONE_OUTCOMES {
+                if self.eat_word(none) {
+                    return Ok(ArgValue::Option(None))
+                }
+            }
+        }
+
+        let start = self.rest;
+        let braced = self.eat("{");
+        self.skip_whitespace();
+        let name = self.ident();
+        if let Some(variant) = def.variants.iter().find(|variant| variant.name == name) {
+            let mut fields = Vec::new();
+            if braced {
+                self.expect(":")?;
+                for (index, field) in variant.fields.iter().enumerate() {
+                    if index > 0 {
+                        self.expect(",")?;
+                    }
+                    let balance = is_balance_field(ty, field, balance);
+                    fields.push(self.value(field.ty.id, balance)?);
+                }
+                self.expect("}")?;
+            } else if !variant.fields.is_empty() {
+                self.expect("(")?;
+                for (index, field) in variant.fields.iter().enumerate() {
+                    if index > 0 {
+                        self.expect(",")?;
+                    }
+                    let balance = is_balance_field(ty, field, balance);
+                    fields.push(self.value(field.ty.id, balance)?);
+                }
+                self.expect(")")?;
+            }
+            return Ok(variant_value(ty, &variant.name, fields))
+        }
+        self.rest = start;
+
+        let implicit = def
+            .variants
+            .iter()
+            .find(|variant| {
+                (variant.name == "Some" || variant.name == "Ok")
+                    && variant.fields.len() == 1
+            })
+            .ok_or_else(|| format!("{:?} does not start with a variant", self.rest))?;
+        let field = &implicit.fields[0];
+        let inner = self.value(field.ty.id, is_balance_field(ty, field, balance))?;
+        Ok(variant_value(ty, &implicit.name, vec![inner]))
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    /// Consumes `token` if the rest starts with it.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        match self.rest.strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Consumes `word` if the rest starts with it as a whole word.
+    fn eat_word(&mut self, word: &str) -> bool {
+        let start = self.rest;
+        if self.eat(word)
+            && !self
+                .rest
+                .starts_with(|c: char| c.is_alphanumeric() || c == '_')
+        {
+            return true
+        }
+        self.rest = start;
+        false
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if self.eat(token) {
+            return Ok(())
+        }
+        Err(format!("expected {:?} in outcome, found {:?}", token, self.rest).into())
+    }
+
+    /// Consumes an identifier.
+    fn ident(&mut self) -> &'a str {
+        let end = self
+            .rest
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(self.rest.len());
+        let (ident, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        ident
+    }
+
+    /// Consumes the next token, i.e. everything up to the next whitespace,
+    /// bracket or separating comma. Commas which group digits are kept.
+    fn token(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let mut end = self.rest.len();
+        let mut chars = self.rest.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            let separating_comma = c == ','
+                && !chars
+                    .peek()
+                    .map(|(_, next)| next.is_ascii_digit())
+                    .unwrap_or(false);
+            if c.is_whitespace() || "()[]{}".contains(c) || separating_comma {
+                end = index;
+                break
+            }
+        }
+        let (token, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scale_info::{
+        build::Fields,
+        meta_type,
+        Path,
+        Registry,
+        TypeInfo,
+    };
+
+    /// The `Balance` of ink!'s default environment.
+    struct InkBalance;
+
+    impl TypeInfo for InkBalance {
+        type Identity = Self;
+
+        fn type_info() -> Type {
+            Type::builder()
+                .path(Path::new("Balance", "ink_env::types"))
+                .composite(Fields::unnamed().field(|f| f.ty::<u128>().type_name("u128")))
+        }
+    }
+
+    /// The `AccountId` of ink!'s default environment.
+    struct InkAccountId;
+
+    impl TypeInfo for InkAccountId {
+        type Identity = Self;
+
+        fn type_info() -> Type {
+            Type::builder()
+                .path(Path::new("AccountId", "ink_primitives::types"))
+                .composite(
+                    Fields::unnamed().field(|f| f.ty::<[u8; 32]>().type_name("[u8; 32]")),
+                )
+        }
+    }
+
+    /// Parses `outcome` as a value of `T`, `balance` tells whether `T` is named
+    /// `Balance` where it is used.
+    fn parse<T: TypeInfo + 'static>(outcome: &str, balance: bool) -> Result<ArgValue> {
+        let mut registry = Registry::new();
+        let ty = registry.register_type(&meta_type::<T>()).id;
+        let registry = PortableRegistry::from(registry);
+        let token = Token::default();
+        let mut parser = Parser {
+            registry: &registry,
+            token: &token,
+            rest: outcome,
+        };
+        let value = parser.value(ty, balance)?;
+        assert_eq!(parser.rest.trim(), "", "{:?} is left over", parser.rest);
+        Ok(value)
+    }
+
+    fn some(value: ArgValue) -> ArgValue {
+        ArgValue::Option(Some(Box::new(value)))
+    }
+
+    #[test]
+    fn primitives_are_parsed() {
+        // when
+        let bool = |outcome| parse::<bool>(outcome, false).ok();
+        let string = |outcome| parse::<String>(outcome, false).ok();
+
+        // then
+        assert_eq!(bool("true"), Some(ArgValue::Bool(true)));
+        assert_eq!(bool("No"), Some(ArgValue::Bool(false)));
+        assert_eq!(bool("maybe"), None);
+        assert_eq!(
+            parse::<u32>("1,000", false).ok(),
+            Some(ArgValue::UInt(1_000))
+        );
+        assert_eq!(parse::<i64>("-5", false).ok(), Some(ArgValue::Int(-5)));
+        assert!(parse::<u8>("-1", false).is_err());
+        assert_eq!(
+            string("\"hello world\""),
+            Some(ArgValue::Str(String::from("hello world")))
+        );
+    }
+
+    #[test]
+    fn u128_values_are_balances_only_if_named_balance() {
+        // when
+        let balance = |outcome| parse::<u128>(outcome, true).expect("parsing failed");
+        let uint = |outcome| parse::<u128>(outcome, false).expect("parsing failed");
+        let named =
+            |outcome| parse::<InkBalance>(outcome, false).expect("parsing failed");
+
+        // then
+        // `UInt` and `Balance` of the same plancks are equal, so the variants are
+        // matched instead
+        let kilo_unit = Balance::plancks(1_000_000_000_000_000);
+        let is_kilo_unit =
+            |value| matches!(value, ArgValue::Balance(b) if b == kilo_unit);
+        assert!(is_kilo_unit(balance("1.0000 kUnit")));
+        assert!(is_kilo_unit(balance("1,000,000,000,000,000")));
+        assert!(is_kilo_unit(named("1.0000 kUnit")));
+        assert!(matches!(uint("1,000"), ArgValue::UInt(1_000)));
+        // the UIs might still display it in units of the token
+        assert!(matches!(
+            uint("1.0000 kUnit"),
+            ArgValue::UInt(1_000_000_000_000_000)
+        ));
+    }
+
+    #[test]
+    fn options_are_parsed_in_the_notations_of_both_uis() {
+        // when
+        let option =
+            |outcome| parse::<Option<u32>>(outcome, false).expect("parsing failed");
+
+        // then
+        for none in NONE_OUTCOMES {
+            assert_eq!(option(none), ArgValue::Option(None));
+        }
+        assert_eq!(option("Some(5)"), some(ArgValue::UInt(5)));
+        assert_eq!(option("{ Some: 5 }"), some(ArgValue::UInt(5)));
+        assert_eq!(option("5"), some(ArgValue::UInt(5)));
+    }
+
+    #[test]
+    fn results_are_parsed_in_the_notations_of_both_uis() {
+        // when
+        let result = |outcome| parse::<std::result::Result<u32, bool>>(outcome, false);
+
+        // then
+        let ok = ArgValue::Enum(String::from("Ok"), vec![ArgValue::UInt(5)]);
+        let err = ArgValue::Enum(String::from("Err"), vec![ArgValue::Bool(false)]);
+        assert_eq!(result("{ Ok: 5 }").ok(), Some(ok.clone()));
+        assert_eq!(result("Ok(5)").ok(), Some(ok.clone()));
+        assert_eq!(result("5").ok(), Some(ok));
+        assert_eq!(result("{ Err: false }").ok(), Some(err.clone()));
+        assert_eq!(result("Err(false)").ok(), Some(err));
+    }
+
+    #[test]
+    fn sequences_and_tuples_are_parsed_in_the_notations_of_both_uis() {
+        // when
+        let sequence =
+            |outcome| parse::<Vec<u16>>(outcome, false).expect("parsing failed");
+        let tuple =
+            |outcome| parse::<(bool, u32)>(outcome, false).expect("parsing failed");
+
+        // then
+        let elements = ArgValue::Vec(vec![
+            ArgValue::UInt(1),
+            ArgValue::UInt(2),
+            ArgValue::UInt(3),
+        ]);
+        assert_eq!(sequence("[ 1 2 3 ]"), elements);
+        assert_eq!(sequence("[1, 2, 3]"), elements);
+        assert_eq!(sequence("[]"), ArgValue::Vec(Vec::new()));
+        let pair = ArgValue::Vec(vec![ArgValue::Bool(true), ArgValue::UInt(5)]);
+        assert_eq!(tuple("(true, 5)"), pair);
+        assert_eq!(tuple("[ true 5 ]"), pair);
+        assert!(parse::<Vec<u16>>("[ 1 2", false).is_err());
+    }
+
+    #[test]
+    fn byte_arrays_are_parsed_including_shortened_ones() {
+        // when
+        let bytes = |outcome| parse::<[u8; 4]>(outcome, false);
+
+        // then
+        assert_eq!(
+            bytes("0x01020304").ok(),
+            Some(ArgValue::Bytes(vec![1, 2, 3, 4]))
+        );
+        assert_eq!(bytes("<empty>").ok(), Some(ArgValue::Bytes(vec![0; 4])));
+        assert_eq!(bytes("0x00…0000").ok(), Some(ArgValue::Bytes(vec![0; 4])));
+        assert!(bytes("0x01…0004").is_err());
+        assert!(bytes("0x010203").is_err());
+    }
+
+    #[test]
+    fn accounts_are_parsed_by_their_name() {
+        // when
+        let account =
+            |outcome| parse::<InkAccountId>(outcome, false).expect("parsing failed");
+
+        // then
+        assert_eq!(account("BOB"), ArgValue::account("BOB"));
+        assert_eq!(account("alice"), ArgValue::account("ALICE"));
+        assert_eq!(
+            account("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"),
+            ArgValue::account("ALICE")
+        );
+        assert!(parse::<InkAccountId>("nobody", false).is_err());
+    }
+}
@@ -0,0 +1,490 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The events which a backend observed as the result of a transaction.
+//!
+//! The browser UIs display events as status messages with a header such as
+//! `contracts.Terminated`, the `rpc` backend reads them from the block. Either
+//! way events are named `pallet.Variant`, with the pallet in lower camel case
+//! (e.g. `transactionPayment.TransactionFeePaid`).
+//!
+//! The events which contracts emit are read from the chain and decoded with the
+//! metadata of the contract, independent of the backend.
+
+use crate::uis::{
+    chain::ExtrinsicId,
+    ArgValue,
+    TransactionError,
+    TransactionResult,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+
+lazy_static! {
+    /// Matches the name of an event in a status message, e.g. `system.ExtrinsicSuccess`.
+    ///
+    /// Extrinsics are displayed the same way, but with a lower case call name
+    /// (e.g. `contracts.call`), so they are not matched.
+    static ref EVENT_NAME: Regex =
+        Regex::new(r"\b([a-z][A-Za-z0-9]*)\.([A-Z][A-Za-z0-9]*)\b")
+            .expect("event name regex is valid");
+}
+
+/// An event, e.g. `contracts.Terminated`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    /// The pallet which emitted the event, e.g. `contracts`.
+    pallet: String,
+    /// The variant of the event, e.g. `Terminated`.
+    variant: String,
+    /// The fields of the event, as far as the backend exposes them.
+    ///
+    /// The browser UIs do not display any fields.
+    fields: Vec<(String, String)>,
+}
+
+impl Event {
+    /// Creates a new `Event` of `pallet` and `variant`, without any fields.
+    pub fn new(pallet: &str, variant: &str) -> Self {
+        Self {
+            pallet: pallet.to_string(),
+            variant: variant.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Adds the field `name` with `value`.
+    pub fn push_field(mut self, name: &str, value: &str) -> Self {
+        self.fields.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Returns the pallet which emitted the event, e.g. `contracts`.
+    pub fn pallet(&self) -> &str {
+        &self.pallet
+    }
+
+    /// Returns the variant of the event, e.g. `Terminated`.
+    pub fn variant(&self) -> &str {
+        &self.variant
+    }
+
+    /// Returns the fields of the event.
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+
+    /// Returns the value of the field `name`.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns `true` if the event is named `name`, e.g. `contracts.Terminated`.
+    ///
+    /// The pallet is compared case-insensitive, so `Contracts.Terminated` matches
+    /// as well.
+    pub fn is(&self, name: &str) -> bool {
+        match name.split_once('.') {
+            Some((pallet, variant)) => {
+                self.pallet.eq_ignore_ascii_case(pallet) && self.variant == variant
+            }
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.pallet, self.variant)
+    }
+}
+
+/// An event emitted by a contract, e.g. the `Transfer` of an ERC-20 contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractEvent {
+    /// The address of the contract which emitted the event.
+    contract: String,
+    /// The label of the event in the contract metadata, e.g. `Transfer`.
+    label: String,
+    /// The decoded fields of the event, in the order of the metadata.
+    fields: Vec<(String, ArgValue)>,
+    /// The topics under which the event was published.
+    topics: Vec<[u8; 32]>,
+}
+
+impl ContractEvent {
+    /// Creates a new `ContractEvent`.
+    pub(crate) fn new(
+        contract: &str,
+        label: &str,
+        fields: Vec<(String, ArgValue)>,
+        topics: Vec<[u8; 32]>,
+    ) -> Self {
+        Self {
+            contract: contract.to_string(),
+            label: label.to_string(),
+            fields,
+            topics,
+        }
+    }
+
+    /// Returns the address of the contract which emitted the event.
+    pub fn contract(&self) -> &str {
+        &self.contract
+    }
+
+    /// Returns the label of the event, e.g. `Transfer`.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the decoded fields of the event.
+    pub fn fields(&self) -> &[(String, ArgValue)] {
+        &self.fields
+    }
+
+    /// Returns the value of the field `name`.
+    pub fn field(&self, name: &str) -> Option<&ArgValue> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the topics under which the event was published.
+    ///
+    /// For ink! 4 contracts the first topic is derived from the event's name,
+    /// followed by one topic for each field marked with `#[ink(topic)]`.
+    pub fn topics(&self) -> &[[u8; 32]] {
+        &self.topics
+    }
+}
+
+/// The events observed as the result of a transaction.
+#[derive(Debug, Default)]
+pub struct Events {
+    /// The events returned by the backend as a result of a transaction, in the
+    /// order in which they were emitted.
+    events: Vec<Event>,
+    /// The raw status messages displayed by the UI, these contain e.g. the
+    /// reason why a transaction was rejected by the transaction pool.
+    messages: Vec<String>,
+    /// The decoded events which contracts emitted during the transaction.
+    contract_events: Vec<ContractEvent>,
+    /// The debug output which the called contract printed.
+    debug_message: String,
+    /// The extrinsic which resulted in these events, if the backend knows it.
+    extrinsic: Option<ExtrinsicId>,
+}
+
+impl Events {
+    /// Creates a new `Events` instance.
+    pub fn new(events: Vec<Event>) -> Self {
+        Self {
+            events,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a new `Events` instance from the status messages of a browser UI,
+    /// given as pairs of header and status text.
+    ///
+    /// The UIs display the names of events and of dispatch errors (e.g.
+    /// `contracts.ContractNotFound`) in either of the two.
+    pub(crate) fn from_messages(messages: Vec<(String, String)>) -> Self {
+        let events = messages
+            .iter()
+            .flat_map(|(header, status)| {
+                EVENT_NAME
+                    .captures_iter(header)
+                    .chain(EVENT_NAME.captures_iter(status))
+            })
+            .map(|captures| Event::new(&captures[1], &captures[2]))
+            .collect();
+        let messages = messages
+            .into_iter()
+            .map(|(header, status)| format!("{} {}", header, status))
+            .collect();
+        Self {
+            events,
+            messages,
+            ..Default::default()
+        }
+    }
+
+    /// Returns `true` if an event named `name` (e.g. `contracts.Terminated`) is
+    /// contained in these events.
+    pub fn contains(&self, name: &str) -> bool {
+        self.find(name).is_some()
+    }
+
+    /// Returns the first event named `name`.
+    pub fn find(&self, name: &str) -> Option<&Event> {
+        self.events.iter().find(|event| event.is(name))
+    }
+
+    /// Returns the number of events named `name`.
+    pub fn count(&self, name: &str) -> usize {
+        self.events.iter().filter(|event| event.is(name)).count()
+    }
+
+    /// Returns an iterator over all events.
+    pub fn iter(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter()
+    }
+
+    /// Returns an iterator over the events emitted by `pallet`, e.g. `contracts`.
+    pub fn iter_pallet<'a>(&'a self, pallet: &'a str) -> impl Iterator<Item = &'a Event> {
+        self.iter()
+            .filter(move |event| event.pallet().eq_ignore_ascii_case(pallet))
+    }
+
+    /// Returns the number of events.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns `true` if there are no events.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Returns `true` if the events named `names` are contained in this order,
+    /// possibly with other events in between.
+    pub fn contains_in_order(&self, names: &[&str]) -> bool {
+        let mut events = self.events.iter();
+        names
+            .iter()
+            .all(|name| events.by_ref().any(|event| event.is(name)))
+    }
+
+    /// Asserts that the events named `names` are contained in this order,
+    /// possibly with other events in between.
+    ///
+    /// # Panics
+    ///
+    /// If one of the events is missing or out of order.
+    pub fn assert_in_order(&self, names: &[&str]) {
+        assert!(
+            self.contains_in_order(names),
+            "expected the events {:?} in this order, but found {}",
+            names,
+            self
+        );
+    }
+
+    /// Returns the decoded events which contracts emitted, in the order in which
+    /// they were emitted.
+    pub fn contract_events(&self) -> &[ContractEvent] {
+        &self.contract_events
+    }
+
+    /// Returns the first event with `label` (e.g. `Transfer`) which a contract
+    /// emitted.
+    pub fn find_contract_event(&self, label: &str) -> Option<&ContractEvent> {
+        self.contract_events
+            .iter()
+            .find(|event| event.label() == label)
+    }
+
+    /// Sets the decoded events which contracts emitted.
+    pub(crate) fn set_contract_events(&mut self, contract_events: Vec<ContractEvent>) {
+        self.contract_events = contract_events;
+    }
+
+    /// Returns the debug output which the called contract printed during the
+    /// transaction, e.g. via `ink::env::debug_println!`.
+    ///
+    /// The output is taken from a dry-run of the call right before the
+    /// transaction, it only contains what this call printed. Contracts only
+    /// print debug output when built in debug mode.
+    pub fn debug_message(&self) -> &str {
+        &self.debug_message
+    }
+
+    /// Sets the debug output which the called contract printed.
+    pub(crate) fn set_debug_message(&mut self, debug_message: String) {
+        self.debug_message = debug_message;
+    }
+
+    /// Returns the extrinsic which resulted in these events, if the backend
+    /// knows it. The browser UIs do not tell.
+    pub(crate) fn extrinsic(&self) -> Option<ExtrinsicId> {
+        self.extrinsic
+    }
+
+    /// Sets the extrinsic which resulted in these events.
+    pub(crate) fn set_extrinsic(&mut self, extrinsic: ExtrinsicId) {
+        self.extrinsic = Some(extrinsic);
+    }
+
+    /// Returns the outcome of the transaction which resulted in these events.
+    pub(crate) fn into_outcome(self) -> TransactionResult<Events> {
+        let success = self.contains("system.ExtrinsicSuccess");
+        let failure = self.contains("system.ExtrinsicFailed");
+        match (success, failure) {
+            (true, false) => Ok(self),
+            (false, true) if self.is_out_of_gas() => {
+                Err(TransactionError::OutOfGas(self))
+            }
+            (false, true) => Err(TransactionError::ExtrinsicFailed(self)),
+            (false, false) if self.message_contains("usurped") => {
+                Err(TransactionError::Usurped(self))
+            }
+            (false, false) => {
+                let rejection = self.messages.iter().find(|message| {
+                    ["Invalid Transaction", "Priority is too low", "outdated"]
+                        .iter()
+                        .any(|reason| message.contains(reason))
+                });
+                match rejection {
+                    Some(reason) => {
+                        Err(TransactionError::Invalid(reason.trim().to_string()))
+                    }
+                    None => Err(TransactionError::Ambiguous(self)),
+                }
+            }
+            (true, true) => Err(TransactionError::Ambiguous(self)),
+        }
+    }
+
+    /// Returns `true` if the transaction ran out of gas.
+    pub(crate) fn is_out_of_gas(&self) -> bool {
+        self.iter_pallet("contracts")
+            .any(|event| event.variant() == "OutOfGas")
+            || self.message_contains("OutOfGas")
+    }
+
+    /// Returns `true` if one of the raw status messages contains `text`.
+    pub(crate) fn message_contains(&self, text: &str) -> bool {
+        self.messages.iter().any(|message| message.contains(text))
+    }
+}
+
+impl fmt::Display for Events {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no events")
+        }
+        let names: Vec<_> = self.iter().map(ToString::to_string).collect();
+        write!(f, "[{}]", names.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(names: &[&str]) -> Events {
+        Events::new(
+            names
+                .iter()
+                .map(|name| {
+                    let (pallet, variant) = name.split_once('.').expect("no pallet");
+                    Event::new(pallet, variant)
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn events_are_matched_by_pallet_and_variant() {
+        // given
+        let event =
+            Event::new("contracts", "Terminated").push_field("beneficiary", "ALICE");
+
+        // then
+        assert!(event.is("contracts.Terminated"));
+        assert!(event.is("Contracts.Terminated"));
+        assert!(!event.is("contracts.terminated"));
+        assert!(!event.is("Terminated"));
+        assert_eq!(event.field("beneficiary"), Some("ALICE"));
+        assert_eq!(event.to_string(), "contracts.Terminated");
+    }
+
+    #[test]
+    fn events_are_found_in_the_status_messages() {
+        // given
+        let messages = vec![
+            (
+                String::from("system.ExtrinsicSuccess"),
+                String::from("contracts.call"),
+            ),
+            (
+                String::from("balances.Withdraw contracts.Called"),
+                String::from("ready"),
+            ),
+        ];
+
+        // when
+        let events = Events::from_messages(messages);
+
+        // then
+        let names: Vec<_> = events.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            names,
+            [
+                "system.ExtrinsicSuccess",
+                "balances.Withdraw",
+                "contracts.Called"
+            ]
+        );
+        assert_eq!(events.len(), 3);
+        assert!(events.message_contains("ready"));
+    }
+
+    #[test]
+    fn events_are_contained_in_order_with_others_in_between() {
+        // given
+        let events = events(&[
+            "balances.Withdraw",
+            "contracts.Called",
+            "balances.Transfer",
+            "contracts.Called",
+            "system.ExtrinsicSuccess",
+        ]);
+
+        // then
+        assert!(events.contains_in_order(&[]));
+        assert!(
+            events.contains_in_order(&["contracts.Called", "system.ExtrinsicSuccess"])
+        );
+        assert!(events.contains_in_order(&["contracts.Called", "contracts.Called"]));
+        assert!(
+            !events.contains_in_order(&["system.ExtrinsicSuccess", "contracts.Called"])
+        );
+        assert!(!events.contains_in_order(&[
+            "contracts.Called",
+            "contracts.Called",
+            "contracts.Called"
+        ]));
+        assert!(!events.contains_in_order(&["contracts.Terminated"]));
+        assert_eq!(events.count("contracts.Called"), 2);
+        events.assert_in_order(&["balances.Withdraw", "balances.Transfer"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected the events")]
+    fn events_out_of_order_fail_the_assertion() {
+        // given
+        let events = events(&["contracts.Called", "balances.Withdraw"]);
+
+        // then
+        events.assert_in_order(&["balances.Withdraw", "contracts.Called"]);
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reports the gas which the uploads and transactions of the tests use.
+//!
+//! One line is appended to `gas-usage.csv` in the artifacts directory of the run
+//! for every successful upload and transaction:
+//!
+//! ```csv
+//! <example>, <test>, <upload|transaction>, <constructor or message>, <estimated gas>, <consumed gas>
+//! ```
+//!
+//! The estimated gas is the `ref_time` which a dry-run requires, the consumed gas
+//! the `ref_time` of the extrinsic's weight in the `System::ExtrinsicSuccess`
+//! event. Either is left empty if it is not known. Attempts of a backend which
+//! were retried, e.g. because the transaction was usurped, are not reported.
+//!
+//! At the end of every test the report is summed up per example into
+//! `gas-usage-per-example.csv`:
+//!
+//! ```csv
+//! <example>, <gas>
+//! ```
+//!
+//! This is the format in which the CI stores the gas usage, it is compared
+//! between runs by the `csv-comparator`.
+
+use crate::utils::{
+    artifacts_dir,
+    example_and_test,
+    test_name,
+};
+use lazy_static::lazy_static;
+use std::{
+    collections::BTreeMap,
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+    sync::Mutex,
+};
+
+lazy_static! {
+    /// Serializes the writes of all tests to the report.
+    static ref REPORT: Mutex<()> = Mutex::new(());
+}
+
+/// The gas which one upload or transaction used.
+pub(crate) struct GasUsage {
+    /// Either `upload` or `transaction`.
+    pub(crate) step: &'static str,
+    /// The label of the constructor or message.
+    pub(crate) label: String,
+    pub(crate) estimated: Option<u64>,
+    pub(crate) consumed: Option<u64>,
+}
+
+/// Returns the path of the report.
+fn report_path() -> PathBuf {
+    artifacts_dir().join("gas-usage.csv")
+}
+
+/// Returns the path of the report summed up per example.
+fn summary_path() -> PathBuf {
+    artifacts_dir().join("gas-usage-per-example.csv")
+}
+
+/// Appends `usage` to the report, attributed to the test which is currently
+/// executed.
+///
+/// Errors are only logged, they must not fail the test.
+pub(crate) fn record(usage: GasUsage) {
+    let (example, test) = example_and_test();
+    let render = |gas: Option<u64>| gas.map(|gas| gas.to_string()).unwrap_or_default();
+    let line = format!(
+        "{}, {}, {}, {}, {}, {}\n",
+        example,
+        test,
+        usage.step,
+        usage.label,
+        render(usage.estimated),
+        render(usage.consumed)
+    );
+    log::info!(
+        "[{}] gas usage of {} {:?}: estimated {:?}, consumed {:?}",
+        test_name(),
+        usage.step,
+        usage.label,
+        usage.estimated,
+        usage.consumed
+    );
+
+    let _guard = REPORT.lock().expect("failed locking `REPORT`");
+    let written = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(report_path())
+        .and_then(|mut report| report.write_all(line.as_bytes()));
+    if let Err(err) = written {
+        log::error!(
+            "[{}] unable to write gas usage to {:?}: {}",
+            test_name(),
+            report_path(),
+            err
+        );
+    }
+}
+
+/// Writes the report summed up per example to `gas-usage-per-example.csv`.
+///
+/// The gas of an example is the sum of the estimated gas of its uploads and
+/// transactions, the consumed gas is taken if the estimate is not known. The
+/// examples in `INK_EXAMPLES_PATH` which did not use any gas are listed with `0`.
+///
+/// Errors are only logged, they must not fail the test.
+pub fn save_summary() {
+    let _guard = REPORT.lock().expect("failed locking `REPORT`");
+    let mut summary: BTreeMap<String, u128> = std::env::var("INK_EXAMPLES_PATH")
+        .ok()
+        .and_then(|path| std::fs::read_dir(path).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| (entry.file_name().to_string_lossy().into_owned(), 0))
+        .collect();
+    let report = std::fs::read_to_string(report_path()).unwrap_or_default();
+    for line in report.lines() {
+        let fields: Vec<_> = line.split(',').map(str::trim).collect();
+        let [example, _test, _step, _label, estimated, consumed] = fields[..] else {
+            log::error!(
+                "[{}] skipping malformed line {:?} of {:?}",
+                test_name(),
+                line,
+                report_path()
+            );
+            continue
+        };
+        let gas = estimated.parse().or_else(|_| consumed.parse()).unwrap_or(0);
+        *summary.entry(example.to_string()).or_default() += gas;
+    }
+
+    let lines: String = summary
+        .iter()
+        .map(|(example, gas)| format!("{}, {}\n", example, gas))
+        .collect();
+    if let Err(err) = std::fs::write(summary_path(), lines) {
+        log::error!(
+            "[{}] unable to write gas usage to {:?}: {}",
+            test_name(),
+            summary_path(),
+            err
+        );
+    }
+}
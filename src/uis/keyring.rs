@@ -0,0 +1,444 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The accounts which sign transactions or are passed as arguments.
+//!
+//! Tests refer to accounts by name (e.g. `BOB`) or by their SS58 address. The
+//! keyring of every test starts out with the dev accounts `ALICE` to `FERDIE`,
+//! further accounts can be derived from a secret URI (e.g. `//Oscar` or
+//! `<phrase>//hard/soft///password`) and registered under a name via
+//! [`register`]. The browser UIs only know the dev accounts, so registered
+//! accounts can be passed as arguments with every backend, but they can only
+//! sign transactions via the `rpc` backend.
+//!
+//! Like the test name, the keyring is kept per test thread. With the `rpc`
+//! backend every test signs with its own fresh accounts instead of the dev
+//! accounts, see [`allocate`], so that the nonces of concurrently running tests
+//! do not collide.
+
+use crate::{
+    uis::Result,
+    utils,
+};
+use codec::Encode;
+use convert_case::{
+    Case,
+    Casing,
+};
+use hmac::Hmac;
+use rand::Rng;
+use sha2::Sha512;
+use std::{
+    cell::RefCell,
+    str::FromStr,
+};
+use subxt::{
+    tx::Signer,
+    utils::{
+        AccountId32,
+        MultiAddress,
+        MultiSignature,
+    },
+    PolkadotConfig,
+};
+use subxt_signer::{
+    bip39::Mnemonic,
+    ecdsa,
+    sr25519,
+    DeriveJunction,
+    ExposeSecret,
+    SecretUri,
+};
+
+/// The names of the dev accounts which are available in the UIs. Each is derived
+/// from the dev phrase with its name as hard junction, e.g. `//Alice`.
+pub(crate) const DEV_ACCOUNTS: [&str; 6] =
+    ["ALICE", "BOB", "CHARLIE", "DAVE", "EVE", "FERDIE"];
+
+thread_local! {
+    /// The keyring of the currently executing test.
+    static KEYRING: RefCell<Keyring> = RefCell::new(Keyring::dev());
+}
+
+/// The signature schemes which accounts can use.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyType {
+    /// Schnorr signatures on Ristretto, the default of Substrate chains.
+    #[default]
+    Sr25519,
+    /// Edwards-curve signatures.
+    Ed25519,
+    /// ECDSA signatures on secp256k1, the account id is the hash of the public key.
+    Ecdsa,
+}
+
+impl FromStr for KeyType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sr25519" => Ok(KeyType::Sr25519),
+            "ed25519" => Ok(KeyType::Ed25519),
+            "ecdsa" => Ok(KeyType::Ecdsa),
+            _ => Err(format!("unknown key type {:?}", s)),
+        }
+    }
+}
+
+/// A keypair of one of the supported [`KeyType`]s.
+#[derive(Clone, Debug)]
+pub enum Keypair {
+    Sr25519(sr25519::Keypair),
+    Ed25519(ed25519_zebra::SigningKey),
+    Ecdsa(ecdsa::Keypair),
+}
+
+impl Keypair {
+    /// Derives the keypair of `key_type` from the secret URI `uri`, the same as
+    /// `subkey` does. If the URI has no phrase the dev phrase is used, so that
+    /// `//Alice` yields the dev account `ALICE`.
+    pub fn from_uri(uri: &str, key_type: KeyType) -> Result<Self> {
+        let uri = SecretUri::from_str(uri)
+            .map_err(|err| format!("invalid secret URI: {}", err))?;
+        let keypair = match key_type {
+            KeyType::Sr25519 => Keypair::Sr25519(sr25519::Keypair::from_uri(&uri)?),
+            KeyType::Ed25519 => Keypair::Ed25519(ed25519_from_uri(&uri)?),
+            KeyType::Ecdsa => Keypair::Ecdsa(ecdsa::Keypair::from_uri(&uri)?),
+        };
+        Ok(keypair)
+    }
+
+    /// Returns the account id of the keypair.
+    pub fn account_id(&self) -> AccountId32 {
+        match self {
+            Keypair::Sr25519(keypair) => keypair.public_key().to_account_id(),
+            Keypair::Ed25519(key) => {
+                AccountId32(ed25519_zebra::VerificationKey::from(key).into())
+            }
+            Keypair::Ecdsa(keypair) => keypair.public_key().to_account_id(),
+        }
+    }
+}
+
+impl Signer<PolkadotConfig> for Keypair {
+    fn account_id(&self) -> AccountId32 {
+        Keypair::account_id(self)
+    }
+
+    fn address(&self) -> MultiAddress<AccountId32, ()> {
+        MultiAddress::Id(Keypair::account_id(self))
+    }
+
+    fn sign(&self, signer_payload: &[u8]) -> MultiSignature {
+        match self {
+            Keypair::Sr25519(keypair) => keypair.sign(signer_payload).into(),
+            Keypair::Ed25519(key) => {
+                MultiSignature::Ed25519(key.sign(signer_payload).into())
+            }
+            Keypair::Ecdsa(keypair) => keypair.sign(signer_payload).into(),
+        }
+    }
+}
+
+/// Named accounts, which are resolved to their account ids and back.
+struct Keyring {
+    /// The accounts by their upper case name.
+    accounts: Vec<(String, Keypair)>,
+}
+
+impl Keyring {
+    /// Returns a keyring which holds the dev accounts.
+    fn dev() -> Self {
+        let accounts = DEV_ACCOUNTS
+            .iter()
+            .map(|name| {
+                let uri = format!("//{}", name.to_case(Case::Title));
+                let keypair = Keypair::from_uri(&uri, KeyType::Sr25519)
+                    .expect("dev accounts are derived from a valid URI");
+                (name.to_string(), keypair)
+            })
+            .collect();
+        Self { accounts }
+    }
+
+    /// Registers `keypair` under `name`. Neither the name nor the account may be
+    /// registered already.
+    fn register(&mut self, name: &str, keypair: Keypair) -> Result<AccountId32> {
+        let name = name.to_uppercase();
+        if self.keypair(&name).is_some() {
+            return Err(format!("account {:?} is registered already", name).into())
+        }
+        let account = keypair.account_id();
+        if let Some(other) = self.name(&account) {
+            return Err(format!(
+                "account {} is registered already as {:?}",
+                account, other
+            )
+            .into())
+        }
+        self.accounts.push((name, keypair));
+        Ok(account)
+    }
+
+    /// Replaces the keypair of the account `name`.
+    fn replace(&mut self, name: &str, keypair: Keypair) {
+        match self
+            .accounts
+            .iter_mut()
+            .find(|(known, _)| known.eq_ignore_ascii_case(name))
+        {
+            Some((_, known)) => *known = keypair,
+            None => self.accounts.push((name.to_uppercase(), keypair)),
+        }
+    }
+
+    /// Returns the keypair of the account `name`.
+    fn keypair(&self, name: &str) -> Option<&Keypair> {
+        self.accounts
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(name))
+            .map(|(_, keypair)| keypair)
+    }
+
+    /// Returns the name of `account`, if it is known.
+    fn name(&self, account: &AccountId32) -> Option<&str> {
+        self.accounts
+            .iter()
+            .find(|(_, keypair)| keypair.account_id() == *account)
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// Derives the account of `key_type` from the secret URI `uri` and registers it
+/// under `name` for the current test. Returns the account id.
+///
+/// Afterwards `name` can be used like the name of a dev account, e.g. as the
+/// caller of a transaction or as an account argument.
+pub fn register(name: &str, uri: &str, key_type: KeyType) -> Result<AccountId32> {
+    let keypair = Keypair::from_uri(uri, key_type)?;
+    KEYRING.with(|keyring| keyring.borrow_mut().register(name, keypair))
+}
+
+/// Replaces the dev accounts in the keyring of the current test by fresh
+/// accounts and returns their account ids.
+///
+/// The fresh accounts are derived from a random seed with the junction of the
+/// dev account, e.g. `0x…//Alice`. They are still named `ALICE` to `FERDIE`,
+/// so the tests do not notice the difference. They need to be funded before use.
+pub(crate) fn allocate() -> Result<Vec<AccountId32>> {
+    let seed: [u8; 32] = rand::thread_rng().gen();
+    KEYRING.with(|keyring| {
+        let mut keyring = keyring.borrow_mut();
+        DEV_ACCOUNTS
+            .iter()
+            .map(|name| {
+                let uri =
+                    format!("0x{}//{}", hex::encode(seed), name.to_case(Case::Title));
+                let keypair = Keypair::from_uri(&uri, KeyType::Sr25519)?;
+                let account = keypair.account_id();
+                keyring.replace(name, keypair);
+                Ok(account)
+            })
+            .collect()
+    })
+}
+
+/// Returns the keypair of the faucet which funds the accounts of each test,
+/// configured via [`utils::faucet_uri`].
+pub(crate) fn faucet() -> Result<Keypair> {
+    Keypair::from_uri(&utils::faucet_uri(), KeyType::Sr25519)
+}
+
+/// Returns the keypair of the account `name`, or of `ALICE` if no name is given.
+pub(crate) fn keypair(name: Option<&str>) -> Result<Keypair> {
+    let name = name.unwrap_or("ALICE");
+    KEYRING.with(|keyring| {
+        keyring
+            .borrow()
+            .keypair(name)
+            .cloned()
+            .ok_or_else(|| format!("{:?} is not a known account", name).into())
+    })
+}
+
+/// Resolves `account` to an account id. `account` is either the name of an
+/// account in the keyring (e.g. `ALICE`) or an SS58 address.
+pub fn resolve_account(account: &str) -> Result<AccountId32> {
+    let known = KEYRING
+        .with(|keyring| keyring.borrow().keypair(account).map(Keypair::account_id));
+    match known {
+        Some(account_id) => Ok(account_id),
+        None => {
+            AccountId32::from_str(account).map_err(|err| {
+                format!("{:?} is not a valid account: {:?}", account, err).into()
+            })
+        }
+    }
+}
+
+/// Returns the name of `account` in the keyring, or its SS58 address if it is
+/// not known.
+pub fn account_name(account: &AccountId32) -> String {
+    KEYRING.with(|keyring| {
+        keyring
+            .borrow()
+            .name(account)
+            .map(ToString::to_string)
+            .unwrap_or_else(|| account.to_string())
+    })
+}
+
+/// Returns `true` if `name` is one of the dev accounts, which the browser UIs
+/// know by name.
+pub(crate) fn is_dev_account(name: &str) -> bool {
+    DEV_ACCOUNTS
+        .iter()
+        .any(|dev_account| dev_account.eq_ignore_ascii_case(name))
+}
+
+/// Derives an ed25519 key from `uri` the way `sp_core::ed25519::Pair` does.
+/// Only hard junctions are supported by ed25519.
+fn ed25519_from_uri(uri: &SecretUri) -> Result<ed25519_zebra::SigningKey> {
+    let phrase = uri.phrase.expose_secret();
+    let mut seed: [u8; 32] = match phrase.strip_prefix("0x") {
+        Some(hex_seed) => {
+            hex::decode(hex_seed)?
+                .try_into()
+                .map_err(|_| "an ed25519 seed must have 32 bytes")?
+        }
+        None => {
+            let mnemonic = Mnemonic::parse(phrase.as_str())
+                .map_err(|err| format!("invalid phrase: {}", err))?;
+            let password = uri
+                .password
+                .as_ref()
+                .map(|password| password.expose_secret().as_str())
+                .unwrap_or_default();
+            // The same as `substrate_bip39::mini_secret_from_entropy`.
+            let salt = format!("mnemonic{}", password);
+            let mut seed = [0u8; 64];
+            pbkdf2::pbkdf2::<Hmac<Sha512>>(
+                &mnemonic.to_entropy(),
+                salt.as_bytes(),
+                2048,
+                &mut seed,
+            )
+            .map_err(|err| format!("unable to derive seed: {}", err))?;
+            seed[..32].try_into().expect("seed has 64 bytes")
+        }
+    };
+    for junction in &uri.junctions {
+        match junction {
+            DeriveJunction::Hard(chain_code) => {
+                seed = ("Ed25519HDKD", seed, chain_code)
+                    .using_encoded(sp_core_hashing::blake2_256);
+            }
+            DeriveJunction::Soft(_) => {
+                return Err("ed25519 keys can not be derived with soft junctions".into())
+            }
+        }
+    }
+    Ok(ed25519_zebra::SigningKey::from(seed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The dev phrase, which a secret URI without a phrase implies.
+    const DEV_PHRASE: &str =
+        "bottom drive obey lake curtain smoke basket hold race lonely fit walk";
+
+    fn address(uri: &str, key_type: KeyType) -> String {
+        Keypair::from_uri(uri, key_type)
+            .expect("deriving keypair failed")
+            .account_id()
+            .to_string()
+    }
+
+    #[test]
+    fn sr25519_dev_accounts_are_derived_like_subkey() {
+        // when
+        let alice = address("//Alice", KeyType::Sr25519);
+        let bob = address("//Bob", KeyType::Sr25519);
+
+        // then
+        assert_eq!(alice, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY");
+        assert_eq!(bob, "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty");
+    }
+
+    #[test]
+    fn ed25519_dev_accounts_are_derived_like_subkey() {
+        // when
+        let alice = address("//Alice", KeyType::Ed25519);
+        let bob = address("//Bob", KeyType::Ed25519);
+
+        // then
+        assert_eq!(alice, "5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpu");
+        assert_eq!(bob, "5GoNkf6WdbxCFnPdAnYYQyCjAKPJgLNxXwPjwTh6DGg6gN3E");
+    }
+
+    #[test]
+    fn uris_without_phrase_imply_the_dev_phrase() {
+        for key_type in [KeyType::Sr25519, KeyType::Ed25519] {
+            // when
+            let implied = address("//Alice", key_type);
+            let explicit = address(&format!("{}//Alice", DEV_PHRASE), key_type);
+
+            // then
+            assert_eq!(implied, explicit);
+        }
+    }
+
+    #[test]
+    fn passwords_and_junctions_derive_different_accounts() {
+        for key_type in [KeyType::Sr25519, KeyType::Ed25519] {
+            // when
+            let alice = address("//Alice", key_type);
+            let with_password = address("//Alice///password", key_type);
+            let nested = address("//Alice//stash", key_type);
+
+            // then
+            assert_ne!(alice, with_password);
+            assert_ne!(alice, nested);
+            assert_ne!(with_password, nested);
+        }
+    }
+
+    #[test]
+    fn ed25519_keys_can_be_derived_from_a_seed() {
+        // given
+        let seed = format!("0x{}", "00".repeat(32));
+
+        // when
+        let keypair =
+            Keypair::from_uri(&seed, KeyType::Ed25519).expect("deriving keypair failed");
+
+        // then
+        assert_eq!(
+            hex::encode(keypair.account_id().0),
+            "3b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29"
+        );
+    }
+
+    #[test]
+    fn ed25519_keys_can_not_be_derived_with_soft_junctions() {
+        // when
+        let keypair = Keypair::from_uri("//Alice/soft", KeyType::Ed25519);
+
+        // then
+        assert!(keypair.is_err());
+        assert!(Keypair::from_uri("//Alice/soft", KeyType::Sr25519).is_ok());
+    }
+}
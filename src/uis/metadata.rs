@@ -0,0 +1,1091 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A typed view on `.contract` bundles, i.e. the metadata of a contract together
+//! with its Wasm code.
+//!
+//! The bundle is validated when it is loaded, everything which needs metadata ‒
+//! the selectors and arguments of messages, decoding return values and events ‒
+//! builds on [`ContractBundle`].
+
+use crate::uis::{
+    Call,
+    Result,
+    Upload,
+};
+use convert_case::{
+    Case,
+    Casing,
+};
+use scale_info::{
+    form::PortableForm,
+    PortableRegistry,
+    Type,
+    TypeDef,
+    TypeDefPrimitive,
+};
+use serde::{
+    Deserialize,
+    Deserializer,
+};
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    path::Path,
+};
+
+/// The versions of the metadata format which can be parsed, ink! 4 writes
+/// version 4 and ink! 5 version 5.
+const SUPPORTED_VERSIONS: [u32; 2] = [4, 5];
+
+/// A `.contract` bundle.
+#[derive(Deserialize)]
+pub(crate) struct ContractBundle {
+    /// The version of the metadata format.
+    #[serde(deserialize_with = "deserialize_version")]
+    pub(crate) version: u32,
+    pub(crate) source: Source,
+    pub(crate) contract: Contract,
+    pub(crate) spec: Spec,
+    pub(crate) storage: Storage,
+    #[serde(flatten)]
+    pub(crate) registry: PortableRegistry,
+}
+
+/// Where the contract comes from and how it was built.
+#[derive(Deserialize)]
+pub(crate) struct Source {
+    /// The hash of the Wasm code, as hex string with a `0x` prefix.
+    pub(crate) hash: String,
+    /// The language of the contract, e.g. `ink! 4.3.0`.
+    pub(crate) language: String,
+    /// The compiler of the contract, e.g. `rustc 1.72.0`.
+    pub(crate) compiler: String,
+    /// The Wasm code, as hex string with a `0x` prefix. Missing in a plain
+    /// metadata `.json` file.
+    pub(crate) wasm: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Contract {
+    pub(crate) name: String,
+    pub(crate) version: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Spec {
+    pub(crate) constructors: Vec<MessageSpec>,
+    pub(crate) messages: Vec<MessageSpec>,
+    #[serde(default)]
+    pub(crate) events: Vec<EventSpec>,
+}
+
+/// A constructor or a message.
+#[derive(Deserialize)]
+pub(crate) struct MessageSpec {
+    pub(crate) label: String,
+    pub(crate) selector: String,
+    pub(crate) args: Vec<ArgSpec>,
+    /// If the constructor or message accepts a payment.
+    #[serde(default)]
+    pub(crate) payable: bool,
+    /// If the message mutates the storage, constructors always do.
+    #[serde(default)]
+    pub(crate) mutates: bool,
+    #[serde(default)]
+    pub(crate) default: bool,
+    #[serde(rename = "returnType")]
+    pub(crate) return_type: Option<TypeSpec>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct EventSpec {
+    pub(crate) label: String,
+    pub(crate) args: Vec<ArgSpec>,
+    /// The topic which identifies the event, since ink! 5. Before, events are
+    /// identified by their index in `spec.events`.
+    #[serde(default)]
+    pub(crate) signature_topic: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ArgSpec {
+    pub(crate) label: String,
+    #[serde(rename = "type")]
+    pub(crate) ty: TypeSpec,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TypeSpec {
+    #[serde(rename = "type")]
+    pub(crate) ty: u32,
+    /// The name of the type in the contract's source, e.g. `["AccountId"]`.
+    #[serde(rename = "displayName", default)]
+    pub(crate) display_name: Vec<String>,
+}
+
+/// The storage layout of the contract.
+#[derive(Deserialize)]
+pub(crate) struct Storage {
+    pub(crate) root: RootLayout,
+}
+
+/// How a value is laid out in the contract's storage.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Layout {
+    Root(RootLayout),
+    Leaf(LeafLayout),
+    Hash(HashLayout),
+    Array(ArrayLayout),
+    Struct(StructLayout),
+    Enum(EnumLayout),
+}
+
+/// A value which is stored under a key of its own, e.g. a `Mapping`.
+#[derive(Deserialize)]
+pub(crate) struct RootLayout {
+    pub(crate) root_key: String,
+    pub(crate) layout: Box<Layout>,
+    /// The type of the value, since ink! 5.
+    #[serde(default)]
+    pub(crate) ty: Option<u32>,
+}
+
+/// A value of the type `ty`, stored under `key`.
+#[derive(Deserialize)]
+pub(crate) struct LeafLayout {
+    pub(crate) key: String,
+    pub(crate) ty: u32,
+}
+
+/// Values which are stored under keys hashed from `offset`.
+#[derive(Deserialize)]
+pub(crate) struct HashLayout {
+    pub(crate) offset: String,
+    pub(crate) layout: Box<Layout>,
+}
+
+/// Values which are stored under consecutive keys, starting at `offset`.
+#[derive(Deserialize)]
+pub(crate) struct ArrayLayout {
+    pub(crate) offset: String,
+    pub(crate) layout: Box<Layout>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct StructLayout {
+    pub(crate) name: String,
+    pub(crate) fields: Vec<FieldLayout>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct FieldLayout {
+    pub(crate) name: Option<String>,
+    pub(crate) layout: Layout,
+}
+
+/// An enum, the variant is stored under `dispatch_key`.
+#[derive(Deserialize)]
+pub(crate) struct EnumLayout {
+    pub(crate) name: String,
+    #[serde(rename = "dispatchKey")]
+    pub(crate) dispatch_key: String,
+    /// The variants by their index.
+    pub(crate) variants: BTreeMap<String, StructLayout>,
+}
+
+impl Layout {
+    /// Checks that all keys of the layout at `path` are 4 bytes as hex and that
+    /// all types exist in `registry`.
+    fn validate(&self, registry: &PortableRegistry, path: &str) -> Result<()> {
+        match self {
+            Layout::Root(root) => root.validate(registry, path),
+            Layout::Leaf(leaf) => {
+                check_key(&leaf.key, path)?;
+                resolve(registry, leaf.ty)
+                    .map_err(|err| format!("`{}`: {}", path, err))?;
+                Ok(())
+            }
+            Layout::Hash(HashLayout { offset, layout })
+            | Layout::Array(ArrayLayout { offset, layout }) => {
+                check_key(offset, path)?;
+                layout.validate(registry, path)
+            }
+            Layout::Struct(layout) => layout.validate(registry, path),
+            Layout::Enum(layout) => {
+                let path = format!("{}.{}", path, layout.name);
+                check_key(&layout.dispatch_key, &path)?;
+                for (index, variant) in &layout.variants {
+                    variant.validate(registry, &format!("{}.{}", path, index))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl RootLayout {
+    /// Checks the root key and the type of the value, see [`Layout::validate`].
+    fn validate(&self, registry: &PortableRegistry, path: &str) -> Result<()> {
+        check_key(&self.root_key, path)?;
+        if let Some(ty) = self.ty {
+            resolve(registry, ty).map_err(|err| format!("`{}`: {}", path, err))?;
+        }
+        self.layout.validate(registry, path)
+    }
+}
+
+impl StructLayout {
+    /// Checks the layouts of all fields, see [`Layout::validate`].
+    fn validate(&self, registry: &PortableRegistry, path: &str) -> Result<()> {
+        let path = format!("{}.{}", path, self.name);
+        for (index, field) in self.fields.iter().enumerate() {
+            let name = field.name.clone().unwrap_or_else(|| index.to_string());
+            field
+                .layout
+                .validate(registry, &format!("{}.{}", path, name))?;
+        }
+        Ok(())
+    }
+}
+
+/// Fails if the storage key `key` of the layout at `path` is not 4 bytes as hex.
+fn check_key(key: &str, path: &str) -> Result<()> {
+    if is_hex(key, 4) {
+        Ok(())
+    } else {
+        Err(format!("`{}`: the key must be 4 bytes as hex, got {:?}", path, key).into())
+    }
+}
+
+/// Deserializes the metadata version, which ink! 4 writes as a string and
+/// ink! 5 as a number.
+fn deserialize_version<'de, D>(deserializer: D) -> std::result::Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Version {
+        Number(u32),
+        String(String),
+    }
+    match Version::deserialize(deserializer)? {
+        Version::Number(version) => Ok(version),
+        Version::String(version) => {
+            version.parse().map_err(|_| {
+                serde::de::Error::custom(format!("invalid version {:?}", version))
+            })
+        }
+    }
+}
+
+impl ContractBundle {
+    /// Reads and validates the contract bundle at `path`.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read(path).map_err(|err| {
+            format!("unable to open contract bundle {:?}: {}", path, err)
+        })?;
+        Self::parse(&json)
+            .map_err(|err| format!("invalid contract bundle {:?}: {}", path, err).into())
+    }
+
+    /// Parses and validates the contract bundle `json`.
+    pub(crate) fn parse(json: &[u8]) -> Result<Self> {
+        // the version is checked first, the rest of older formats differs too
+        #[derive(Deserialize)]
+        struct Versioned {
+            #[serde(default, deserialize_with = "deserialize_optional_version")]
+            version: Option<u32>,
+        }
+        let versioned: Versioned = serde_json::from_slice(json)
+            .map_err(|err| format!("not well-formatted: {}", err))?;
+        match versioned.version {
+            Some(version) if SUPPORTED_VERSIONS.contains(&version) => (),
+            Some(version) => {
+                return Err(format!(
+                    "unsupported metadata version {}, supported are {:?}",
+                    version, SUPPORTED_VERSIONS
+                )
+                .into())
+            }
+            None => {
+                return Err(format!(
+                "no metadata `version`, metadata of ink! 3 or older is not supported, \
+                    supported are versions {:?}",
+                SUPPORTED_VERSIONS
+            )
+                .into())
+            }
+        }
+
+        let bundle: Self = serde_json::from_slice(json)
+            .map_err(|err| format!("does not match the metadata schema: {}", err))?;
+        bundle.validate()?;
+        Ok(bundle)
+    }
+
+    /// Checks what the schema alone does not, i.e. the format of hashes, selectors
+    /// and storage keys and that all referenced types exist.
+    fn validate(&self) -> Result<()> {
+        if !self.source.language.starts_with("ink!") {
+            return Err(format!(
+                "only ink! contracts are supported, the language is {:?}",
+                self.source.language
+            )
+            .into())
+        }
+        if !is_hex(&self.source.hash, 32) {
+            return Err(format!(
+                "`source.hash` must be 32 bytes as hex, got {:?}",
+                self.source.hash
+            )
+            .into())
+        }
+        if let Some(wasm) = &self.source.wasm {
+            if !wasm.starts_with("0x") {
+                return Err("`source.wasm` must be hex with a `0x` prefix".into())
+            }
+        }
+        for (kind, specs) in [
+            ("constructors", &self.spec.constructors),
+            ("messages", &self.spec.messages),
+        ] {
+            for spec in specs {
+                if !is_hex(&spec.selector, 4) {
+                    return Err(format!(
+                        "`spec.{}.{}.selector` must be 4 bytes as hex, got {:?}",
+                        kind, spec.label, spec.selector
+                    )
+                    .into())
+                }
+                let types = spec
+                    .args
+                    .iter()
+                    .map(|arg| (arg.label.as_str(), &arg.ty))
+                    .chain(spec.return_type.iter().map(|ty| ("returnType", ty)));
+                for (label, ty) in types {
+                    resolve(&self.registry, ty.ty).map_err(|err| {
+                        format!("`spec.{}.{}.{}`: {}", kind, spec.label, label, err)
+                    })?;
+                }
+            }
+        }
+        for event in &self.spec.events {
+            for arg in &event.args {
+                resolve(&self.registry, arg.ty.ty).map_err(|err| {
+                    format!("`spec.events.{}.{}`: {}", event.label, arg.label, err)
+                })?;
+            }
+        }
+        self.storage.root.validate(&self.registry, "storage.root")
+    }
+
+    /// Returns the constructor with `name`.
+    ///
+    /// If no name is given, the constructor marked as `default` is returned or
+    /// else the first one ‒ this is what the UIs select by default.
+    pub(crate) fn constructor(&self, name: Option<&str>) -> Result<&MessageSpec> {
+        let constructors = &self.spec.constructors;
+        let constructor = match name {
+            Some(name) => {
+                constructors
+                    .iter()
+                    .find(|constructor| normalize(&constructor.label) == normalize(name))
+            }
+            None => {
+                constructors
+                    .iter()
+                    .find(|constructor| constructor.default)
+                    .or_else(|| constructors.first())
+            }
+        };
+        constructor.ok_or_else(|| {
+            format!(
+                "constructor {:?} does not exist in contract {}, its constructors are: {}",
+                name.unwrap_or_default(),
+                self.contract.name,
+                labels(constructors)
+            )
+            .into()
+        })
+    }
+
+    /// Returns the message with `name`.
+    pub(crate) fn message(&self, name: &str) -> Result<&MessageSpec> {
+        self.spec
+            .messages
+            .iter()
+            .find(|message| normalize(&message.label) == normalize(name))
+            .ok_or_else(|| {
+                format!(
+                    "message {:?} does not exist in contract {}, its messages are: {}",
+                    name,
+                    self.contract.name,
+                    labels(&self.spec.messages)
+                )
+                .into()
+            })
+    }
+
+    /// Checks that the constructor of `upload` exists and that `upload` passes
+    /// exactly its arguments.
+    ///
+    /// A `value` which is not an argument of the constructor is the endowment,
+    /// the UIs name the field for it like this. It is only accepted by payable
+    /// constructors.
+    pub(crate) fn check_upload(&self, upload: &Upload) -> Result<()> {
+        let constructor = self.constructor(upload.constructor.as_deref())?;
+        let what = format!(
+            "constructor {} of contract {}",
+            constructor.label, self.contract.name
+        );
+        if !constructor.payable
+            && upload
+                .initial_values
+                .iter()
+                .any(|(key, _)| is_endowment(constructor, key))
+        {
+            return Err(format!(
+                "{} is not payable, but an endowment `value` is set",
+                what
+            )
+            .into())
+        }
+        let keys = upload
+            .initial_values
+            .iter()
+            .chain(&upload.items)
+            .map(|(key, _)| key.as_str())
+            .filter(|key| !is_endowment(constructor, key));
+        check_args(&what, constructor, keys)
+    }
+
+    /// Checks that the message of `call` exists, that `call` passes exactly its
+    /// arguments and that it only pays for a payable message.
+    ///
+    /// A payable message might also be called without a payment.
+    pub(crate) fn check_call(&self, call: &Call) -> Result<()> {
+        let message = self.message(&call.method)?;
+        let what = format!(
+            "message {} of contract {}",
+            message.label, self.contract.name
+        );
+        if let (false, Some(payment)) = (message.payable, &call.payment) {
+            return Err(format!(
+                "{} is not payable, but a payment of {} is set",
+                what, payment
+            )
+            .into())
+        }
+        let keys = call
+            .values
+            .iter()
+            .chain(&call.items)
+            .map(|(key, _)| key.as_str());
+        check_args(&what, message, keys)
+    }
+}
+
+/// Checks that `keys` name all arguments of `spec` and nothing else, `what`
+/// describes `spec` in the errors.
+///
+/// A key might carry the type of the argument as a suffix (e.g. `to: AccountId`),
+/// which then has to match the type in the metadata.
+fn check_args<'a>(
+    what: &str,
+    spec: &MessageSpec,
+    keys: impl Iterator<Item = &'a str>,
+) -> Result<()> {
+    let mut passed = Vec::new();
+    for key in keys {
+        let arg = spec
+            .args
+            .iter()
+            .find(|arg| normalize(&arg.label) == normalize_arg(key))
+            .ok_or_else(|| {
+                format!(
+                    "{} has no argument {:?}, its arguments are: {}",
+                    what,
+                    key,
+                    signature(spec.args.iter())
+                )
+            })?;
+        let ty = key.split_once(':').map(|(_, ty)| ty.trim());
+        if let (Some(ty), Some(expected)) = (ty, arg.ty.display_name.last()) {
+            if ty.split('<').next() != Some(expected.as_str()) {
+                return Err(format!(
+                    "argument {:?} of {} is of type {}, not {}",
+                    arg.label, what, expected, ty
+                )
+                .into())
+            }
+        }
+        passed.push(normalize(&arg.label));
+    }
+    let mut missing = spec
+        .args
+        .iter()
+        .filter(|arg| !passed.contains(&normalize(&arg.label)))
+        .peekable();
+    if missing.peek().is_some() {
+        return Err(format!("{} misses the arguments {}", what, signature(missing)).into())
+    }
+    Ok(())
+}
+
+/// Returns `true` if `key` sets the endowment of `constructor` instead of one of
+/// its arguments, which is the case for `value` if no argument is named so.
+pub(crate) fn is_endowment(constructor: &MessageSpec, key: &str) -> bool {
+    normalize_arg(key) == "value" && !has_arg(constructor, "value")
+}
+
+/// Returns `true` if `spec` has an argument named `name`.
+fn has_arg(spec: &MessageSpec, name: &str) -> bool {
+    spec.args.iter().any(|arg| normalize(&arg.label) == name)
+}
+
+/// Renders the labels of `specs` for an error, e.g. `new, default`.
+fn labels(specs: &[MessageSpec]) -> String {
+    specs
+        .iter()
+        .map(|spec| spec.label.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Renders `args` for an error, e.g. `to: AccountId, value: Balance`.
+fn signature<'a>(args: impl Iterator<Item = &'a ArgSpec>) -> String {
+    let args: Vec<String> = args
+        .map(|arg| {
+            match arg.ty.display_name.last() {
+                Some(ty) => format!("{}: {}", arg.label, ty),
+                None => arg.label.clone(),
+            }
+        })
+        .collect();
+    if args.is_empty() {
+        String::from("none")
+    } else {
+        args.join(", ")
+    }
+}
+
+/// Deserializes a metadata version which might be missing.
+fn deserialize_optional_version<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_version(deserializer).map(Some)
+}
+
+/// Returns `true` if `value` is `len` bytes as hex string with a `0x` prefix.
+fn is_hex(value: &str, len: usize) -> bool {
+    value.strip_prefix("0x").is_some_and(|hex| {
+        hex.len() == 2 * len && hex.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+/// The metadata of all contracts which were uploaded via a backend, indexed by
+/// the contract address.
+#[derive(Default)]
+pub(crate) struct Contracts(HashMap<String, ContractBundle>);
+
+impl Contracts {
+    /// Reads the contract bundle at `path` and registers it for the contract
+    /// at `contract_address`. Replaces any previously registered metadata.
+    pub(crate) fn load(&mut self, contract_address: &str, path: &Path) -> Result<()> {
+        let bundle = ContractBundle::load(path)?;
+        self.insert(contract_address, bundle);
+        Ok(())
+    }
+
+    /// Registers `bundle` for the contract at `contract_address`.
+    pub(crate) fn insert(&mut self, contract_address: &str, bundle: ContractBundle) {
+        log::info!(
+            "[{}] contract {} is {} {}, written in {} and compiled with {}",
+            crate::utils::test_name(),
+            contract_address,
+            bundle.contract.name,
+            bundle.contract.version,
+            bundle.source.language,
+            bundle.source.compiler
+        );
+        self.0.insert(contract_address.to_string(), bundle);
+    }
+
+    /// Returns the metadata of the contract at `contract_address`.
+    pub(crate) fn get(&self, contract_address: &str) -> Result<&ContractBundle> {
+        self.0.get(contract_address).ok_or_else(|| {
+            format!(
+                "no metadata for contract {} found, it must be uploaded via this backend first",
+                contract_address
+            )
+            .into()
+        })
+    }
+}
+
+/// Normalizes a message or constructor label, so that the different namings
+/// used by the tests (`baseErc20::balanceOf`, `erc1155::balance_of`, …) match
+/// the labels in the metadata.
+pub(crate) fn normalize(label: &str) -> String {
+    label
+        .split("::")
+        .map(|segment| segment.trim().to_case(Case::Snake).to_lowercase())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Normalizes an argument key, the tests might add the type as a suffix
+/// (e.g. `to: AccountId`).
+pub(crate) fn normalize_arg(key: &str) -> String {
+    normalize(key.split(':').next().unwrap_or(key))
+}
+
+/// Resolves the type `ty` in `registry`.
+pub(crate) fn resolve(
+    registry: &PortableRegistry,
+    ty: u32,
+) -> Result<&Type<PortableForm>> {
+    registry
+        .resolve(ty)
+        .ok_or_else(|| format!("type {} not found in metadata", ty).into())
+}
+
+/// Returns `true` if `ty` is an `AccountId`.
+pub(crate) fn is_account_id(ty: &Type<PortableForm>) -> bool {
+    ty.path.segments.last().map(String::as_str) == Some("AccountId")
+}
+
+/// Returns `true` if `ty` is a `u8`.
+pub(crate) fn is_u8(registry: &PortableRegistry, ty: u32) -> bool {
+    matches!(
+        registry.resolve(ty).map(|ty| &ty.type_def),
+        Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+    )
+}
+
+/// Returns `Some` if `ty` is a byte array, a byte vector or a newtype around
+/// one of those. The inner option holds the length of arrays.
+pub(crate) fn byte_array_len(
+    registry: &PortableRegistry,
+    ty: u32,
+) -> Option<Option<usize>> {
+    match &registry.resolve(ty)?.type_def {
+        TypeDef::Array(array) if is_u8(registry, array.type_param.id) => {
+            Some(Some(array.len as usize))
+        }
+        TypeDef::Sequence(sequence) if is_u8(registry, sequence.type_param.id) => {
+            Some(None)
+        }
+        TypeDef::Composite(composite) if composite.fields.len() == 1 => {
+            byte_array_len(registry, composite.fields[0].ty.id)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uis::Balance;
+    use serde_json::{
+        json,
+        Value,
+    };
+    use std::path::PathBuf;
+
+    /// Returns the metadata of a small contract in the format of ink! 4.
+    fn metadata() -> Value {
+        json!({
+            "version": "4",
+            "source": {
+                "hash": format!("0x{}", "00".repeat(32)),
+                "language": "ink! 4.3.0",
+                "compiler": "rustc 1.72.0",
+                "wasm": "0x0061736d"
+            },
+            "contract": { "name": "test", "version": "0.1.0" },
+            "spec": {
+                "constructors": [
+                    {
+                        "label": "new",
+                        "selector": "0x9bae9d5e",
+                        "args": [arg("init_value", 0, "bool")],
+                        "payable": false,
+                        "default": true
+                    },
+                    {
+                        "label": "new_payable",
+                        "selector": "0x00000001",
+                        "args": [],
+                        "payable": true
+                    },
+                    {
+                        "label": "with_value",
+                        "selector": "0x00000002",
+                        "args": [arg("value", 1, "Balance")],
+                        "payable": true
+                    }
+                ],
+                "messages": [
+                    {
+                        "label": "get",
+                        "selector": "0x2f865bd9",
+                        "args": [],
+                        "returnType": { "type": 0, "displayName": ["bool"] }
+                    },
+                    {
+                        "label": "transfer",
+                        "selector": "0x84a15da1",
+                        "args": [arg("to", 2, "AccountId"), arg("value", 1, "Balance")],
+                        "mutates": true
+                    },
+                    {
+                        "label": "deposit",
+                        "selector": "0x00000003",
+                        "args": [],
+                        "payable": true,
+                        "mutates": true
+                    }
+                ],
+                "events": [
+                    { "label": "Transferred", "args": [arg("to", 2, "AccountId")] }
+                ]
+            },
+            "storage": {
+                "root": {
+                    "root_key": "0x00000000",
+                    "layout": { "leaf": { "key": "0x00000000", "ty": 0 } }
+                }
+            },
+            "types": [
+                { "id": 0, "type": { "def": { "primitive": "bool" } } },
+                { "id": 1, "type": { "def": { "primitive": "u128" } } },
+                {
+                    "id": 2,
+                    "type": {
+                        "path": ["ink_primitives", "types", "AccountId"],
+                        "def": {
+                            "composite": {
+                                "fields": [{ "type": 3, "typeName": "[u8; 32]" }]
+                            }
+                        }
+                    }
+                },
+                { "id": 3, "type": { "def": { "array": { "len": 32, "type": 4 } } } },
+                { "id": 4, "type": { "def": { "primitive": "u8" } } }
+            ]
+        })
+    }
+
+    fn arg(label: &str, ty: u32, display_name: &str) -> Value {
+        json!({ "label": label, "type": { "type": ty, "displayName": [display_name] } })
+    }
+
+    fn parse(metadata: &Value) -> Result<ContractBundle> {
+        ContractBundle::parse(&serde_json::to_vec(metadata).expect("serializing failed"))
+    }
+
+    /// Asserts that `metadata` is rejected with an error containing `expected`.
+    fn assert_rejected(metadata: &Value, expected: &str) {
+        assert_err(parse(metadata), expected)
+    }
+
+    /// Asserts that `result` is an error containing `expected`.
+    fn assert_err<T>(result: Result<T>, expected: &str) {
+        match result {
+            Ok(_) => panic!("no error containing {:?}", expected),
+            Err(err) => {
+                assert!(
+                    err.to_string().contains(expected),
+                    "{:?} does not contain {:?}",
+                    err.to_string(),
+                    expected
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn valid_bundles_are_parsed() {
+        // given
+        let mut ink_5 = metadata();
+        ink_5["version"] = json!(5);
+
+        // when
+        let ink_4 = parse(&metadata()).expect("parsing failed");
+        let ink_5 = parse(&ink_5).expect("parsing failed");
+
+        // then
+        assert_eq!(ink_4.version, 4);
+        assert_eq!(ink_5.version, 5);
+        assert_eq!(ink_4.contract.name, "test");
+        assert_eq!(ink_4.spec.messages.len(), 3);
+    }
+
+    #[test]
+    fn unsupported_versions_are_rejected() {
+        // given
+        let mut ink_3 = metadata();
+        ink_3.as_object_mut().unwrap().remove("version");
+        let mut future = metadata();
+        future["version"] = json!(6);
+        let mut invalid = metadata();
+        invalid["version"] = json!("four");
+
+        // then
+        assert_rejected(&ink_3, "ink! 3");
+        assert_rejected(&future, "unsupported metadata version 6");
+        assert_rejected(&invalid, "invalid version");
+    }
+
+    #[test]
+    fn invalid_source_is_rejected() {
+        // given
+        let mut short_hash = metadata();
+        short_hash["source"]["hash"] = json!("0x0000");
+        let mut wasm = metadata();
+        wasm["source"]["wasm"] = json!("0061736d");
+
+        // then
+        assert_rejected(&short_hash, "`source.hash` must be 32 bytes");
+        assert_rejected(&wasm, "`source.wasm` must be hex");
+    }
+
+    #[test]
+    fn invalid_selectors_are_rejected() {
+        // given
+        let mut constructor = metadata();
+        constructor["spec"]["constructors"][0]["selector"] = json!("0x9bae9d");
+        let mut message = metadata();
+        message["spec"]["messages"][0]["selector"] = json!("0xnothex!");
+
+        // then
+        assert_rejected(&constructor, "`spec.constructors.new.selector`");
+        assert_rejected(&message, "`spec.messages.get.selector`");
+    }
+
+    #[test]
+    fn unknown_types_are_rejected() {
+        // given
+        let mut arg = metadata();
+        arg["spec"]["messages"][1]["args"][0]["type"]["type"] = json!(42);
+        let mut return_type = metadata();
+        return_type["spec"]["messages"][0]["returnType"]["type"] = json!(42);
+        let mut event = metadata();
+        event["spec"]["events"][0]["args"][0]["type"]["type"] = json!(42);
+
+        // then
+        assert_rejected(&arg, "`spec.messages.transfer.to`: type 42 not found");
+        assert_rejected(&return_type, "`spec.messages.get.returnType`");
+        assert_rejected(&event, "`spec.events.Transferred.to`");
+    }
+
+    #[test]
+    fn bundles_not_matching_the_schema_are_rejected() {
+        // given
+        let mut metadata = metadata();
+        metadata["spec"].as_object_mut().unwrap().remove("messages");
+
+        // then
+        assert_rejected(&metadata, "does not match the metadata schema");
+    }
+
+    #[test]
+    fn invalid_storage_layouts_are_rejected() {
+        // given
+        let mut unknown_type = metadata();
+        unknown_type["storage"]["root"]["layout"] = json!({
+            "struct": {
+                "name": "Flipper",
+                "fields": [
+                    { "name": "value", "layout": { "leaf": { "key": "0x00000000", "ty": 42 } } }
+                ]
+            }
+        });
+        let mut root_key = metadata();
+        root_key["storage"]["root"]["root_key"] = json!("0x00");
+
+        // then
+        assert_rejected(
+            &unknown_type,
+            "`storage.root.Flipper.value`: type 42 not found",
+        );
+        assert_rejected(&root_key, "`storage.root`: the key must be 4 bytes");
+    }
+
+    #[test]
+    fn contracts_not_written_in_ink_are_rejected() {
+        // given
+        let mut solidity = metadata();
+        solidity["source"]["language"] = json!("Solidity 0.8.19");
+
+        // then
+        assert_rejected(&solidity, "only ink! contracts are supported");
+    }
+
+    fn bundle() -> ContractBundle {
+        parse(&metadata()).expect("parsing failed")
+    }
+
+    fn upload() -> Upload {
+        Upload::new(PathBuf::from("test.contract"))
+    }
+
+    fn transfer() -> Call {
+        Call::new(
+            "5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM",
+            "transfer",
+        )
+    }
+
+    #[test]
+    fn calls_passing_exactly_the_arguments_are_accepted() {
+        // given
+        let bundle = bundle();
+        let call = transfer()
+            .push_value("to", [0u8; 32])
+            .push_value("value", 1_000u128);
+        let typed = transfer()
+            .push_value("to: AccountId", [0u8; 32])
+            .push_value("value: Balance", 1_000u128);
+
+        // when
+        let call = bundle.check_call(&call);
+        let typed = bundle.check_call(&typed);
+
+        // then
+        assert!(call.is_ok(), "{:?}", call.err());
+        assert!(typed.is_ok(), "{:?}", typed.err());
+    }
+
+    #[test]
+    fn unknown_and_missing_arguments_are_rejected() {
+        // given
+        let bundle = bundle();
+        let unknown = transfer()
+            .push_value("to", [0u8; 32])
+            .push_value("value", 1_000u128)
+            .push_value("memo", 0u8);
+        let missing = transfer().push_value("to", [0u8; 32]);
+
+        // then
+        assert_err(bundle.check_call(&unknown), "has no argument \"memo\"");
+        assert_err(
+            bundle.check_call(&missing),
+            "misses the arguments value: Balance",
+        );
+        assert_err(
+            bundle.check_call(&Call::new("", "transfer_from")),
+            "message \"transfer_from\" does not exist",
+        );
+    }
+
+    #[test]
+    fn type_suffixes_must_match_the_metadata() {
+        // given
+        let bundle = bundle();
+        let mismatch = transfer()
+            .push_value("to: Balance", [0u8; 32])
+            .push_value("value", 1_000u128);
+        let generic = transfer()
+            .push_value("to: AccountId<Default>", [0u8; 32])
+            .push_value("value", 1_000u128);
+
+        // then
+        assert_err(
+            bundle.check_call(&mismatch),
+            "is of type AccountId, not Balance",
+        );
+        assert!(bundle.check_call(&generic).is_ok());
+    }
+
+    #[test]
+    fn only_payable_messages_accept_a_payment() {
+        // given
+        let bundle = bundle();
+        let payment = Balance::plancks(1_000);
+        let deposit = Call::new("", "deposit");
+
+        // then
+        assert!(bundle.check_call(&deposit).is_ok());
+        assert!(bundle.check_call(&deposit.payment(payment)).is_ok());
+        assert_err(
+            bundle.check_call(
+                &transfer()
+                    .push_value("to", [0u8; 32])
+                    .push_value("value", 1_000u128)
+                    .payment(payment),
+            ),
+            "is not payable, but a payment",
+        );
+    }
+
+    #[test]
+    fn uploads_are_checked_against_the_default_constructor() {
+        // given
+        let bundle = bundle();
+
+        // then
+        assert!(bundle
+            .check_upload(&upload().push_initial_value("initValue", true))
+            .is_ok());
+        assert_err(
+            bundle.check_upload(&upload()),
+            "constructor new of contract test misses the arguments init_value: bool",
+        );
+        assert_err(
+            bundle.check_upload(&upload().constructor("default")),
+            "constructor \"default\" does not exist",
+        );
+    }
+
+    #[test]
+    fn only_payable_constructors_accept_an_endowment() {
+        // given
+        let bundle = bundle();
+        let endowment = Balance::plancks(1_000);
+
+        // when
+        let payable = upload()
+            .constructor("newPayable")
+            .push_initial_value("value", endowment);
+        let not_payable = upload()
+            .push_initial_value("init_value", true)
+            .push_initial_value("value", endowment);
+        // `value` is an argument of this constructor, not the endowment
+        let with_value = upload()
+            .constructor("with_value")
+            .push_initial_value("value", endowment);
+
+        // then
+        assert!(bundle.check_upload(&payable).is_ok());
+        assert_err(
+            bundle.check_upload(&not_payable),
+            "is not payable, but an endowment",
+        );
+        assert!(bundle.check_upload(&with_value).is_ok());
+        assert_err(
+            bundle.check_upload(&upload().constructor("with_value")),
+            "misses the arguments value: Balance",
+        );
+    }
+}
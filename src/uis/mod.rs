@@ -12,43 +12,74 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#[cfg(not(feature = "polkadot-js-ui"))]
+pub mod args;
+pub mod balance;
 pub mod canvas_ui;
-
-#[cfg(feature = "polkadot-js-ui")]
+mod chain;
+mod decode;
+mod events;
+pub mod gas;
+pub mod keyring;
+mod metadata;
 pub mod polkadot_js;
+mod retry;
+pub mod rpc;
+pub mod trace;
+mod wait;
 
-#[cfg(feature = "polkadot-js-ui")]
-use convert_case::{
-    Case,
-    Casing,
+pub use args::ArgValue;
+pub use balance::{
+    AccountBalance,
+    Balance,
+    BalanceDelta,
+    Token,
+};
+pub use chain::BalanceSnapshot;
+pub use events::{
+    ContractEvent,
+    Event,
+    Events,
 };
+pub(crate) use metadata::ContractBundle;
 
+use crate::utils::node::{
+    self,
+    Node,
+};
 use async_trait::async_trait;
+use canvas_ui::CanvasUi;
+use chain::Chain;
 use fantoccini::{
+    elements::Element,
     error::CmdError,
     Client,
     ClientBuilder,
+    Locator,
 };
-use lazy_static::lazy_static;
+use gas::GasUsage;
+use metadata::Contracts;
+use polkadot_js::PolkadotJs;
+use rpc::Rpc;
 use serde_json::{
     self,
     map::Map,
     value::Value,
 };
 use std::{
-    path::PathBuf,
+    fmt,
+    fs::File,
+    path::{
+        Path,
+        PathBuf,
+    },
     process,
-    sync::Mutex,
+    str::FromStr,
+    sync::Arc,
     time::Duration,
 };
 
-lazy_static! {
-    static ref PICKED_PORTS: Mutex<Vec<u16>> = Mutex::new(vec![]);
-}
-
 // The result of an interaction with the UI.
-pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
 // The result of submitting a transaction.
 pub type TransactionResult<T> = std::result::Result<T, TransactionError>;
@@ -56,26 +87,152 @@ pub type TransactionResult<T> = std::result::Result<T, TransactionError>;
 // An error which happened when interacting with the UI.
 #[derive(Debug)]
 pub enum TransactionError {
+    /// The extrinsic failed, but the dispatch error could not be decoded.
     ExtrinsicFailed(Events),
-    Other(Box<dyn std::error::Error>),
+    /// The extrinsic failed with an error of a pallet, e.g. `contracts.ContractTrapped`.
+    Module(Box<ModuleError>, Events),
+    /// The transaction failed, because it ran out of gas.
+    OutOfGas(Events),
+    /// The contract reverted, e.g. because the message returned `Result::Err`.
+    /// Holds the decoded error.
+    ContractReverted(ArgValue),
+    /// The transaction was replaced in the transaction pool by another one with
+    /// the same nonce.
+    Usurped(Events),
+    /// The transaction was rejected by the transaction pool, e.g. because its
+    /// priority is too low or it is outdated.
+    Invalid(String),
+    /// The UI did not show the expected elements in time.
+    Timeout(String),
+    /// Either both or none of `system.ExtrinsicSuccess` and `system.ExtrinsicFailed`
+    /// were found, so the outcome of the transaction is unknown.
+    Ambiguous(Events),
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionError::ExtrinsicFailed(events) => {
+                write!(f, "extrinsic failed with events {}", events)
+            }
+            TransactionError::Module(error, events) => {
+                write!(f, "extrinsic failed with {} and events {}", error, events)
+            }
+            TransactionError::OutOfGas(events) => {
+                write!(f, "transaction ran out of gas with events {}", events)
+            }
+            TransactionError::ContractReverted(err) => {
+                write!(f, "contract reverted with {:?}", err)
+            }
+            TransactionError::Usurped(events) => {
+                write!(f, "transaction was usurped with events {}", events)
+            }
+            TransactionError::Invalid(reason) => {
+                write!(f, "transaction is invalid: {}", reason)
+            }
+            TransactionError::Timeout(reason) => write!(f, "timed out: {}", reason),
+            TransactionError::Ambiguous(events) => {
+                write!(f, "outcome of transaction is ambiguous, events {}", events)
+            }
+            TransactionError::Other(err) => write!(f, "{}", err),
+        }
+    }
 }
 
+impl std::error::Error for TransactionError {}
+
 impl From<CmdError> for TransactionError {
     fn from(cmd_err: CmdError) -> Self {
-        TransactionError::Other(Box::new(cmd_err))
+        match cmd_err {
+            CmdError::WaitTimeout => TransactionError::Timeout(cmd_err.to_string()),
+            CmdError::Standard(ref err) if err.error() == "timeout" => {
+                TransactionError::Timeout(cmd_err.to_string())
+            }
+            cmd_err => TransactionError::Other(Box::new(cmd_err)),
+        }
     }
 }
 
-#[async_trait]
-pub trait ContractsUi {
-    /// Returns the balance postfix numbers.
-    async fn balance_postfix(&mut self, account: String) -> self::Result<u128>;
+/// An error of a pallet, with which an extrinsic failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuleError {
+    /// The name of the error, e.g. `contracts.ContractTrapped`.
+    name: String,
+    /// The documentation of the error in the runtime metadata.
+    docs: String,
+}
+
+impl ModuleError {
+    /// Creates a new `ModuleError` for the error `error` of `pallet`.
+    pub(crate) fn new(pallet: &str, error: &str, docs: &str) -> Self {
+        Self {
+            name: format!("{}.{}", pallet, error),
+            docs: docs.to_string(),
+        }
+    }
+
+    /// Returns the pallet which returned the error, e.g. `contracts`.
+    pub fn pallet(&self) -> &str {
+        self.split().0
+    }
 
+    /// Returns the name of the error within its pallet, e.g. `ContractTrapped`.
+    pub fn error(&self) -> &str {
+        self.split().1
+    }
+
+    /// Returns the documentation of the error.
+    pub fn docs(&self) -> &str {
+        &self.docs
+    }
+
+    /// Returns `true` if the error is named `name`, e.g. `contracts.ContractNotFound`.
+    ///
+    /// The pallet is compared case-insensitive.
+    pub fn is(&self, name: &str) -> bool {
+        match name.split_once('.') {
+            Some((pallet, error)) => {
+                self.pallet().eq_ignore_ascii_case(pallet) && self.error() == error
+            }
+            None => false,
+        }
+    }
+
+    /// Splits the name into the pallet and the error.
+    fn split(&self) -> (&str, &str) {
+        self.name
+            .split_once('.')
+            .expect("name is always `pallet.Error`")
+    }
+}
+
+impl fmt::Display for ModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.docs().is_empty() {
+            write!(f, " ({})", self.docs())?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for TransactionError {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        TransactionError::Other(err)
+    }
+}
+
+#[async_trait]
+pub trait ContractsUi: Send {
     /// Uploads the contract behind `contract_path`.
-    async fn execute_upload(&mut self, upload_input: Upload) -> self::Result<String>;
+    async fn execute_upload(
+        &mut self,
+        upload_input: Upload,
+    ) -> self::TransactionResult<String>;
 
-    /// Executes the RPC call `call`.
-    async fn execute_rpc(&mut self, call: Call) -> self::Result<String>;
+    /// Executes the RPC call `call` and returns its decoded return value.
+    async fn execute_rpc(&mut self, call: Call) -> self::Result<ArgValue>;
 
     /// Executes the transaction `call`.
     async fn execute_transaction(
@@ -84,71 +241,642 @@ pub trait ContractsUi {
     ) -> self::TransactionResult<Events>;
 
     /// Updates the metadata
+    #[allow(dead_code)]
     async fn update_metadata(
         &mut self,
-        contract_addr: &String,
-        new_abi: &PathBuf,
+        contract_addr: &str,
+        new_abi: &Path,
     ) -> self::Result<String>;
+
+    /// Tears down the backend at the end of a test.
+    async fn shutdown(&mut self) -> self::Result<()>;
+
+    /// Saves what the backend displays into `dir`, to debug a failed test.
+    ///
+    /// Backends without a display save nothing.
+    async fn save_failure_artifacts(&mut self, _dir: &Path) -> self::Result<()> {
+        Ok(())
+    }
+}
+
+/// The backends which the tests can be run against.
+///
+/// The backend is chosen at runtime via the env variable `WATERFALL_UI`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UiBackend {
+    /// The `canvas-ui`, driven via a browser.
+    CanvasUi,
+    /// The `polkadot-js` UI, driven via a browser.
+    PolkadotJs,
+    /// The node's JSON-RPC interface, no browser involved.
+    Rpc,
+}
+
+impl UiBackend {
+    /// Returns the backend configured via the env variable `WATERFALL_UI`.
+    ///
+    /// Defaults to the `canvas-ui`.
+    pub fn from_env() -> Self {
+        std::env::var("WATERFALL_UI")
+            .unwrap_or_else(|_| "canvas-ui".to_string())
+            .parse()
+            .expect("unable to parse `WATERFALL_UI`")
+    }
+}
+
+impl FromStr for UiBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "canvas-ui" => Ok(UiBackend::CanvasUi),
+            "polkadot-js" => Ok(UiBackend::PolkadotJs),
+            "rpc" => Ok(UiBackend::Rpc),
+            other => {
+                Err(format!(
+                    "unknown UI {:?}, expected one of `canvas-ui`, `polkadot-js`, `rpc`",
+                    other
+                ))
+            }
+        }
+    }
 }
 
-/// Holds everything necessary to interact with the user interface.
+impl fmt::Display for UiBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            UiBackend::CanvasUi => "canvas-ui",
+            UiBackend::PolkadotJs => "polkadot-js",
+            UiBackend::Rpc => "rpc",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The interface against which the tests are run.
+///
+/// Dispatches all interactions to the backend chosen via `WATERFALL_UI`.
 pub struct Ui {
+    /// The kind of backend in use.
+    kind: UiBackend,
+    /// The backend which executes the interactions.
+    backend: Box<dyn ContractsUi>,
+    /// The connection to the node, used to query the chain state.
+    chain: Chain,
+    /// The node spawned for this test, killed when the last handle is dropped.
+    /// `None` when running against an external node.
+    _node: Option<Arc<Node>>,
+    /// The metadata of the contracts which were uploaded via this instance,
+    /// needed to decode the events which they emit.
+    contracts: Contracts,
+    /// Whether the artifacts of a failed step were saved.
+    saved_failure_artifacts: bool,
+}
+
+impl Ui {
+    /// Creates a new `Ui` instance for the backend configured via `WATERFALL_UI`.
+    pub async fn new() -> self::Result<Self> {
+        Self::with_backend(UiBackend::from_env()).await
+    }
+
+    /// Creates a new `Ui` instance for `kind`.
+    ///
+    /// This allows running the same interactions against different backends
+    /// within one test.
+    ///
+    /// Only the `rpc` backend signs with fresh accounts per test, see
+    /// [`keyring::allocate`]. The browser UIs are not supported: they only know
+    /// the dev accounts of the development chain, so concurrently running tests
+    /// still share their nonces and rely on retrying usurped transactions.
+    pub async fn with_backend(kind: UiBackend) -> self::Result<Self> {
+        log::info!("using {} backend", kind);
+        let node = node::start().await?;
+        let chain = Chain::connect().await?;
+        let token = chain.token().clone();
+        let backend: Box<dyn ContractsUi> = match kind {
+            UiBackend::CanvasUi => Box::new(CanvasUi::new(token).await?),
+            UiBackend::PolkadotJs => Box::new(PolkadotJs::new(token).await?),
+            UiBackend::Rpc => Box::new(Rpc::new().await?),
+        };
+        // The fresh accounts are not imported into the keyrings of the browser
+        // UIs, these sign with the dev accounts.
+        if kind == UiBackend::Rpc {
+            let accounts = keyring::allocate()?;
+            chain
+                .fund(&keyring::faucet()?, &accounts, crate::utils::funding())
+                .await?;
+        }
+        Ok(Self {
+            kind,
+            backend,
+            chain,
+            _node: node,
+            contracts: Contracts::default(),
+            saved_failure_artifacts: false,
+        })
+    }
+
+    /// Returns the balance of `account`, which is either the name of an account in
+    /// the keyring (e.g. `EVE`) or an SS58 address.
+    ///
+    /// The balance is read from the chain state, independent of the backend.
+    pub async fn balance(&self, account: &str) -> self::Result<AccountBalance> {
+        self.chain.balance(account).await
+    }
+
+    /// Takes a snapshot of the balance of `account`, to be compared with
+    /// [`Ui::assert_balance_delta`] after interacting with a contract.
+    pub async fn balance_snapshot(&self, account: &str) -> self::Result<BalanceSnapshot> {
+        self.chain.snapshot(account).await
+    }
+
+    /// Asserts that the free balance of the account in `snapshot` changed by
+    /// `expected` since the snapshot was taken.
+    ///
+    /// The transaction fees which the account paid in the meantime are not part
+    /// of the change, they are taken from the `TransactionFeePaid` events.
+    pub async fn assert_balance_delta(
+        &self,
+        snapshot: &BalanceSnapshot,
+        expected: BalanceDelta,
+    ) -> self::Result<()> {
+        let delta = self.chain.balance_delta(snapshot).await?;
+        assert_eq!(
+            delta, expected,
+            "unexpected change of the free balance, net of fees"
+        );
+        Ok(())
+    }
+
+    /// Fails if `caller` can not sign via the backend in use, the browser UIs
+    /// only know the dev accounts.
+    fn check_caller(&self, caller: Option<&str>) -> self::Result<()> {
+        match caller {
+            Some(caller)
+                if self.kind != UiBackend::Rpc && !keyring::is_dev_account(caller) =>
+            {
+                Err(format!(
+                    "{:?} is not a dev account, it can only sign via the rpc backend",
+                    caller
+                )
+                .into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the error of the failed extrinsic with `events`, which failed with
+    /// the module error `error`.
+    fn dispatch_error(error: Option<ModuleError>, events: Events) -> TransactionError {
+        match error {
+            Some(error) if error.is("contracts.OutOfGas") => {
+                TransactionError::OutOfGas(events)
+            }
+            Some(error) => TransactionError::Module(Box::new(error), events),
+            None => TransactionError::ExtrinsicFailed(events),
+        }
+    }
+
+    /// Saves the artifacts of a failed test into `artifacts/<example>/<test>/`
+    /// under the artifacts directory of the run, e.g. a screenshot of the
+    /// browser.
+    ///
+    /// Errors are only logged, they must not hide the failure of the test.
+    pub async fn save_failure_artifacts(&mut self) {
+        let dir = crate::utils::test_artifacts_dir();
+        match self.backend.save_failure_artifacts(&dir).await {
+            Ok(()) => self.saved_failure_artifacts = true,
+            Err(err) => {
+                log::error!(
+                    "[{}] unable to save failure artifacts to {:?}: {}",
+                    crate::utils::test_name(),
+                    dir,
+                    err
+                )
+            }
+        }
+    }
+
+    /// Returns whether the artifacts of a failed step were saved, e.g. by
+    /// [`Ui::save_failure_artifacts`].
+    pub fn saved_failure_artifacts(&self) -> bool {
+        self.saved_failure_artifacts
+    }
+
+    /// Saves the failure artifacts if `outcome` of a step is an error.
+    ///
+    /// The artifacts are saved right away, while the browser still shows the
+    /// failure, the test might go on after an expected error.
+    async fn check_step<T, E>(
+        &mut self,
+        outcome: std::result::Result<T, E>,
+    ) -> std::result::Result<T, E> {
+        if outcome.is_err() {
+            self.save_failure_artifacts().await;
+        }
+        outcome
+    }
+}
+
+#[async_trait]
+impl ContractsUi for Ui {
+    async fn execute_upload(
+        &mut self,
+        upload_input: Upload,
+    ) -> self::TransactionResult<String> {
+        self.check_caller(upload_input.caller.as_deref())?;
+        // mistakes in a test are reported right away, instead of as a timeout of
+        // the UI
+        ContractBundle::load(&upload_input.contract_path)?.check_upload(&upload_input)?;
+        let contract_path = upload_input.contract_path.clone();
+        let since = self.chain.best_block_number().await?;
+        let signer = keyring::keypair(upload_input.caller.as_deref())?.account_id();
+        let nonce = self.chain.nonce(&signer).await?;
+        let (constructor, estimated) =
+            match self.chain.estimate_upload(&upload_input).await {
+                Ok((constructor, estimated)) => (constructor, Some(estimated)),
+                Err(err) => {
+                    log::info!(
+                        "[{}] unable to estimate the gas of the upload: {}",
+                        crate::utils::test_name(),
+                        err
+                    );
+                    (String::from("unknown"), None)
+                }
+            };
+        let upload = self.backend.execute_upload(upload_input);
+        let outcome = trace::step("execute_upload", None, upload).await;
+        let addr = match self.check_step(outcome).await {
+            Ok(addr) => addr,
+            Err(TransactionError::ExtrinsicFailed(events)) => {
+                let extrinsic = self
+                    .chain
+                    .submitted_extrinsic(Some(&events), since, &signer, nonce)
+                    .await?;
+                let error = self.chain.module_error(&extrinsic).await?;
+                return Err(Self::dispatch_error(error, events))
+            }
+            Err(err) => return Err(err),
+        };
+        self.contracts.load(&addr, &contract_path)?;
+        let extrinsic = self.chain.instantiation(since, &addr).await?;
+        gas::record(GasUsage {
+            step: "upload",
+            label: constructor,
+            estimated,
+            consumed: self.chain.consumed_weight(&extrinsic).await?,
+        });
+        Ok(addr)
+    }
+
+    async fn execute_rpc(&mut self, call: Call) -> self::Result<ArgValue> {
+        self.check_caller(call.caller.as_deref())?;
+        self.contracts
+            .get(&call.contract_address)?
+            .check_call(&call)?;
+        let outcome =
+            trace::step("execute_rpc", None, self.backend.execute_rpc(call)).await;
+        self.check_step(outcome).await
+    }
+
+    async fn execute_transaction(
+        &mut self,
+        call: Call,
+    ) -> self::TransactionResult<Events> {
+        self.check_caller(call.caller.as_deref())?;
+        self.contracts
+            .get(&call.contract_address)?
+            .check_call(&call)?;
+        let since = self.chain.best_block_number().await?;
+        let signer = keyring::keypair(call.caller.as_deref())?.account_id();
+        let nonce = self.chain.nonce(&signer).await?;
+        // The UIs do not show the error with which the contract reverted, nor what
+        // it printed, so the call is dry-run against the same state first.
+        let dry_run = self.chain.dry_run(&self.contracts, &call).await?;
+        let message = call.method.clone();
+        let transaction = self.backend.execute_transaction(call);
+        let outcome = trace::step("execute_transaction", None, transaction).await;
+        let mut events = match self.check_step(outcome).await {
+            Ok(events) => events,
+            Err(TransactionError::ExtrinsicFailed(events)) => {
+                let extrinsic = self
+                    .chain
+                    .submitted_extrinsic(Some(&events), since, &signer, nonce)
+                    .await?;
+                let error = self.chain.module_error(&extrinsic).await?;
+                return Err(match (error, dry_run.revert) {
+                    (Some(error), Some(revert))
+                        if error.is("contracts.ContractReverted") =>
+                    {
+                        TransactionError::ContractReverted(revert)
+                    }
+                    (error, _) => Self::dispatch_error(error, events),
+                })
+            }
+            Err(err) => return Err(err),
+        };
+        if let Some(revert) = &dry_run.revert {
+            log::info!(
+                "[{}] the dry-run of {:?} reverted with {:?}, but the extrinsic succeeded",
+                crate::utils::test_name(),
+                message,
+                revert
+            );
+        }
+        let extrinsic = self
+            .chain
+            .submitted_extrinsic(Some(&events), since, &signer, nonce)
+            .await?;
+        let contract_events = self
+            .chain
+            .contract_events(&extrinsic, &self.contracts)
+            .await?;
+        events.set_contract_events(contract_events);
+        events.set_debug_message(dry_run.debug_message);
+        gas::record(GasUsage {
+            step: "transaction",
+            label: message,
+            estimated: Some(dry_run.gas_required),
+            consumed: self.chain.consumed_weight(&extrinsic).await?,
+        });
+        Ok(events)
+    }
+
+    async fn update_metadata(
+        &mut self,
+        contract_addr: &str,
+        new_abi: &Path,
+    ) -> self::Result<String> {
+        let update = self.backend.update_metadata(contract_addr, new_abi);
+        let outcome = trace::step("update_metadata", None, update).await;
+        let result = self.check_step(outcome).await?;
+        self.contracts.load(contract_addr, new_abi)?;
+        Ok(result)
+    }
+
+    async fn shutdown(&mut self) -> self::Result<()> {
+        self.backend.shutdown().await
+    }
+}
+
+/// Holds everything necessary to interact with a user interface in the browser.
+///
+/// The browser based backends wrap this and dereference to it.
+pub struct Browser {
     client: Client,
     geckodriver: process::Child,
+    /// The file into which `geckodriver` and the browser log, including the
+    /// browser console.
+    console_log: PathBuf,
+    /// How spuriously failing WebDriver actions are retried.
+    retry: retry::RetryPolicy,
+    /// The chain's token, which the UIs display balances in.
+    token: Token,
+    /// The metadata of the contracts which were uploaded via this browser,
+    /// needed to decode the outcomes of RPC calls.
+    contracts: Contracts,
 }
 
-impl Ui {
-    /// Creates a new `Ui` instance.
+impl Browser {
+    /// Creates a new `Browser` instance.
     ///
     /// As part of this set-up a `geckodriver` instance is spawned to a free port.
-    pub async fn new() -> self::Result<Self> {
+    /// `token` is the chain's token, which the UI displays balances in.
+    pub async fn new(token: Token) -> self::Result<Self> {
         crate::utils::assert_node_running();
 
-        let mut port = None;
-        for retry in 0..10 {
-            let port_candidate = portpicker::pick_unused_port().expect("no free port");
-            log::info!("picked free port candidate {}", port_candidate);
-
-            // add this port to a global variable and check that no other thread has yet chosen it.
-            let mut picked_ports =
-                PICKED_PORTS.lock().expect("failed locking `PICKED_PORTS`");
-            log::info!("picked ports {:?}", picked_ports);
-            if !picked_ports.contains(&port_candidate) {
-                picked_ports.push(port_candidate);
-                port = Some(port_candidate);
-                break
-            } else {
-                log::info!("port {} was already chosen by another thread, picking another one (try {})", port_candidate, retry);
-            }
-        }
-        let port = port.expect("no free port could be determined!");
+        let port = crate::utils::pick_unused_port();
         log::info!("picked free port {} for geckodriver instance", port);
 
-        // the output is unfortunately always printed
-        // https://users.rust-lang.org/t/cargo-test-printing-println-output-from-child-threads/11627
-        // https://github.com/rust-lang/rust/issues/35136
-        let geckodriver = process::Command::new("geckodriver")
-            .args(&["--port", &port.to_string(), "--log", "fatal"])
-            .stdout(std::process::Stdio::null())
+        let console_log =
+            crate::utils::artifacts_dir().join(format!("geckodriver-{}.log", port));
+        let output = File::create(&console_log)?;
+        let mut geckodriver = process::Command::new("geckodriver")
+            .args(["--port", &port.to_string(), "--log", "fatal"])
+            .stdout(process::Stdio::from(output.try_clone()?))
+            .stderr(process::Stdio::from(output))
             .spawn()
             .expect("geckodriver can not be spawned");
 
-        std::thread::sleep(Duration::from_secs(3));
+        let ready = wait::wait_until(
+            "geckodriver accepting connections",
+            wait::timeout(),
+            wait::poll_interval(),
+            || {
+                async move {
+                    Ok(tokio::net::TcpStream::connect(("127.0.0.1", port))
+                        .await
+                        .ok()
+                        .map(|_| ()))
+                }
+            },
+        )
+        .await;
+        if let Err(err) = ready {
+            let _ = geckodriver.kill();
+            let _ = geckodriver.wait();
+            return Err(Box::new(err))
+        }
 
         // connect to `webdriver` instance that is listening on that port
-        let client = ClientBuilder::native()
+        let client = match ClientBuilder::native()
             .capabilities(get_capabilities())
             .connect(&format!("http://localhost:{}", port))
-            .await?;
+            .await
+        {
+            Ok(client) => client,
+            Err(err) => {
+                let _ = geckodriver.kill();
+                let _ = geckodriver.wait();
+                return Err(Box::new(err))
+            }
+        };
         Ok(Self {
             client,
             geckodriver,
+            console_log,
+            retry: retry::RetryPolicy::from_env(),
+            token,
+            contracts: Contracts::default(),
         })
     }
 
+    /// Waits until an element at `locator` is present, and returns it.
+    pub async fn wait_for_element(
+        &self,
+        locator: Locator<'_>,
+    ) -> std::result::Result<Element, CmdError> {
+        let wait = self
+            .client
+            .wait()
+            .at_most(wait::timeout())
+            .every(wait::poll_interval())
+            .for_element(locator);
+        trace::step("wait_for_element", Some(format!("{:?}", locator)), wait).await
+    }
+
+    /// Waits up to `timeout` for an element at `locator`, which the UI does not
+    /// always display.
+    ///
+    /// Returns `None` if the element did not appear.
+    pub async fn wait_for_optional_element(
+        &self,
+        locator: Locator<'_>,
+        timeout: Duration,
+    ) -> std::result::Result<Option<Element>, CmdError> {
+        let wait = async move {
+            match self
+                .client
+                .wait()
+                .at_most(timeout)
+                .every(wait::poll_interval())
+                .for_element(locator)
+                .await
+            {
+                Ok(element) => Ok(Some(element)),
+                Err(CmdError::WaitTimeout) => Ok(None),
+                Err(err) => Err(err),
+            }
+        };
+        trace::step(
+            "wait_for_optional_element",
+            Some(format!("{:?}", locator)),
+            wait,
+        )
+        .await
+    }
+
+    /// Clicks on the element at `locator`, as soon as it is present and can be
+    /// clicked.
+    ///
+    /// Spurious UI errors like this one are retried according to the
+    /// [`retry::RetryPolicy`]:
+    ///
+    /// ```json
+    /// Standard(WebDriverError { error: ElementClickIntercepted,
+    /// message: "Element <button class=\"ui--Button hasLabel Button-sc-l9wqp0-0 fUpXVx\">
+    /// is not clickable at point (750,216) because another element
+    /// <div class=\"ui--InputFile error InputFile-sc-vhlvx4-0 jqSBqi\"> obscures it",
+    /// stack: "", delete_session: false })
+    /// ```
+    pub async fn click(&self, locator: Locator<'_>) -> std::result::Result<(), CmdError> {
+        let click = async move {
+            self.wait_for_element(locator).await?;
+            self.retry
+                .run(&format!("click on {:?}", locator), || {
+                    async move { self.client.find(locator).await?.click().await }
+                })
+                .await
+        };
+        trace::step("click", Some(format!("{:?}", locator)), click).await
+    }
+
+    /// Types `text` into the element at `locator`, retrying spurious UI errors.
+    pub async fn send_keys(
+        &self,
+        locator: Locator<'_>,
+        text: &str,
+    ) -> std::result::Result<(), CmdError> {
+        let what = format!("send keys to {:?}", locator);
+        let send_keys = self.retry.run(&what, || {
+            async move { self.client.find(locator).await?.send_keys(text).await }
+        });
+        trace::step("send_keys", Some(format!("{:?}", locator)), send_keys).await
+    }
+
+    /// Replaces the value of the input at `locator` with `text`, retrying
+    /// spurious UI errors.
+    pub async fn fill(
+        &self,
+        locator: Locator<'_>,
+        text: &str,
+    ) -> std::result::Result<(), CmdError> {
+        let what = format!("fill {:?}", locator);
+        let fill = self.retry.run(&what, || {
+            async move {
+                let input = self.client.find(locator).await?;
+                input.clear().await?;
+                input.send_keys(text).await
+            }
+        });
+        trace::step("fill", Some(format!("{:?}", locator)), fill).await
+    }
+
+    /// Returns the text of the element at `locator`, retrying spurious UI errors.
+    pub async fn text(
+        &self,
+        locator: Locator<'_>,
+    ) -> std::result::Result<String, CmdError> {
+        let what = format!("read text of {:?}", locator);
+        let text = self.retry.run(&what, || {
+            async move { self.client.find(locator).await?.text().await }
+        });
+        trace::step("text", Some(format!("{:?}", locator)), text).await
+    }
+
+    /// Waits until the UI displays the final status of an extrinsic, i.e. until
+    /// an element at `locator` is present.
+    ///
+    /// If the status does not appear within `timeout`, the status messages which
+    /// are displayed are logged and a [`TransactionError::Timeout`] is returned.
+    pub async fn wait_for_status(
+        &self,
+        locator: Locator<'_>,
+        timeout: Duration,
+    ) -> TransactionResult<()> {
+        let wait = async move {
+            let status = self
+                .client
+                .wait()
+                .at_most(timeout)
+                .every(wait::poll_interval())
+                .for_element(locator)
+                .await;
+            match status {
+                Ok(_) => Ok(()),
+                Err(CmdError::WaitTimeout) => {
+                    let statuses = self
+                        .client
+                        .find_all(Locator::XPath("//div[contains(@class, 'ui--Status')]"))
+                        .await?;
+                    for status in statuses {
+                        log::info!(
+                            "[{}] displayed status while waiting: {:?}",
+                            crate::utils::test_name(),
+                            status.text().await?
+                        );
+                    }
+                    Err(TransactionError::Timeout(format!(
+                        "status {:?} not displayed within {:?}",
+                        locator, timeout
+                    )))
+                }
+                Err(err) => Err(err.into()),
+            }
+        };
+        trace::step("wait_for_status", Some(format!("{:?}", locator)), wait).await
+    }
+
+    /// Saves a screenshot, the HTML of the current page and the browser console
+    /// log into `dir`.
+    pub async fn save_failure_artifacts(&self, dir: &Path) -> self::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let screenshot = self.client.screenshot().await?;
+        std::fs::write(dir.join("screenshot.png"), screenshot)?;
+        let source = self.client.source().await?;
+        std::fs::write(dir.join("page.html"), source)?;
+        std::fs::copy(&self.console_log, dir.join("console.log"))?;
+        log::info!(
+            "[{}] saved failure artifacts to {:?}",
+            crate::utils::test_name(),
+            dir
+        );
+        Ok(())
+    }
+
     /// Closes the `client`.
     ///
-    /// It would be better to have this in `Ui::Drop`, but this is not possible
+    /// It would be better to have this in `Browser::Drop`, but this is not possible
     /// due to the async nature of the `client.close()` method.
     pub async fn shutdown(&mut self) -> self::Result<()> {
         if !closing_enabled() {
@@ -164,7 +892,7 @@ impl Ui {
     }
 }
 
-impl Drop for Ui {
+impl Drop for Browser {
     fn drop(&mut self) {
         if !closing_enabled() {
             log::info!(
@@ -172,7 +900,7 @@ impl Drop for Ui {
             );
             return
         }
-        // We kill the `geckodriver` instance here and not in `Ui::shutdown()`.
+        // We kill the `geckodriver` instance here and not in `Browser::shutdown()`.
         // The reason is that if a test fails (e.g. due to an assertion), then the test
         // will be interrupted and the shutdown method at the end of a test will not
         // be reached, but this drop will.
@@ -184,46 +912,6 @@ impl Drop for Ui {
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct Payment {
-    /// The payment.
-    payment: String,
-    /// The unit of payment.
-    unit: String,
-}
-
-#[derive(Debug)]
-pub struct Event {
-    /// The header text returned in a status event by the UI.
-    header: String,
-    /// The status text returned in a status event by the UI.
-    status: String,
-}
-
-#[derive(Debug)]
-pub struct Events {
-    /// The events returned by the UI as a result of a RPC call or a transaction.
-    events: Vec<Event>,
-}
-
-impl Events {
-    /// Creates a new `Events` instance.
-    pub fn new(events: Vec<Event>) -> Self {
-        Self { events }
-    }
-
-    /// Returns `true` if the `event` is contained in these events.
-    pub fn contains(&self, event: &str) -> bool {
-        self.events.iter().any(|evt| {
-            assert!(
-                !evt.header.contains("OutOfGas") && !evt.status.contains("OutOfGas"),
-                "Found OutOfGas!"
-            );
-            evt.header.contains(event) || evt.status.contains(event)
-        })
-    }
-}
-
 #[derive(Clone)]
 pub struct Call {
     /// Address of the contract.
@@ -233,53 +921,41 @@ pub struct Call {
     /// Maximum gas allowed.
     max_gas_allowed: Option<String>,
     /// Values to pass along.
-    values: Vec<(String, String)>,
+    values: Vec<(String, ArgValue)>,
     /// Items to add as instantiation values.
-    items: Vec<(String, String)>,
+    items: Vec<(String, ArgValue)>,
     /// The payment to send with the call.
-    payment: Option<Payment>,
+    payment: Option<Balance>,
     /// The account from which to execute the call.
     caller: Option<String>,
+    /// How long to wait for the outcome of the call.
+    timeout: Option<Duration>,
 }
 
 impl Call {
     /// Creates a new `Call` instance.
     pub fn new(contract_address: &str, method: &str) -> Self {
-        let method = method.to_string();
-
-        // the `polkadot-js` ui displays method names in camel-case
-        #[cfg(feature = "polkadot-js-ui")]
-        let method = method.to_case(Case::Camel).replace(": I32", ": i32");
-
         Self {
             contract_address: contract_address.to_string(),
-            method,
+            method: method.to_string(),
             max_gas_allowed: None,
             values: Vec::new(),
             items: Vec::new(),
             payment: None,
             caller: None,
+            timeout: None,
         }
     }
 
-    /// Adds an initial value.
-    ///
-    /// TODO: Make `val` an enum of `Boolean` and `String`.
-    pub fn push_value(mut self, key: &str, val: &str) -> Self {
-        // the `polkadot-js` ui displays method names in camel-case
-        #[cfg(feature = "polkadot-js-ui")]
-        let key = key
-            .to_case(Case::Camel)
-            .replace(":", ": ")
-            .replace(": I32", ": i32");
-
-        self.values.push((key.to_string(), val.to_string()));
+    /// Adds a value.
+    pub fn push_value(mut self, key: &str, val: impl Into<ArgValue>) -> Self {
+        self.values.push((key.to_string(), val.into()));
         self
     }
 
     /// Adds an item.
-    pub fn add_item(mut self, key: &str, val: &str) -> Self {
-        self.items.push((key.to_string(), val.to_string()));
+    pub fn add_item(mut self, key: &str, val: impl Into<ArgValue>) -> Self {
+        self.items.push((key.to_string(), val.into()));
         self
     }
 
@@ -291,11 +967,8 @@ impl Call {
     }
 
     /// Sets the payment submitted with the call.
-    pub fn payment(mut self, payment: &str, unit: &str) -> Self {
-        self.payment = Some(Payment {
-            payment: payment.to_string(),
-            unit: unit.to_string(),
-        });
+    pub fn payment(mut self, payment: Balance) -> Self {
+        self.payment = Some(payment);
         self
     }
 
@@ -304,6 +977,18 @@ impl Call {
         self.caller = Some(caller.to_string());
         self
     }
+
+    /// Sets how long to wait for the outcome of the call, instead of the
+    /// default [`wait::timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns how long to wait for the outcome of the call.
+    pub(crate) fn outcome_timeout(&self) -> Duration {
+        self.timeout.unwrap_or_else(wait::timeout)
+    }
 }
 
 #[derive(Clone)]
@@ -311,9 +996,9 @@ pub struct Upload {
     /// Path to the contract which should be uploaded.
     contract_path: PathBuf,
     /// Values to instantiate the contract with.
-    initial_values: Vec<(String, String)>,
+    initial_values: Vec<(String, ArgValue)>,
     /// Items to add as instantiation values.
-    items: Vec<(String, String)>,
+    items: Vec<(String, ArgValue)>,
     /// Maximum allowed gas.
     #[allow(dead_code)]
     max_allowed_gas: String,
@@ -321,6 +1006,8 @@ pub struct Upload {
     constructor: Option<String>,
     /// The caller to use. If not specified the default selected one is used.
     caller: Option<String>,
+    /// How long to wait for the outcome of the upload.
+    timeout: Option<Duration>,
 }
 
 impl Upload {
@@ -333,27 +1020,19 @@ impl Upload {
             max_allowed_gas: "5000".to_string(),
             constructor: None,
             caller: None,
+            timeout: None,
         }
     }
 
     /// Adds an initial value.
-    ///
-    /// TODO: Make `val` an enum of `Boolean` and `String`.
-    pub fn push_initial_value(mut self, key: &str, val: &str) -> Self {
-        // the `polkadot-js` ui displays method names in camel-case
-        #[cfg(feature = "polkadot-js-ui")]
-        let key = key
-            .to_case(Case::Camel)
-            .replace(":", ": ")
-            .replace(": I32", ": i32");
-
-        self.initial_values.push((key.to_string(), val.to_string()));
+    pub fn push_initial_value(mut self, key: &str, val: impl Into<ArgValue>) -> Self {
+        self.initial_values.push((key.to_string(), val.into()));
         self
     }
 
     /// Adds an item.
-    pub fn add_item(mut self, key: &str, val: &str) -> Self {
-        self.items.push((key.to_string(), val.to_string()));
+    pub fn add_item(mut self, key: &str, val: impl Into<ArgValue>) -> Self {
+        self.items.push((key.to_string(), val.into()));
         self
     }
 
@@ -382,6 +1061,18 @@ impl Upload {
         self.caller = Some(caller.to_string());
         self
     }
+
+    /// Sets how long to wait for the outcome of the upload, instead of the
+    /// default [`wait::timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns how long to wait for the outcome of the upload.
+    pub(crate) fn outcome_timeout(&self) -> Duration {
+        self.timeout.unwrap_or_else(wait::timeout)
+    }
 }
 
 /// Returns `true` if the shutdown procedure should be executed after a test run.
@@ -396,16 +1087,45 @@ fn closing_enabled() -> bool {
 }
 
 /// Returns the capabilities with which the `fantoccini::Client` is instantiated.
-#[cfg(feature = "headless")]
 fn get_capabilities() -> Map<String, Value> {
     let mut caps = Map::new();
-    let opts = serde_json::json!({ "args": ["--headless"] });
-    caps.insert("moz:firefoxOptions".to_string(), opts.clone());
+    // The browser prints its console to stdout, which ends up in the log of
+    // `geckodriver`.
+    let mut opts = serde_json::json!({
+        "prefs": { "devtools.console.stdout.content": true }
+    });
+    if cfg!(feature = "headless") {
+        opts["args"] = serde_json::json!(["--headless"]);
+    }
+    caps.insert("moz:firefoxOptions".to_string(), opts);
     caps
 }
 
-/// Returns the capabilities with which the `fantoccini::Client` is instantiated.
-#[cfg(not(feature = "headless"))]
-fn get_capabilities() -> Map<String, Value> {
-    Map::new()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_call_waits_for_its_own_timeout() {
+        // given
+        let call = Call::new("5Contract", "flip");
+
+        // when
+        let call = call.timeout(Duration::from_secs(300));
+
+        // then
+        assert_eq!(call.outcome_timeout(), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn an_upload_waits_for_its_own_timeout() {
+        // given
+        let upload = Upload::new(PathBuf::from("flipper.contract"));
+
+        // when
+        let upload = upload.timeout(Duration::from_secs(300));
+
+        // then
+        assert_eq!(upload.outcome_timeout(), Duration::from_secs(300));
+    }
 }
@@ -14,12 +14,17 @@
 
 use crate::{
     uis::{
+        args,
+        decode,
+        retry,
+        wait,
+        ArgValue,
+        Browser,
         Call,
         ContractsUi,
-        Event,
         Events,
         Result,
-        TransactionError,
+        Token,
         TransactionResult,
         Upload,
     },
@@ -29,252 +34,215 @@ use crate::{
     },
 };
 use async_trait::async_trait;
-use fantoccini::{
-    error,
-    Locator,
+use convert_case::{
+    Case,
+    Casing,
+};
+use fantoccini::Locator;
+use std::{
+    ops::{
+        Deref,
+        DerefMut,
+    },
+    path::Path,
+    time::Duration,
 };
-use std::path::PathBuf;
 
-#[async_trait]
-impl ContractsUi for crate::uis::Ui {
-    /// Returns the balance postfix numbers.
-    async fn balance_postfix(&mut self, account: String) -> Result<u128> {
-        let log_id = format!("{} {}", test_name(), account.clone());
-        self.client
-            .goto(&format!(
-                "https://polkadot.js.org/apps/?rpc=ws%3A%2F%2F127.0.0.1%3A{}#/accounts",
-                utils::node_port()
-            ))
-            .await?;
+/// How long to wait for the UI to estimate the gas of a transaction.
+const ESTIMATION_TIMEOUT: Duration = Duration::from_secs(3);
 
-        // Firefox might not load if the website at that address is already open, hence we refresh
-        // just to be sure that it's a clean, freshly loaded page in front of us.
-        self.client.refresh().await?;
+/// The status which the UI displays once an upload has a final status.
+const UPLOAD_OUTCOME: Locator<'static> = Locator::XPath(
+    "//div[contains(@class, 'ui--Status')]//*/div[@class = 'status' and not(contains(text(),'ready') or contains(text(),'usurped') or contains(text(),'outdated'))]",
+);
 
-        log::info!("[{}] waiting for page to become visible", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//div[@class = 'menuSection']"))
-            .await?;
+/// The status which the UI displays once a transaction has a final status.
+const TRANSACTION_OUTCOME: Locator<'static> = Locator::XPath(
+    "//div[contains(@class, 'ui--Status')]//*/div[contains(text(),'system.ExtrinsicSuccess') or contains(text(), 'system.ExtrinsicFailed')]",
+);
 
-        std::thread::sleep(std::time::Duration::from_secs(6));
+/// Drives the `polkadot-js` UI in the browser.
+pub struct PolkadotJs(Browser);
 
-        let path = format!(
-            "//span[. = '{}']/ancestor::tr/following-sibling::tr//span[@class = 'ui--FormatBalance-postfix']",
-            account
-        );
-        let txt = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(&path))
-            .await?
-            .text()
-            .await?;
-        log::info!("[{}] found balance {} for account {}", log_id, txt, account);
-        Ok(txt.parse::<u128>().expect("failed parsing"))
+impl PolkadotJs {
+    /// Creates a new `PolkadotJs` instance for a chain with `token`, spawning a
+    /// browser session.
+    pub async fn new(token: Token) -> Result<Self> {
+        Ok(Self(Browser::new(token).await?))
+    }
+}
+
+impl Deref for PolkadotJs {
+    type Target = Browser;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for PolkadotJs {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
     }
+}
 
+#[async_trait]
+impl ContractsUi for PolkadotJs {
     /// Uploads the contract behind `contract_path`.
     ///
     /// # Developer Note
     ///
     /// This method must not make any assumptions about the state of the Ui before
     /// the method is invoked. It must e.g. open the upload page right at the start.
-    async fn execute_upload(&mut self, upload_input: Upload) -> Result<String> {
-        let log_id = format!("{}", test_name(),);
+    async fn execute_upload(
+        &mut self,
+        upload_input: Upload,
+    ) -> TransactionResult<String> {
+        let addr = self
+            .retry
+            .run_if("upload", retry::is_retryable_in_browser, || {
+                self.upload(&upload_input)
+            })
+            .await?;
+        self.contracts.load(&addr, &upload_input.contract_path)?;
+        Ok(addr)
+    }
+
+    /// Executes the RPC call `call`.
+    ///
+    /// # Developer Note
+    ///
+    /// This method must not make any assumptions about the state of the Ui before
+    /// the method is invoked. It must e.g. open the upload page right at the start.
+    async fn execute_rpc(&mut self, call: Call) -> Result<ArgValue> {
+        // the message has to be looked up before the names are adapted to the ui
+        let message = self
+            .contracts
+            .get(&call.contract_address)?
+            .message(&call.method)?
+            .label
+            .clone();
+        let call = normalize_call(call);
+        let log_id = format!("{} {}", test_name(), call.method.clone());
+
+        let url = url();
         log::info!(
-            "[{}] opening url for upload of {}: {:?}",
+            "[{}] opening url for rpc {:?}: {:?}",
             log_id,
-            upload_input
-                .contract_path
-                .file_name()
-                .expect("file name must exist")
-                .to_str()
-                .expect("conversion must work"),
-            url()
+            call.method,
+            url
         );
-        self.client.goto(&url()).await?;
+        self.client.goto(&url).await?;
 
-        // Firefox might not load if the website at that address is already open due to e.g.
-        // a prior `execute_transaction` call in the test. Hence we refresh just to be sure
-        // that it's a clean, freshly loaded page in front of us.
+        // Firefox might not load if the website at that address is already open, hence we refresh
+        // just to be sure that it's a clean, freshly loaded page in front of us.
         self.client.refresh().await?;
 
         log::info!("[{}] waiting for page to become visible", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//div[@class = 'menuSection']"))
+        self.wait_for_element(Locator::XPath("//div[@class = 'menuSection']"))
             .await?;
 
-        std::thread::sleep(std::time::Duration::from_secs(3));
-
-        log::info!("[{}] click upload", log_id);
-        self.click(Locator::XPath(
-            "//button[contains(text(),'Upload & deploy code')]",
-        ))
-        .await?;
-
-        log::info!("[{}] injecting jquery", log_id);
-        // The `inject` script will retry to load jQuery every 10 seconds.
-        // This is because the CI sometimes has spurious network errors.
-        let inject = String::from(
-            "(function (){\
-                    var d = document;\
-                    if (!d.getElementById('jquery')) {\
-                        function load_jquery() {\
-                            var d = document;\
-                            var s = d.createElement('script');\
-                            s.src = 'https://code.jquery.com/jquery-3.6.0.min.js';\
-                            s.id = 'jquery';\
-                            d.body.appendChild(s);\
-                        }\
-                        var jTimer = setInterval(function() {\
-                            load_jquery();\
-                        }, 10000);\
-                        load_jquery();\
-                        (function() {\
-                            var nTimer = setInterval(function() {\
-                                if (window.jQuery) {\
-                                    $('body').append('<div id=\"jquery-ready\"></div');\
-                                    clearInterval(nTimer);\
-                                    clearInterval(jTimer);\
-                                }\
-                            }, 100);\
-                        })();\
-                    }\
-                })();",
-        );
-        self.client.execute(&*inject, Vec::new()).await?;
-
-        log::info!("[{}] waiting for jquery", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::Css("#jquery-ready"))
-            .await?;
+        // iterate through the list and see which of the entries has the correct address
+        let contracts_in_list = self.wait_for_contracts().await?;
+        log::info!("[{}] found {} contracts in list", log_id, contracts_in_list);
 
-        log::info!("[{}] uploading {:?}", log_id, upload_input.contract_path);
-        let upload = self
-            .client
-            .find(Locator::XPath("//input[@type = 'file']"))
-            .await?;
-        upload
-            .send_keys(&upload_input.contract_path.display().to_string())
-            .await?;
-        self.client
-            .execute("$(\"input[type = 'file']\").trigger('change')", Vec::new())
+        let mut contract_index = None;
+        for index in (0..contracts_in_list + 1).rev() {
+            log::info!("[{}] checking contract {:?}", log_id, index);
+            self.click(Locator::XPath(&format!(
+                "(//span[contains(@class, 'ui--AccountName')])[{}]",
+                index
+            )))
             .await?;
 
-        if let Some(caller) = &upload_input.caller {
-            let caller = caller.to_lowercase();
-            // open listbox for accounts
-            log::info!("[{}] click listbox for accounts", log_id);
-            self.click(Locator::XPath(
-                    "//*[contains(text(),'deployment account')]/ancestor::div[1]",
-                ))
+            log::info!("[{}] getting contract address", log_id);
+            let addr = self
+                .text(Locator::XPath("//div[@class = 'ui--AddressMenu-addr']"))
                 .await?;
+            log::info!("[{}] contract address {}", log_id, addr);
 
-            std::thread::sleep(std::time::Duration::from_secs(3));
-
-            let key = "deployment account";
-            let value = caller;
             log::info!(
-                "[{}] inserting '{}' into input field '{}'",
+                "[{}] comparing {} == {}",
                 log_id,
-                value,
-                key
-            );
-            let path = format!(
-                "//*[contains(text(),'deployment account')]/ancestor::div[1]//*/input"
+                addr,
+                call.contract_address
             );
-            let input = self.client.find(Locator::XPath(&path)).await?;
-            // we need to clear a possible default input from the field
-            input.clear().await?;
-            input.send_keys(&value).await?;
-
-            log::info!("[{}] choosing account option '{}''", log_id, value);
-            let path = format!("//div[contains(@class, 'active')]//*/div[contains(@class, 'selected item')]");
-            self.client
-                .find(Locator::XPath(&path))
-                .await?
-                .click()
-                .await?;
+            if addr == call.contract_address {
+                log::info!("[{}] found contract address at index {:?}", log_id, index);
+                contract_index = Some(index);
+                break
+            }
         }
 
-        log::info!(
-            "[{}] wait for upload of {:?} to be finished",
-            log_id,
-            upload_input.contract_path
-        );
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                "//label[contains(text(), 'code bundle name')]",
-            ))
-            .await?;
+        let index = contract_index.expect("index must exist");
+        log::info!("[{}] close sidebar", log_id);
+        self.click(Locator::XPath(
+            "//div[contains(@class, 'ui--Sidebar')]/button",
+        ))
+        .await?;
 
-        log::info!(
-            "[{}] click next on {:?}",
-            log_id,
-            upload_input.contract_path
-        );
-        self.client
-            .find(Locator::XPath("//button[contains(text(), 'Next')]"))
+        log::info!("[{}] opening detail view for contract {:?}", log_id, index);
+        self.click(Locator::XPath(&format!(
+            "(//div[contains(@class, 'ui--Messages')])[{}]",
+            index
+        )))
+        .await?;
+
+        // assert that only one expanded method view exists
+        let expanded_views = self
+            .client
+            .find_all(Locator::XPath(
+                "//div[contains(@class, 'ui--Expander-content')]",
+            ))
             .await?
-            .click()
-            .await?;
+            .len();
+        assert!(
+            expanded_views == 1,
+            "found too many expanded views ({})!",
+            expanded_views
+        );
 
-        if let Some(constructor) = &upload_input.constructor {
-            log::info!("[{}] click constructor list box", log_id);
-            self.client
-                .wait().for_element(Locator::XPath(
-                    "//*[contains(text(),'deployment constructor')]/ancestor::div[1]//*/div[@role='listbox']"
-                ))
-                .await?.click().await?;
+        // click `method`
+        log::info!("[{}] try to find result for {:?}", log_id, call.method);
+        let path = format!("//span[@class = 'ui--MessageSignature-name' and (text() = '{}' or text() = '{}')]/ancestor::div[1]/ancestor::div[1]/ancestor::div[1]/div[contains(@class, 'result')]//div[@class = 'ui--Param-text ']", call.method, call.method.to_lowercase());
+        let possibly_rpc_result = self.client.find(Locator::XPath(&path)).await;
 
-            log::info!("[{}] click constructor option {}", log_id, constructor);
-            let path = format!(
-                "//span[@class = 'ui--MessageSignature-name' and contains(normalize-space(text()),'{}')]",
-                constructor
+        // if the rpc can be executed without params (e.g. `get(&self)`)
+        // the result is already shown
+        if possibly_rpc_result.is_ok() {
+            let result = possibly_rpc_result?.text().await?;
+            log::info!(
+                "[{}] found result for {:?}: {:?}",
+                log_id,
+                call.method,
+                result
             );
-            self.client
-                .wait()
-                .for_element(Locator::XPath(&path))
-                .await?
-                .click()
-                .await?;
+            return self.decode_outcome(&call.contract_address, &message, &result)
         }
 
-        for (key, value) in upload_input.initial_values.iter() {
-            // if the value is `Yes` or `No` we assume it's a listbox with a boolean
-            let mut value = transform_value(&value);
-            if value == "Yes" || value == "No" {
+        // otherwise we have to execute the rpc and set the params
+        log::info!("[{}] open rpc param details", log_id);
+        let path = format!("//span[@class = 'ui--MessageSignature-name' and (text() = '{}' or text() = '{}')]/ancestor::div[1]/ancestor::div[1]/ancestor::div[1]/button", call.method, call.method.to_lowercase());
+        self.click(Locator::XPath(&path)).await?;
+
+        // possibly set values
+        let (fields, items) = args::form_inputs(&call.values, &call.items);
+        for (key, arg) in fields {
+            let mut value = arg.render(&self.token)?;
+            if arg.is_choice() {
                 log::info!("[{}] opening dropdown list '{}'", log_id, key);
-                let path = format!(
-                    "//label/*[contains(normalize-space(text()),'{}')]/ancestor::div[1]",
-                    key
-                );
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                let path =
+                    format!("//label/*[contains(text(),'{}')]/ancestor::div[1]", key);
+                self.click(Locator::XPath(&path)).await?;
 
                 log::info!("[{}] choosing option '{}''", log_id, value);
-                let path = format!("//label/*[contains(normalize-space(text()),'{}')]/ancestor::div[1]//*/div[@role = 'option']/span[text() = '{}']", key, value);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
-            } else if is_account(&value) {
+                let path = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]//*/div[@role = 'option']/span[text() = '{}']", key, value);
+                self.click(Locator::XPath(&path)).await?;
+            } else if arg.is_dev_account() {
                 log::info!("[{}] opening dropdown list '{}'", log_id, key);
                 let path =
                     format!("//label/*[contains(text(),'{}')]/ancestor::div[1]", key);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                self.click(Locator::XPath(&path)).await?;
 
                 log::info!(
                     "[{}] inserting '{}' into input field '{}'",
@@ -284,402 +252,378 @@ impl ContractsUi for crate::uis::Ui {
                 );
                 let path =
                     format!("//*[contains(normalize-space(text()),'{}')]/ancestor::div[1]//*/input", key);
-                let input = self.client.find(Locator::XPath(&path)).await?;
                 // we need to clear a possible default input from the field
-                input.clear().await?;
-                input.send_keys(&value).await?;
+                self.fill(Locator::XPath(&path), &value).await?;
 
                 log::info!("[{}] choosing account option '{}''", log_id, value);
-                let path = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]//*/div[text() = '{}']", key, value);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                let path = "//div[contains(@class, 'active')]//*/div[contains(@class, 'selected item')]".to_string();
+                self.click(Locator::XPath(&path)).await?;
             } else {
-                log::info!(
-                    "[{}] inserting '{}' into input field '{}'",
-                    log_id,
-                    value,
+                log::info!("[{}] entering {:?} into {:?}", log_id, &value, &key);
+                let path = format!(
+                    "//*[contains(text(),'{}')]/ancestor::div[1]/div//input[@type = 'text']",
                     key
                 );
-                let path =
-                    format!("//*[contains(normalize-space(text()),'{}')]/ancestor::div[1]//*/input", key);
-                let input = self.client.find(Locator::XPath(&path)).await?;
-                // we need to clear a possible default input from the field
-                input.clear().await?;
                 value.push('\n');
-                input.send_keys(&value).await?;
+                self.fill(Locator::XPath(&path), &value).await?;
             }
         }
 
-        for (key, value) in upload_input.items.iter() {
+        // reset items which are already set as default by the ui
+        let remove_item = "//button[not(contains(@class, 'isDisabled')) and contains(text(), 'Remove item')]";
+        while self.client.find(Locator::XPath(remove_item)).await.is_ok() {
+            self.click(Locator::XPath(remove_item)).await?;
+        }
+
+        // possibly add items
+        for (key, arg) in items.iter() {
+            let value = arg.render_item()?;
             log::info!("[{}] adding item '{}' for '{}'", log_id, value, key);
             let add_item = format!("//div[contains(normalize-space(text()),'{}')]/ancestor::div[1]/ancestor::div[1]/*/button[contains(text(), 'Add item')]", key);
-            self.client
-                .find(Locator::XPath(&add_item))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(&add_item)).await?;
 
             let last_item = format!("//div[contains(normalize-space(text()),'{}')]/ancestor::div[1]/ancestor::div[1]/*/div[@class = 'ui--Params-Content']/div[last()]//input", key);
-            let input = self.client.find(Locator::XPath(&last_item)).await?;
             // we need to clear a possible default input from the field
-            input.clear().await?;
-            input.send_keys(&format!("{}\n", &value)).await?;
+            self.fill(Locator::XPath(&last_item), &format!("{}\n", &value))
+                .await?;
 
-            if is_account(&value) {
+            if arg.is_dev_account() {
                 log::info!("[{}] choosing account option '{}''", log_id, value);
-                let path = format!("//div[contains(@class, 'active')]//*/div[contains(@class, 'selected item')]");
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
+                let path = "//div[contains(@class, 'active')]//*/div[contains(@class, 'selected item')]".to_string();
+                self.click(Locator::XPath(&path)).await?;
+            }
+        }
+
+        // click call
+        log::info!("[{}] click read", log_id);
+        let is_read_button_available = self
+            .client
+            .find(Locator::XPath("//button[contains(text(),'Read')]"))
+            .await;
+
+        match is_read_button_available {
+            Ok(el) => el.click().await?,
+            Err(_) => {
+                let path = "//*[contains(text(),'read contract only, no execution')]/ancestor::div[1]//div[contains(@class,'ui--Toggle')]";
+                self.click(Locator::XPath(path)).await?;
+
+                self.click(Locator::XPath("//button[contains(text(),'Read')]"))
                     .await?;
             }
         }
 
-        log::info!("[{}] click deploy", log_id);
-        self.client
-            .find(Locator::XPath("//button[contains(text(),'Deploy')]"))
-            .await?
-            .click()
+        log::info!("[{}] wait for outcome to appear", log_id);
+        self
+            .wait_for_element(Locator::XPath("//div[contains(text(),'Call results')]/ancestor::div[1]/ancestor::div[1]/div[@class = 'ui--Expander-content']"))
             .await?;
 
-        log::info!("[{}] click sign and submit", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                "//button[contains(text(),'Sign and Submit')]",
+        log::info!("[{}] read outcome", log_id);
+        let outcome = self
+            .wait_for_element(Locator::XPath(
+                "(//div[contains(@class, 'ui--output')])[last()]/div",
             ))
             .await?
-            .click()
+            .text()
             .await?;
+        log::info!("[{}] outcome text is {:?}", log_id, outcome);
+        self.decode_outcome(&call.contract_address, &message, &outcome)
+    }
+
+    /// Executes the transaction `call`.
+    ///
+    /// # Developer Note
+    ///
+    /// This method must not make any assumptions about the state of the Ui before
+    /// the method is invoked. It must e.g. open the upload page right at the start.
+    async fn execute_transaction(&mut self, call: Call) -> TransactionResult<Events> {
+        let call = normalize_call(call);
+        self.retry
+            .run_if(
+                &format!("transaction {:?}", call.method),
+                retry::is_retryable_in_browser,
+                || self.transaction(&call),
+            )
+            .await
+    }
 
+    /// Updates the metadata which the UI uses for interacting with the contract
+    /// at `contract_addr` to `new_abi`.
+    async fn update_metadata(
+        &mut self,
+        contract_addr: &str,
+        new_abi: &Path,
+    ) -> Result<String> {
+        let log_id = test_name().to_string();
         log::info!(
-            "[{}] upload: waiting for either success or failure notification {:?}",
+            "[{}] opening url for updating metadata of {}: {:?}",
             log_id,
-            upload_input.contract_path
+            contract_addr,
+            url()
         );
+        self.client.goto(&url()).await?;
 
-        let mut res;
-        for waited in 0..26 {
-            std::thread::sleep(std::time::Duration::from_secs(5));
-            res = self.client.find(
-                Locator::XPath("//div[contains(@class, 'ui--Status')]//*/div[@class = 'status' and not(contains(text(),'ready') or contains(text(),'usurped') or contains(text(),'outdated'))]")
-            ).await;
-            if res.is_ok() {
-                log::info!(
-                    "[{}] upload: status contains {:?}",
-                    log_id,
-                    self.client
-                        .find(Locator::XPath("//div[contains(@class, 'ui--Status')]"))
-                        .await?
-                        .text()
-                        .await?
-                );
-                log::info!(
-                    "[{}] upload: found status {:?}",
-                    log_id,
-                    res.expect("res must exist here").text().await?
-                );
-                log::info!(
-                    "[{}] upload: success for {:?} after waiting {}",
-                    log_id,
-                    upload_input.contract_path,
-                    waited,
-                );
-                break
-            } else {
-                if waited == 25 {
-                    log::info!(
-                        "[{}] timed out on waiting for {:?} upload! next recursion.",
-                        log_id,
-                        upload_input.contract_path
-                    );
-                    return self.execute_upload(upload_input.clone()).await
-                } else {
-                    log::info!(
-                        "[{}] timed out on waiting for {:?} upload after {}! sleeping.",
-                        log_id,
-                        upload_input.contract_path,
-                        waited
-                    );
-                }
-            }
-        }
+        // Firefox might not load if the website at that address is already open due to e.g.
+        // a prior `execute_transaction` call in the test. Hence we refresh just to be sure
+        // that it's a clean, freshly loaded page in front of us.
+        self.client.refresh().await?;
 
-        log::info!(
-            "[{}] upload: extracting status messages {:?}",
-            log_id,
-            upload_input.contract_path
-        );
-        let statuses = self
-            .client
-            .find_all(Locator::XPath(
-                "//div[contains(@class, 'ui--Status')]//div[@class = 'desc']//div[@class = 'header']//div",
-            ))
+        log::info!("[{}] waiting for page to become visible", log_id);
+        self.wait_for_element(Locator::XPath("//div[@class = 'menuSection']"))
             .await?;
-        let mut statuses_processed = Vec::new();
-        for el in statuses {
-            // the switch of status vs. header is intentional here
-            let txt = el.html(true).await?.to_string().replace("\"", "");
-            statuses_processed.push(Event {
-                // TODO remove `header` as a field altogether
-                header: String::from(""),
-                status: txt,
-            });
-        }
-        for status in &statuses_processed {
-            log::info!("[{}] upload: found status {:?}", log_id, status,);
-        }
-        let events = Events::new(statuses_processed);
-        if events.contains("Priority is too low") {
-            log::info!(
-                "[{}] found priority too low during upload of {:?}! trying again!",
-                log_id,
-                upload_input.contract_path
-            );
-            return self.execute_upload(upload_input.clone()).await
-        } else if events.contains("usurped") {
-            log::info!(
-                "[{}] found usurped for upload of {:?}! trying again!",
-                log_id,
-                upload_input.contract_path
-            );
-            return self.execute_upload(upload_input.clone()).await
-        } else if events.contains("outdated") {
-            log::info!(
-                "[{}] found outdated for upload of {:?}! trying again!",
-                log_id,
-                upload_input.contract_path
-            );
-            return self.execute_upload(upload_input.clone()).await
-        } else {
-            log::info!(
-                "[{}] did not find priority too low in {:?} status messages {:?}",
-                log_id,
-                events.events.len(),
-                upload_input.contract_path
-            );
-        }
-        assert!(
-            events.contains("system.ExtrinsicSuccess"),
-            "upload must have succeeded, but events contain only {:?}",
-            events.events
+
+        log::info!("[{}] injecting jquery", log_id);
+        // The `inject` script will retry to load jQuery every 10 seconds.
+        // This is because the CI sometimes has spurious network errors.
+        let inject = String::from(
+            "(function (){\
+                    var d = document;\
+                    if (!d.getElementById('jquery')) {\
+                        function load_jquery() {\
+                            var d = document;\
+                            var s = d.createElement('script');\
+                            s.src = 'https://code.jquery.com/jquery-3.6.0.min.js';\
+                            s.id = 'jquery';\
+                            d.body.appendChild(s);\
+                        }\
+                        var jTimer = setInterval(function() {\
+                            load_jquery();\
+                        }, 10000);\
+                        load_jquery();\
+                        (function() {\
+                            var nTimer = setInterval(function() {\
+                                if (window.jQuery) {\
+                                    $('body').append('<div id=\"jquery-ready\"></div');\
+                                    clearInterval(nTimer);\
+                                    clearInterval(jTimer);\
+                                }\
+                            }, 100);\
+                        })();\
+                    }\
+                })();",
         );
+        self.client.execute(&inject, Vec::new()).await?;
 
-        // wait for disappearance animation to finish instead
-        // otherwise the notifications might occlude buttons
-        log::info!("[{}] wait for animation to finish", log_id);
+        log::info!("[{}] waiting for jquery", log_id);
+        self.wait_for_element(Locator::Css("#jquery-ready")).await?;
+
+        log::info!("[{}] click 'Add an existing contract'", log_id);
+        self.click(Locator::XPath(
+            "//button[contains(text(),'Add an existing contract')]",
+        ))
+        .await?;
+
+        log::info!("[{}] entering contract address {:?}", log_id, contract_addr);
+        let path = "//input[@data-testid = 'contract address']";
+
+        // The following form submission failed when the input was done all-at-once.
+        // To overcome this UI quirk, we type in the address here in two batches.
+        let mut first_typed = contract_addr.to_string();
+        let last_typed = first_typed
+            .pop()
+            .expect("the contract address should not be empty!")
+            .to_string();
+
+        self.fill(Locator::XPath(path), &first_typed).await?;
+        self.send_keys(Locator::XPath(path), &last_typed).await?;
+
+        log::info!("[{}] uploading {:?}", log_id, new_abi);
+        self.send_keys(
+            Locator::XPath("//input[@type = 'file']"),
+            &new_abi.display().to_string(),
+        )
+        .await?;
         self.client
-            .execute("$('.ui--Status').hide()", Vec::new())
+            .execute("$(\"input[type = 'file']\").trigger('change')", Vec::new())
             .await?;
 
         log::info!(
-            "[{}] click on recently added contract in list (the last one)",
-            log_id
+            "[{}] wait for upload of {:?} to be finished",
+            log_id,
+            new_abi
         );
-        self.client
-            .find(Locator::XPath(
-                "(//span[contains(@class, 'ui--AccountName')])[last()]",
-            ))
-            .await?
-            .click()
-            .await?;
+        self.wait_for_element(Locator::XPath(
+            "//div[contains(text(), 'Constructors (')]",
+        ))
+        .await?;
 
-        log::info!("[{}] getting contract address", log_id);
-        let addr = self
-            .client
-            .wait()
-            .for_element(Locator::XPath("//div[@class = 'ui--AddressMenu-addr']"))
-            .await?
-            .text()
+        log::info!("[{}] click save on {:?}", log_id, new_abi);
+        self.click(Locator::XPath("//button[contains(text(), 'Save')]"))
             .await?;
-        log::info!("[{}] contract address {:?}", log_id, addr);
 
-        log::info!("[{}] close sidebar", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                "//div[contains(@class, 'ui--Sidebar')]/button",
-            ))
-            .await?
-            .click()
-            .await?;
+        self.contracts.load(contract_addr, new_abi)?;
+        Ok(String::from(""))
+    }
 
-        Ok(String::from(addr))
+    /// Closes the browser session.
+    async fn shutdown(&mut self) -> Result<()> {
+        self.0.shutdown().await
     }
 
-    /// Executes the RPC call `call`.
-    ///
-    /// # Developer Note
-    ///
-    /// This method must not make any assumptions about the state of the Ui before
-    /// the method is invoked. It must e.g. open the upload page right at the start.
-    async fn execute_rpc(&mut self, call: Call) -> Result<String> {
-        let log_id = format!("{} {}", test_name(), call.method.clone());
+    async fn save_failure_artifacts(&mut self, dir: &Path) -> Result<()> {
+        self.0.save_failure_artifacts(dir).await
+    }
+}
 
-        let url = url();
+impl PolkadotJs {
+    /// Uploads the contract of `upload_input` once, without retrying, and
+    /// returns its address.
+    async fn upload(&self, upload_input: &Upload) -> TransactionResult<String> {
+        let log_id = test_name().to_string();
         log::info!(
-            "[{}] opening url for rpc {:?}: {:?}",
+            "[{}] opening url for upload of {}: {:?}",
             log_id,
-            call.method,
-            url
+            upload_input
+                .contract_path
+                .file_name()
+                .expect("file name must exist")
+                .to_str()
+                .expect("conversion must work"),
+            url()
         );
-        self.client.goto(&url).await?;
+        self.client.goto(&url()).await?;
 
-        // Firefox might not load if the website at that address is already open, hence we refresh
-        // just to be sure that it's a clean, freshly loaded page in front of us.
+        // Firefox might not load if the website at that address is already open due to e.g.
+        // a prior `execute_transaction` call in the test. Hence we refresh just to be sure
+        // that it's a clean, freshly loaded page in front of us.
         self.client.refresh().await?;
 
         log::info!("[{}] waiting for page to become visible", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//div[@class = 'menuSection']"))
+        self.wait_for_element(Locator::XPath("//div[@class = 'menuSection']"))
             .await?;
 
-        std::thread::sleep(std::time::Duration::from_secs(3));
+        log::info!("[{}] click upload", log_id);
+        self.click(Locator::XPath(
+            "//button[contains(text(),'Upload & deploy code')]",
+        ))
+        .await?;
 
-        // iterate through the list and see which of the entries has the correct address
-        let contracts_in_list = self
-            .client
-            .find_all(Locator::XPath("//span[contains(@class, 'ui--AccountName')]"))
-            .await?
-            .len();
-        log::info!("[{}] found {} contracts in list", log_id, contracts_in_list);
-        assert!(
-            contracts_in_list > 0,
-            "there must be more than zero contracts in the list!"
+        log::info!("[{}] injecting jquery", log_id);
+        // The `inject` script will retry to load jQuery every 10 seconds.
+        // This is because the CI sometimes has spurious network errors.
+        let inject = String::from(
+            "(function (){\
+                    var d = document;\
+                    if (!d.getElementById('jquery')) {\
+                        function load_jquery() {\
+                            var d = document;\
+                            var s = d.createElement('script');\
+                            s.src = 'https://code.jquery.com/jquery-3.6.0.min.js';\
+                            s.id = 'jquery';\
+                            d.body.appendChild(s);\
+                        }\
+                        var jTimer = setInterval(function() {\
+                            load_jquery();\
+                        }, 10000);\
+                        load_jquery();\
+                        (function() {\
+                            var nTimer = setInterval(function() {\
+                                if (window.jQuery) {\
+                                    $('body').append('<div id=\"jquery-ready\"></div');\
+                                    clearInterval(nTimer);\
+                                    clearInterval(jTimer);\
+                                }\
+                            }, 100);\
+                        })();\
+                    }\
+                })();",
         );
+        self.client.execute(&inject, Vec::new()).await?;
 
-        let mut contract_index = None;
-        for index in (0..contracts_in_list + 1).rev() {
-            log::info!("[{}] checking contract {:?}", log_id, index);
-            self.client
-                .find(Locator::XPath(&format!(
-                    "(//span[contains(@class, 'ui--AccountName')])[{}]",
-                    index
-                )))
-                .await?
-                .click()
-                .await?;
+        log::info!("[{}] waiting for jquery", log_id);
+        self.wait_for_element(Locator::Css("#jquery-ready")).await?;
 
-            log::info!("[{}] getting contract address", log_id);
-            let addr = self
-                .client
-                .find(Locator::XPath("//div[@class = 'ui--AddressMenu-addr']"))
-                .await?
-                .text()
-                .await?;
-            log::info!("[{}] contract address {}", log_id, addr);
+        log::info!("[{}] uploading {:?}", log_id, upload_input.contract_path);
+        self.send_keys(
+            Locator::XPath("//input[@type = 'file']"),
+            &upload_input.contract_path.display().to_string(),
+        )
+        .await?;
+        self.client
+            .execute("$(\"input[type = 'file']\").trigger('change')", Vec::new())
+            .await?;
 
+        if let Some(caller) = &upload_input.caller {
+            let caller = caller.to_lowercase();
+            // open listbox for accounts
+            log::info!("[{}] click listbox for accounts", log_id);
+            self.click(Locator::XPath(
+                "//*[contains(text(),'deployment account')]/ancestor::div[1]",
+            ))
+            .await?;
+
+            let key = "deployment account";
+            let value = caller;
             log::info!(
-                "[{}] comparing {} == {}",
+                "[{}] inserting '{}' into input field '{}'",
                 log_id,
-                addr,
-                call.contract_address
+                value,
+                key
             );
-            if addr == call.contract_address {
-                log::info!("[{}] found contract address at index {:?}", log_id, index);
-                contract_index = Some(index);
-                break
-            }
-        }
+            let path =
+                "//*[contains(text(),'deployment account')]/ancestor::div[1]//*/input"
+                    .to_string();
+            self.wait_for_element(Locator::XPath(&path)).await?;
+            // we need to clear a possible default input from the field
+            self.fill(Locator::XPath(&path), &value).await?;
 
-        let index = contract_index.expect("index must exist");
-        log::info!("[{}] close sidebar", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                "//div[contains(@class, 'ui--Sidebar')]/button",
-            ))
-            .await?
-            .click()
-            .await?;
+            log::info!("[{}] choosing account option '{}''", log_id, value);
+            let path = "//div[contains(@class, 'active')]//*/div[contains(@class, 'selected item')]".to_string();
+            self.click(Locator::XPath(&path)).await?;
+        }
 
-        log::info!("[{}] opening detail view for contract {:?}", log_id, index);
-        self.client
-            .find(Locator::XPath(&format!(
-                "(//div[contains(@class, 'ui--Messages')])[{}]",
-                index
-            )))
-            .await?
-            .click()
-            .await?;
+        log::info!(
+            "[{}] wait for upload of {:?} to be finished",
+            log_id,
+            upload_input.contract_path
+        );
+        self.wait_for_element(Locator::XPath(
+            "//label[contains(text(), 'code bundle name')]",
+        ))
+        .await?;
 
-        // assert that only one expanded method view exists
-        let expanded_views = self
-            .client
-            .find_all(Locator::XPath(
-                "//div[contains(@class, 'ui--Expander-content')]",
-            ))
-            .await?
-            .len();
-        assert!(
-            expanded_views == 1,
-            "found too many expanded views ({})!",
-            expanded_views
+        log::info!(
+            "[{}] click next on {:?}",
+            log_id,
+            upload_input.contract_path
         );
+        self.click(Locator::XPath("//button[contains(text(), 'Next')]"))
+            .await?;
 
-        // click `method`
-        log::info!("[{}] try to find result for {:?}", log_id, call.method);
-        let path = format!("//span[@class = 'ui--MessageSignature-name' and (text() = '{}' or text() = '{}')]/ancestor::div[1]/ancestor::div[1]/ancestor::div[1]/div[contains(@class, 'result')]//div[@class = 'ui--Param-text ']", call.method, call.method.to_lowercase());
-        let possibly_rpc_result = self.client.find(Locator::XPath(&path)).await;
+        if let Some(constructor) = &upload_input.constructor {
+            log::info!("[{}] click constructor list box", log_id);
+            self.click(Locator::XPath(
+                    "//*[contains(text(),'deployment constructor')]/ancestor::div[1]//*/div[@role='listbox']"
+                )).await?;
 
-        // if the rpc can be executed without params (e.g. `get(&self)`)
-        // the result is already shown
-        if possibly_rpc_result.is_ok() {
-            let result = possibly_rpc_result?.text().await?;
-            log::info!(
-                "[{}] found result for {:?}: {:?}",
-                log_id,
-                call.method,
-                result
+            log::info!("[{}] click constructor option {}", log_id, constructor);
+            let path = format!(
+                "//span[@class = 'ui--MessageSignature-name' and contains(normalize-space(text()),'{}')]",
+                constructor
             );
-            return Ok(result)
+            self.click(Locator::XPath(&path)).await?;
         }
 
-        // otherwise we have to execute the rpc and set the params
-        log::info!("[{}] open rpc param details", log_id);
-        let path = format!("//span[@class = 'ui--MessageSignature-name' and (text() = '{}' or text() = '{}')]/ancestor::div[1]/ancestor::div[1]/ancestor::div[1]/button", call.method, call.method.to_lowercase());
-        self.client
-            .wait()
-            .for_element(Locator::XPath(&path))
-            .await?
-            .click()
-            .await?;
-
-        // possibly set values
-        for (key, value) in call.values {
-            // if the value is `Yes` or `No` we assume it's a listbox with a boolean
-            let mut value = transform_value(&value);
-            if value == "Yes" || value == "No" {
+        let (fields, items) =
+            args::form_inputs(&upload_input.initial_values, &upload_input.items);
+        for (key, arg) in fields.iter() {
+            let key = ui_arg_name(key);
+            let mut value = arg.render(&self.token)?;
+            if arg.is_choice() {
                 log::info!("[{}] opening dropdown list '{}'", log_id, key);
-                let path =
-                    format!("//label/*[contains(text(),'{}')]/ancestor::div[1]", key);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                let path = format!(
+                    "//label/*[contains(normalize-space(text()),'{}')]/ancestor::div[1]",
+                    key
+                );
+                self.click(Locator::XPath(&path)).await?;
 
                 log::info!("[{}] choosing option '{}''", log_id, value);
-                let path = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]//*/div[@role = 'option']/span[text() = '{}']", key, value);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
-            } else if is_account(&value) {
+                let path = format!("//label/*[contains(normalize-space(text()),'{}')]/ancestor::div[1]//*/div[@role = 'option']/span[text() = '{}']", key, value);
+                self.click(Locator::XPath(&path)).await?;
+            } else if arg.is_dev_account() {
                 log::info!("[{}] opening dropdown list '{}'", log_id, key);
                 let path =
                     format!("//label/*[contains(text(),'{}')]/ancestor::div[1]", key);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                self.click(Locator::XPath(&path)).await?;
 
                 log::info!(
                     "[{}] inserting '{}' into input field '{}'",
@@ -689,156 +633,123 @@ impl ContractsUi for crate::uis::Ui {
                 );
                 let path =
                     format!("//*[contains(normalize-space(text()),'{}')]/ancestor::div[1]//*/input", key);
-                let input = self.client.find(Locator::XPath(&path)).await?;
                 // we need to clear a possible default input from the field
-                input.clear().await?;
-                input.send_keys(&value).await?;
+                self.fill(Locator::XPath(&path), &value).await?;
 
                 log::info!("[{}] choosing account option '{}''", log_id, value);
-                let path = format!("//div[contains(@class, 'active')]//*/div[contains(@class, 'selected item')]");
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                let path = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]//*/div[text() = '{}']", key, value);
+                self.click(Locator::XPath(&path)).await?;
             } else {
-                log::info!("[{}] entering {:?} into {:?}", log_id, &value, &key);
-                let path = format!(
-                    "//*[contains(text(),'{}')]/ancestor::div[1]/div//input[@type = 'text']",
+                log::info!(
+                    "[{}] inserting '{}' into input field '{}'",
+                    log_id,
+                    value,
                     key
                 );
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .clear()
-                    .await?;
+                let path =
+                    format!("//*[contains(normalize-space(text()),'{}')]/ancestor::div[1]//*/input", key);
+                // we need to clear a possible default input from the field
                 value.push('\n');
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .send_keys(&value)
-                    .await?;
+                self.fill(Locator::XPath(&path), &value).await?;
             }
         }
 
-        // reset items which are already set as default by the ui
-        let remove_item = "//button[not(contains(@class, 'isDisabled')) and contains(text(), 'Remove item')]";
-        while self.client.find(Locator::XPath(&remove_item)).await.is_ok() {
-            self.client
-                .find(Locator::XPath(&remove_item))
-                .await?
-                .click()
-                .await?;
-        }
-
-        // possibly add items
-        for (key, value) in call.items.iter() {
+        for (key, arg) in items.iter() {
+            let value = arg.render_item()?;
             log::info!("[{}] adding item '{}' for '{}'", log_id, value, key);
             let add_item = format!("//div[contains(normalize-space(text()),'{}')]/ancestor::div[1]/ancestor::div[1]/*/button[contains(text(), 'Add item')]", key);
-            self.client
-                .find(Locator::XPath(&add_item))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(&add_item)).await?;
 
             let last_item = format!("//div[contains(normalize-space(text()),'{}')]/ancestor::div[1]/ancestor::div[1]/*/div[@class = 'ui--Params-Content']/div[last()]//input", key);
-            let input = self.client.find(Locator::XPath(&last_item)).await?;
             // we need to clear a possible default input from the field
-            input.clear().await?;
-            input.send_keys(&format!("{}\n", &value)).await?;
+            self.fill(Locator::XPath(&last_item), &format!("{}\n", &value))
+                .await?;
 
-            if is_account(&value) {
+            if arg.is_dev_account() {
                 log::info!("[{}] choosing account option '{}''", log_id, value);
-                let path = format!("//div[contains(@class, 'active')]//*/div[contains(@class, 'selected item')]");
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                let path = "//div[contains(@class, 'active')]//*/div[contains(@class, 'selected item')]".to_string();
+                self.click(Locator::XPath(&path)).await?;
             }
         }
 
-        // click call
-        log::info!("[{}] click read", log_id);
-        let is_read_button_available = self
-            .client
-            .find(Locator::XPath("//button[contains(text(),'Read')]"))
-            .await;
+        log::info!("[{}] click deploy", log_id);
+        self.click(Locator::XPath("//button[contains(text(),'Deploy')]"))
+            .await?;
 
-        match is_read_button_available {
-            Ok(el) => el.click().await?,
-            Err(_) => {
-                let path = "//*[contains(text(),'read contract only, no execution')]/ancestor::div[1]//div[contains(@class,'ui--Toggle')]";
-                self.client
-                    .find(Locator::XPath(path))
-                    .await?
-                    .click()
-                    .await?;
+        log::info!("[{}] click sign and submit", log_id);
+        self.click(Locator::XPath(
+            "//button[contains(text(),'Sign and Submit')]",
+        ))
+        .await?;
 
-                self.client
-                    .find(Locator::XPath("//button[contains(text(),'Read')]"))
-                    .await?
-                    .click()
-                    .await?;
-            }
+        log::info!(
+            "[{}] upload: waiting for either success or failure notification {:?}",
+            log_id,
+            upload_input.contract_path
+        );
+
+        self.wait_for_status(UPLOAD_OUTCOME, upload_input.outcome_timeout())
+            .await?;
+
+        log::info!(
+            "[{}] upload: extracting status messages {:?}",
+            log_id,
+            upload_input.contract_path
+        );
+        let statuses = self
+            .client
+            .find_all(Locator::XPath(
+                "//div[contains(@class, 'ui--Status')]//div[@class = 'desc']//div[@class = 'header']//div",
+            ))
+            .await?;
+        let mut statuses_processed = Vec::new();
+        for el in statuses {
+            // the switch of status vs. header is intentional here
+            let txt = el.html(true).await?.to_string().replace("\"", "");
+            statuses_processed.push((txt, String::new()));
+        }
+        for (status, _) in &statuses_processed {
+            log::info!("[{}] upload: found status {:?}", log_id, status,);
         }
+        let events = Events::from_messages(statuses_processed);
+        log::info!("[{}] found the events {}", log_id, events);
+        events.into_outcome()?;
 
-        log::info!("[{}] wait for outcome to appear", log_id);
+        // wait for disappearance animation to finish instead
+        // otherwise the notifications might occlude buttons
+        log::info!("[{}] wait for animation to finish", log_id);
         self.client
-            .wait().for_element(Locator::XPath("//div[contains(text(),'Call results')]/ancestor::div[1]/ancestor::div[1]/div[@class = 'ui--Expander-content']"))
+            .execute("$('.ui--Status').hide()", Vec::new())
             .await?;
 
-        log::info!("[{}] read outcome", log_id);
-        let mut ret_value = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                "(//div[contains(@class, 'ui--output')])[last()]/div",
-            ))
-            .await?
-            .text()
-            .await
-            .map(|str| str.replace("{ Ok: ", "").replace(" }", ""))?;
+        log::info!(
+            "[{}] click on recently added contract in list (the last one)",
+            log_id
+        );
+        self.click(Locator::XPath(
+            "(//span[contains(@class, 'ui--AccountName')])[last()]",
+        ))
+        .await?;
 
-        log::info!("[{}] read outcome type", log_id);
-        let ret_type = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(
-                "(//span[@class = 'ui--MessageSignature-returnType'])[last()]",
-            ))
+        log::info!("[{}] getting contract address", log_id);
+        let addr = self
+            .wait_for_element(Locator::XPath("//div[@class = 'ui--AddressMenu-addr']"))
             .await?
             .text()
             .await?;
+        log::info!("[{}] contract address {:?}", log_id, addr);
 
-        if (ret_type.contains("AccountId") || ret_type.contains("accountid"))
-            && ret_value != "<none>"
-            && ret_value != "<empty>"
-            && ret_value != "null"
-        {
-            // convert hash account id to mnemonic name
-            log::info!("[{}] attempting to resolve {}", log_id, &ret_value);
-            ret_value = name_to_address(&ret_value).expect("address for name must exist");
-            log::info!("[{}] resolved to {}", log_id, &ret_value);
-        }
-
-        if ret_value == "<none>" || ret_value == "null" {
-            ret_value = "None".to_string();
-        }
-
-        log::info!("[{}] outcome value is {:?}", log_id, ret_value);
-        log::info!("[{}] outcome type value is {:?}", log_id, ret_type);
+        log::info!("[{}] close sidebar", log_id);
+        self.click(Locator::XPath(
+            "//div[contains(@class, 'ui--Sidebar')]/button",
+        ))
+        .await?;
 
-        Ok(ret_value)
+        Ok(addr)
     }
 
-    /// Executes the transaction `call`.
-    ///
-    /// # Developer Note
-    ///
-    /// This method must not make any assumptions about the state of the Ui before
-    /// the method is invoked. It must e.g. open the upload page right at the start.
-    async fn execute_transaction(&mut self, call: Call) -> TransactionResult<Events> {
+    /// Executes the transaction `call` once, without retrying.
+    async fn transaction(&self, call: &Call) -> TransactionResult<Events> {
         let log_id = format!("{} {}", test_name(), call.method.clone());
 
         let url = url();
@@ -855,43 +766,25 @@ impl ContractsUi for crate::uis::Ui {
         self.client.refresh().await?;
 
         log::info!("[{}] waiting for page to become visible", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//div[@class = 'menuSection']"))
+        self.wait_for_element(Locator::XPath("//div[@class = 'menuSection']"))
             .await?;
 
-        std::thread::sleep(std::time::Duration::from_secs(6));
-
         // iterate through the list and see which of the entries has the correct address
-        let contracts_in_list = self
-            .client
-            .find_all(Locator::XPath("//span[contains(@class, 'ui--AccountName')]"))
-            .await?
-            .len();
+        let contracts_in_list = self.wait_for_contracts().await?;
         log::info!("[{}] found {} contracts in list", log_id, contracts_in_list);
-        assert!(
-            contracts_in_list > 0,
-            "there must be more than zero contracts in the list!"
-        );
 
         let mut contract_index = None;
         for index in (0..contracts_in_list + 1).rev() {
             log::info!("[{}] checking contract {:?}", log_id, index);
-            self.client
-                .find(Locator::XPath(&format!(
-                    "(//span[contains(@class, 'ui--AccountName')])[{}]",
-                    index
-                )))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(&format!(
+                "(//span[contains(@class, 'ui--AccountName')])[{}]",
+                index
+            )))
+            .await?;
 
             log::info!("[{}] getting contract address", log_id);
             let addr = self
-                .client
-                .find(Locator::XPath("//div[@class = 'ui--AddressMenu-addr']"))
-                .await?
-                .text()
+                .text(Locator::XPath("//div[@class = 'ui--AddressMenu-addr']"))
                 .await?;
             log::info!("[{}] contract address {}", log_id, addr);
 
@@ -910,24 +803,17 @@ impl ContractsUi for crate::uis::Ui {
 
         let index = contract_index.expect("index must exist");
         log::info!("[{}] close sidebar", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                "//div[contains(@class, 'ui--Sidebar')]/button",
-            ))
-            .await?
-            .click()
-            .await?;
+        self.click(Locator::XPath(
+            "//div[contains(@class, 'ui--Sidebar')]/button",
+        ))
+        .await?;
 
         log::info!("[{}] opening detail view for contract {:?}", log_id, index);
-        self.client
-            .find(Locator::XPath(&format!(
-                "(//div[contains(@class, 'ui--Messages')])[{}]",
-                index
-            )))
-            .await?
-            .click()
-            .await?;
+        self.click(Locator::XPath(&format!(
+            "(//div[contains(@class, 'ui--Messages')])[{}]",
+            index
+        )))
+        .await?;
 
         // assert that only one expanded method view exists
         let expanded_views = self
@@ -945,62 +831,35 @@ impl ContractsUi for crate::uis::Ui {
 
         log::info!("[{}] open exec details", log_id);
         let path = format!("//span[@class = 'ui--MessageSignature-name' and (text() = '{}')]/ancestor::div[1]/ancestor::div[1]/ancestor::div[1]/button", call.method);
-        self.client
-            .wait()
-            .for_element(Locator::XPath(&path))
-            .await?
-            .click()
-            .await?;
+        self.click(Locator::XPath(&path)).await?;
 
         log::info!("[{}] waiting for exec details to appear", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//h1[text() = 'Call a contract']"))
+        self.wait_for_element(Locator::XPath("//h1[text() = 'Call a contract']"))
             .await?;
 
         if let Some(caller) = &call.caller {
             // open listbox for accounts
             log::info!("[{}] click listbox for accounts", log_id);
-            self.client
-                .wait()
-                .for_element(Locator::XPath(
-                    "//*[contains(text(),'call from account')]/ancestor::div[1]/div",
-                ))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(
+                "//*[contains(text(),'call from account')]/ancestor::div[1]/div",
+            ))
+            .await?;
 
             // enter the caller
             log::info!("[{}] entering {:?} into listbox", log_id, caller);
-            let path = format!(
+            let path =
                 "//*[contains(text(),'call from account')]/ancestor::div[1]//input"
-            );
-            self.client
-                .find(Locator::XPath(&path))
-                .await?
-                .clear()
-                .await?;
-            self.client
-                .find(Locator::XPath(&path))
-                .await?
-                .send_keys(&format!("{}\n", caller))
+                    .to_string();
+            self.fill(Locator::XPath(&path), &format!("{}\n", caller))
                 .await?;
         }
 
         // possibly add payment
         if let Some(payment) = &call.payment {
-            log::info!("[{}] entering payment {:?}", log_id, payment.payment);
+            let payment = payment.in_units(self.token.decimals);
+            log::info!("[{}] entering payment {:?}", log_id, payment);
             let path = "//*[contains(text(),'value')]/ancestor::div[1]/div//input[@type = 'text']";
-            self.client
-                .find(Locator::XPath(path))
-                .await?
-                .clear()
-                .await?;
-            self.client
-                .find(Locator::XPath(path))
-                .await?
-                .send_keys(&payment.payment)
-                .await?;
+            self.fill(Locator::XPath(path), &payment).await?;
         }
 
         // possibly set max gas
@@ -1009,17 +868,14 @@ impl ContractsUi for crate::uis::Ui {
             // e.g. results in `ContractTrapped`).
             log::info!("[{}] possibly unset 'use estimated gas' checkbox", log_id);
             let max_gas_input_path = "//*[contains(text(),'max gas allowed')]/ancestor::div[1]/div//input[@type = 'text']";
-            let max_gas_input = self
-                .client
-                .wait()
-                .for_element(Locator::XPath(max_gas_input_path))
+            self.wait_for_element(Locator::XPath(max_gas_input_path))
                 .await?;
 
-            std::thread::sleep(std::time::Duration::from_secs(3));
-
             let path = "//*[contains(text(),'use estimated gas')]/ancestor::div[1]/div";
-            let possibly_estimated_gas = self.client.find(Locator::XPath(path)).await;
-            if let Ok(el) = possibly_estimated_gas {
+            let possibly_estimated_gas = self
+                .wait_for_optional_element(Locator::XPath(path), ESTIMATION_TIMEOUT)
+                .await?;
+            if let Some(el) = possibly_estimated_gas {
                 log::info!("[{}] unsetting 'use estimated gas' checkbox", log_id);
                 el.click().await?;
             } else {
@@ -1027,40 +883,28 @@ impl ContractsUi for crate::uis::Ui {
             }
 
             log::info!("[{}] entering max gas {:?}", log_id, max_gas);
-            max_gas_input.clear().await?;
-            max_gas_input.send_keys(&max_gas).await?;
+            self.fill(Locator::XPath(max_gas_input_path), max_gas)
+                .await?;
         }
 
         // possibly set values
-        for (key, value) in call.values.clone() {
-            // if the value is `Yes` or `No` we assume it's a listbox with a boolean
-            let mut value = transform_value(&value);
-            if value == "Yes" || value == "No" {
+        let (fields, items) = args::form_inputs(&call.values, &call.items);
+        for (key, arg) in fields {
+            let mut value = arg.render(&self.token)?;
+            if arg.is_choice() {
                 log::info!("[{}] opening dropdown list '{}'", log_id, key);
                 let path =
                     format!("//label/*[contains(text(),'{}')]/ancestor::div[1]", key);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                self.click(Locator::XPath(&path)).await?;
 
                 log::info!("[{}] choosing option '{}''", log_id, value);
                 let path = format!("//label/*[contains(text(),'{}')]/ancestor::div[1]//*/div[@role = 'option']/span[text() = '{}']", key, value);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
-            } else if is_account(&value) {
+                self.click(Locator::XPath(&path)).await?;
+            } else if arg.is_dev_account() {
                 log::info!("[{}] opening dropdown list '{}'", log_id, key);
                 let path =
                     format!("//label/*[contains(text(),'{}')]/ancestor::div[1]", key);
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                self.click(Locator::XPath(&path)).await?;
 
                 log::info!(
                     "[{}] inserting '{}' into input field '{}'",
@@ -1070,176 +914,97 @@ impl ContractsUi for crate::uis::Ui {
                 );
                 let path =
                     format!("//*[contains(normalize-space(text()),'{}')]/ancestor::div[1]//*/input", key);
-                let input = self.client.find(Locator::XPath(&path)).await?;
                 // we need to clear a possible default input from the field
-                input.clear().await?;
-                input.send_keys(&value).await?;
+                self.fill(Locator::XPath(&path), &value).await?;
 
                 log::info!("[{}] choosing account option '{}''", log_id, value);
-                let path = format!("//div[contains(@class, 'active')]//*/div[contains(@class, 'selected item')]");
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                let path = "//div[contains(@class, 'active')]//*/div[contains(@class, 'selected item')]".to_string();
+                self.click(Locator::XPath(&path)).await?;
             } else {
                 log::info!("[{}] entering {:?} into {:?}", log_id, &value, &key);
                 let path = format!(
                     "//div[contains(@class, 'ui--Params')]//*[contains(normalize-space(text()),'{}')]/ancestor::div[1]/div//input[@type = 'text']",
                     key
                 );
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .clear()
-                    .await?;
                 value.push('\n');
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .send_keys(&value)
-                    .await?;
+                self.fill(Locator::XPath(&path), &value).await?;
             }
         }
 
-        std::thread::sleep(std::time::Duration::from_secs(2));
+        // the ui processed the values once the form is valid
+        self.wait_for_element(Locator::XPath(
+            "//button[not(contains(@class, 'isDisabled')) and contains(text(),'Execute')]",
+        ))
+        .await?;
 
         // reset items which are already set as default by the ui
         let remove_item = "//button[not(contains(@class, 'isDisabled')) and contains(text(), 'Remove item')]";
-        while self.client.find(Locator::XPath(&remove_item)).await.is_ok() {
-            self.client
-                .find(Locator::XPath(&remove_item))
-                .await?
-                .click()
-                .await?;
+        while self.client.find(Locator::XPath(remove_item)).await.is_ok() {
+            self.click(Locator::XPath(remove_item)).await?;
         }
 
         // possibly add items
-        for (key, value) in call.items.iter() {
+        for (key, arg) in items.iter() {
+            let value = arg.render_item()?;
             log::info!("[{}] adding item '{}' for '{}'", log_id, value, key);
             let add_item = format!("//div[contains(normalize-space(text()),'{}')]/ancestor::div[1]/ancestor::div[1]/*/button[contains(text(), 'Add item')]", key);
-            self.client
-                .find(Locator::XPath(&add_item))
-                .await?
-                .click()
-                .await?;
+            self.click(Locator::XPath(&add_item)).await?;
             log::info!("[{}] added item '{}' for '{}'", log_id, value, key);
 
             let last_item = format!("//div[contains(normalize-space(text()),'{}')]/ancestor::div[1]/ancestor::div[1]/*/div[@class = 'ui--Params-Content']/div[last()]//input", key);
-            let input = self.client.find(Locator::XPath(&last_item)).await?;
             // we need to clear a possible default input from the field
-            input.clear().await?;
-            input.send_keys(&value).await?;
+            self.fill(Locator::XPath(&last_item), &value).await?;
 
             log::info!("[{}] send item '{}' for '{}'", log_id, value, key);
 
-            if is_account(&value) {
+            if arg.is_dev_account() {
                 log::info!("[{}] choosing account option '{}''", log_id, value);
-                let path = format!("//div[contains(@class, 'active')]//*/div[contains(@class, 'selected item')]");
-                self.client
-                    .find(Locator::XPath(&path))
-                    .await?
-                    .click()
-                    .await?;
+                let path = "//div[contains(@class, 'active')]//*/div[contains(@class, 'selected item')]".to_string();
+                self.click(Locator::XPath(&path)).await?;
             }
             log::info!("[{}] after '{}' for '{}'", log_id, value, key);
         }
 
-        std::thread::sleep(std::time::Duration::from_secs(3));
-
         log::info!("[{}] get estimated gas", log_id);
-        let max_gas_input_path = "//*[contains(text(),'max RefTime allowed')]/ancestor::div[1]/div//input[@type = 'text']";
-        let max_gas_input = self
-            .client
-            .wait()
-            .for_element(Locator::XPath(max_gas_input_path))
-            .await?
-            .attr("value")
-            .await?;
+        let max_gas_input = Locator::XPath("//*[contains(text(),'max RefTime allowed')]/ancestor::div[1]/div//input[@type = 'text']");
+        let client = &self.client;
+        let estimated_gas = wait::wait_until(
+            "gas estimation",
+            wait::timeout(),
+            wait::poll_interval(),
+            || {
+                async move {
+                    let value = match client.find(max_gas_input).await {
+                        Ok(input) => input.attr("value").await?,
+                        Err(_) => None,
+                    };
+                    Ok(value.filter(|value| !value.is_empty()))
+                }
+            },
+        )
+        .await?;
         log::info!(
             "[{}] estimated gas for transaction is {}",
             log_id,
-            max_gas_input.expect("estimated gas must exist")
+            estimated_gas
         );
 
         log::info!("[{}] click execute", log_id);
-        self.client
-            .find(Locator::XPath("//button[contains(text(),'Execute')]"))
-            .await?
-            .click()
+        self.click(Locator::XPath("//button[contains(text(),'Execute')]"))
             .await?;
 
         log::info!("[{}] click sign and submit", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                "//button[contains(text(),'Sign and Submit')]",
-            ))
-            .await?
-            .click()
-            .await?;
+        self.click(Locator::XPath(
+            "//button[contains(text(),'Sign and Submit')]",
+        ))
+        .await?;
 
         log::info!(
             "[{}] transaction: waiting for either success or failure notification",
             log_id
         );
-        let mut res;
-        for waited in 0..21 {
-            std::thread::sleep(std::time::Duration::from_secs(3));
-            res = self.client.find(
-                Locator::XPath("//div[contains(@class, 'ui--Status')]//*/div[contains(text(),'system.ExtrinsicSuccess') or contains(text(), 'system.ExtrinsicFailed')]")
-            ).await;
-            if res.is_ok() {
-                log::info!(
-                    "[{}] transaction: success for {:?} after waiting {}",
-                    log_id,
-                    call.method,
-                    waited,
-                );
-                break
-            } else {
-                log::info!(
-                    "[{}] transaction: waited for {:?} for {}",
-                    log_id,
-                    call.method,
-                    waited,
-                );
-
-                let statuses = self
-                    .client
-                    .find_all(Locator::XPath(
-                        "//div[contains(@class, 'ui--Status')]//div[@class = 'desc']//div[@class = 'header']//div",
-                    ))
-                    .await?;
-                log::info!(
-                    "[{}] transaction retry: found {:?} status messages for {:?}",
-                    log_id,
-                    statuses.len(),
-                    call.method
-                );
-                for el in statuses {
-                    let txt = el.html(true).await?.to_string().replace("\"", "");
-                    log::info!("[{}] transaction retry, text: {:?}", log_id, txt,);
-                }
-
-                if waited == 20 {
-                    log::info!(
-                        "[{}] timed out on waiting for {:?} transaction after {}! next recursion.",
-                        log_id,
-                        call.method,
-                        waited
-                    );
-                    return self.execute_transaction(call.clone()).await
-                } else {
-                    log::info!(
-                        "[{}] timed out on waiting for {:?} transaction after {}! sleeping.",
-                        log_id,
-                        call.method,
-                        waited
-                    );
-                }
-            }
-        }
+        self.wait_for_status(TRANSACTION_OUTCOME, call.outcome_timeout())
+            .await?;
 
         log::info!("[{}] transaction: extracting status messages", log_id);
         let statuses = self
@@ -1257,225 +1022,60 @@ impl ContractsUi for crate::uis::Ui {
         for el in statuses {
             let mut contents = el
                 .find_all(Locator::XPath(
-                    "//div[contains(@class, 'header') or contains(@class, 'status')]",
+                    ".//div[contains(@class, 'header') or contains(@class, 'status')]",
                 ))
                 .await?;
             for content in contents.iter_mut() {
                 let status = content.html(true).await?;
                 log::info!("[{}] found status message {:?}", log_id, status);
-                statuses_processed.push(Event {
-                    header: String::from(""),
-                    status,
-                });
+                statuses_processed.push((status, String::new()));
             }
         }
-        let events = Events::new(statuses_processed);
-
-        if events.contains("Priority is too low") {
-            log::info!(
-                "[{}] found priority too low during transaction execution of {:?}! trying again!",
-                log_id,
-                call.method
-            );
-            return self.execute_transaction(call.clone()).await
-        } else if events.contains("usurped") {
-            log::info!(
-                "[{}] found usurped for transaction of {:?}! trying again!",
-                log_id,
-                call.method
-            );
-            return self.execute_transaction(call.clone()).await
-        } else if events.contains("outdated") {
-            log::info!(
-                "[{}] found outdated for transaction of {:?}! trying again!",
-                log_id,
-                call.method
-            );
-            return self.execute_transaction(call.clone()).await
-        } else {
-            log::info!(
-                "[{}] did not find priority too low in {:?} status messages",
-                log_id,
-                events.events.len()
-            );
-        }
+        let events = Events::from_messages(statuses_processed);
+        log::info!("[{}] found the events {}", log_id, events);
 
-        let success = events.contains("system.ExtrinsicSuccess");
-        let failure = events.contains("system.ExtrinsicFailed");
-        match (success, failure) {
-            (true, false) => Ok(events),
-            (false, true) => Err(TransactionError::ExtrinsicFailed(events)),
-            (false, false) => panic!("ERROR: Neither 'ExtrinsicSuccess' nor 'ExtrinsicFailed' was found in status messages!"),
-            (true, true) => panic!("ERROR: Both 'ExtrinsicSuccess' nor 'ExtrinsicFailed' was found in status messages!"),
-        }
+        events.into_outcome()
     }
 
-    /// Updates the metadata which the UI uses for interacting with the contract
-    /// at `contract_addr` to `new_abi`.
-    async fn update_metadata(
-        &mut self,
-        contract_addr: &String,
-        new_abi: &PathBuf,
-    ) -> Result<String> {
-        let log_id = format!("{}", test_name(),);
-        log::info!(
-            "[{}] opening url for updating metadata of {}: {:?}",
-            log_id,
-            contract_addr,
-            url()
-        );
-        self.client.goto(&url()).await?;
-
-        // Firefox might not load if the website at that address is already open due to e.g.
-        // a prior `execute_transaction` call in the test. Hence we refresh just to be sure
-        // that it's a clean, freshly loaded page in front of us.
-        self.client.refresh().await?;
-
-        log::info!("[{}] waiting for page to become visible", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//div[@class = 'menuSection']"))
-            .await?;
-
-        std::thread::sleep(std::time::Duration::from_secs(3));
-
-        log::info!("[{}] injecting jquery", log_id);
-        // The `inject` script will retry to load jQuery every 10 seconds.
-        // This is because the CI sometimes has spurious network errors.
-        let inject = String::from(
-            "(function (){\
-                    var d = document;\
-                    if (!d.getElementById('jquery')) {\
-                        function load_jquery() {\
-                            var d = document;\
-                            var s = d.createElement('script');\
-                            s.src = 'https://code.jquery.com/jquery-3.6.0.min.js';\
-                            s.id = 'jquery';\
-                            d.body.appendChild(s);\
-                        }\
-                        var jTimer = setInterval(function() {\
-                            load_jquery();\
-                        }, 10000);\
-                        load_jquery();\
-                        (function() {\
-                            var nTimer = setInterval(function() {\
-                                if (window.jQuery) {\
-                                    $('body').append('<div id=\"jquery-ready\"></div');\
-                                    clearInterval(nTimer);\
-                                    clearInterval(jTimer);\
-                                }\
-                            }, 100);\
-                        })();\
-                    }\
-                })();",
-        );
-        self.client.execute(&*inject, Vec::new()).await?;
-
-        log::info!("[{}] waiting for jquery", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::Css("#jquery-ready"))
-            .await?;
-
-        log::info!("[{}] click 'Add an existing contract'", log_id);
-        self.client
-            .wait()
-            .for_element(Locator::XPath(
-                "//button[contains(text(),'Add an existing contract')]",
-            ))
-            .await?
-            .click()
-            .await?;
-
-        log::info!("[{}] entering contract address {:?}", log_id, contract_addr);
-        let path = "//input[@data-testid = 'contract address']";
-
-        // The following form submission failed when the input was done all-at-once.
-        // To overcome this UI quirk, we type in the address here in two batches.
-        let mut first_typed = contract_addr.clone();
-        let last_typed = first_typed
-            .pop()
-            .expect("the contract address should not be empty!")
-            .to_string();
-
-        self.client
-            .find(Locator::XPath(path))
-            .await?
-            .clear()
-            .await?;
-        self.client
-            .find(Locator::XPath(path))
-            .await?
-            .send_keys(&first_typed)
-            .await?;
-        self.client
-            .find(Locator::XPath(path))
-            .await?
-            .send_keys(&last_typed)
-            .await?;
-
-        log::info!("[{}] uploading {:?}", log_id, new_abi);
-        let upload = self
-            .client
-            .find(Locator::XPath("//input[@type = 'file']"))
-            .await?;
-        upload.send_keys(&new_abi.display().to_string()).await?;
-        self.client
-            .execute("$(\"input[type = 'file']\").trigger('change')", Vec::new())
-            .await?;
-
-        log::info!(
-            "[{}] wait for upload of {:?} to be finished",
-            log_id,
-            new_abi
-        );
-        self.client
-            .wait()
-            .for_element(Locator::XPath("//div[contains(text(), 'Constructors (')]"))
-            .await?;
-
-        std::thread::sleep(std::time::Duration::from_secs(2));
-
-        log::info!("[{}] click save on {:?}", log_id, new_abi);
-        self.client
-            .find(Locator::XPath("//button[contains(text(), 'Save')]"))
-            .await?
-            .click()
-            .await?;
-
-        Ok(String::from(""))
+    /// Waits until the UI lists at least one contract, and returns the number of
+    /// listed contracts.
+    async fn wait_for_contracts(&self) -> TransactionResult<usize> {
+        let client = &self.client;
+        wait::wait_until(
+            "contracts listed",
+            wait::timeout(),
+            wait::poll_interval(),
+            || {
+                async move {
+                    let contracts = client
+                        .find_all(Locator::XPath(
+                            "//span[contains(@class, 'ui--AccountName')]",
+                        ))
+                        .await?
+                        .len();
+                    Ok((contracts > 0).then_some(contracts))
+                }
+            },
+        )
+        .await
     }
-}
-
-impl crate::uis::Ui {
-    /// Clicks on the `locator`, if an error occurs we retry ten times with a sleep
-    /// of two seconds in between.
-    ///
-    /// This was introduced to retry on these spurious UI errors:
-    ///
-    /// ```json
-    /// Standard(WebDriverError { error: ElementClickIntercepted,
-    /// message: "Element <button class=\"ui--Button hasLabel Button-sc-l9wqp0-0 fUpXVx\">
-    /// is not clickable at point (750,216) because another element
-    /// <div class=\"ui--InputFile error InputFile-sc-vhlvx4-0 jqSBqi\"> obscures it",
-    /// stack: "", delete_session: false })
-    /// ```
-    async fn click(
-        &mut self,
-        locator: Locator<'_>,
-    ) -> std::result::Result<(), error::CmdError> {
-        let mut possibly_err =
-            self.client.wait().for_element(locator).await?.click().await;
-
-        const MAX_ATTEMPTS: usize = 10;
-        let mut attempt = 0;
-        while possibly_err.is_err() && attempt < MAX_ATTEMPTS {
-            std::thread::sleep(std::time::Duration::from_secs(2));
-            possibly_err = self.client.wait().for_element(locator).await?.click().await;
-            attempt = attempt + 1;
-        }
 
-        possibly_err
+    /// Decodes the `outcome` of `message`, as displayed by the ui.
+    fn decode_outcome(
+        &self,
+        contract_address: &str,
+        message: &str,
+        outcome: &str,
+    ) -> Result<ArgValue> {
+        let metadata = self.contracts.get(contract_address)?;
+        let value = decode::parse_outcome(
+            metadata,
+            metadata.message(message)?,
+            outcome,
+            &self.token,
+        )?;
+        log::info!("[{}] outcome value is {:?}", test_name(), value);
+        Ok(value)
     }
 }
 
@@ -1504,32 +1104,26 @@ fn url() -> String {
     format!("{}contracts", base_url())
 }
 
-/// Returns the address for a given `name`.
-fn name_to_address(name: &str) -> Option<String> {
-    match name {
-        "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY" => Some("ALICE".to_string()),
-        "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty" => Some("BOB".to_string()),
-        "5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y" => Some("CHARLIE".to_string()),
-        "5DAAnrj7VHTznn2AWBemMuyBwZWs6FNFjdyVXUeYum3PTXFy" => Some("DAVE".to_string()),
-        "5HGjWAeFDfFCWPsjFQdVV2Msvz2XtMktvgocEZcCj68kUMaw" => Some("EVE".to_string()),
-        _ => None,
-    }
+/// Returns `method` as the `polkadot-js` UI displays it, i.e. in camel-case.
+fn ui_method_name(method: &str) -> String {
+    method.to_case(Case::Camel).replace(": I32", ": i32")
 }
 
-fn transform_value(value: &str) -> String {
-    match value {
-        "true" => String::from("Yes"),
-        "false" => String::from("No"),
-        _ => value.to_string(),
-    }
+/// Returns the argument `key` as the `polkadot-js` UI displays it, i.e. in camel-case
+/// and with a space in front of the type.
+fn ui_arg_name(key: &str) -> String {
+    key.to_case(Case::Camel)
+        .replace(':', ": ")
+        .replace(": I32", ": i32")
 }
 
-// Returns `true` if the `value` is an account.
-fn is_account(value: &str) -> bool {
-    value == "ALICE"
-        || value == "BOB"
-        || value == "CHARLIE"
-        || value == "DAVE"
-        || value == "EVE"
-        || value == "FERDIE"
+/// Converts the method and argument names of `call` into the spelling used by the UI.
+fn normalize_call(mut call: Call) -> Call {
+    call.method = ui_method_name(&call.method);
+    call.values = call
+        .values
+        .into_iter()
+        .map(|(key, value)| (ui_arg_name(&key), value))
+        .collect();
+    call
 }
@@ -0,0 +1,270 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Retrying WebDriver actions which fail spuriously.
+//!
+//! The UIs re-render while they are used, so an element might be covered by
+//! another one or be replaced right when it is clicked. Such errors are retried
+//! with an exponential backoff, all other errors are returned right away. All
+//! backends retry extrinsics which the transaction pool rejects spuriously with
+//! the same policy.
+
+use super::{
+    trace,
+    TransactionError,
+};
+use crate::utils::test_name;
+use fantoccini::error::CmdError;
+use std::{
+    future::Future,
+    time::Duration,
+};
+
+/// The longest time to back off between two attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// The WebDriver error codes of errors which are worth retrying.
+const RETRYABLE_ERRORS: [&str; 4] = [
+    "element click intercepted",
+    "element not interactable",
+    "stale element reference",
+    "move target out of bounds",
+];
+
+/// How often and how fast a failed action is retried.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one.
+    attempts: u32,
+    /// The time to back off after the first failed attempt. It doubles after
+    /// every further failed attempt, up to [`MAX_BACKOFF`].
+    backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Returns the policy configured via `WATERFALL_RETRY_ATTEMPTS` (defaults to
+    /// 10 attempts) and `WATERFALL_RETRY_BACKOFF` in milliseconds (defaults to
+    /// 100 milliseconds).
+    pub fn from_env() -> Self {
+        let attempts = std::env::var("WATERFALL_RETRY_ATTEMPTS")
+            .map(|attempts| {
+                attempts
+                    .parse()
+                    .expect("unable to parse `WATERFALL_RETRY_ATTEMPTS`")
+            })
+            .unwrap_or(10);
+        let backoff = std::env::var("WATERFALL_RETRY_BACKOFF")
+            .map(|millis| {
+                Duration::from_millis(millis.parse().expect(
+                    "unable to parse `WATERFALL_RETRY_BACKOFF` into milliseconds",
+                ))
+            })
+            .unwrap_or(Duration::from_millis(100));
+        Self { attempts, backoff }
+    }
+
+    /// Returns the time to back off after the failed `attempt`, starting at `1`.
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(MAX_BACKOFF)
+    }
+
+    /// Runs `action` until it succeeds, fails with an error which is not
+    /// retryable, or the attempts are used up.
+    ///
+    /// `what` describes the action, e.g. `click on XPath("//button")`, each
+    /// retry is logged with it.
+    pub async fn run<T, F, Fut>(&self, what: &str, action: F) -> Result<T, CmdError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, CmdError>>,
+    {
+        self.run_if(what, is_retryable, action).await
+    }
+
+    /// Runs `action` until it succeeds, fails with an error for which `retryable`
+    /// returns `false`, or the attempts are used up. The last error is returned
+    /// then.
+    pub async fn run_if<T, E, R, F, Fut>(
+        &self,
+        what: &str,
+        retryable: R,
+        mut action: F,
+    ) -> Result<T, E>
+    where
+        E: std::fmt::Display,
+        R: Fn(&E) -> bool,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut attempt = 1;
+        loop {
+            // only the description of the error is kept while backing off, the
+            // error itself might not be `Send`
+            let err = match action().await {
+                Err(err) if retryable(&err) && attempt < self.attempts => err.to_string(),
+                result => return result,
+            };
+            let backoff = self.backoff(attempt);
+            log::info!(
+                "[{}] retrying {} in {:?} (attempt {} of {}): {}",
+                test_name(),
+                what,
+                backoff,
+                attempt,
+                self.attempts,
+                err
+            );
+            trace::retry();
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Returns `true` if `err` is a spurious error of the UI, which might not occur
+/// when the action is tried again.
+pub fn is_retryable(err: &CmdError) -> bool {
+    match err {
+        CmdError::NoSuchElement(_) => true,
+        CmdError::Standard(err) => RETRYABLE_ERRORS.contains(&err.error()),
+        _ => false,
+    }
+}
+
+/// Returns `true` if the transaction was usurped or rejected by the transaction
+/// pool for a reason which is resolved by submitting it again.
+pub fn is_retryable_transaction(err: &TransactionError) -> bool {
+    match err {
+        TransactionError::Usurped(_) => true,
+        TransactionError::Invalid(reason) => {
+            reason.contains("Priority is too low") || reason.contains("outdated")
+        }
+        _ => false,
+    }
+}
+
+/// Like [`is_retryable_transaction`], but a timeout is retried as well: the
+/// browser UIs sometimes do not display the outcome of a transaction, it is then
+/// submitted again from the start.
+pub fn is_retryable_in_browser(err: &TransactionError) -> bool {
+    is_retryable_transaction(err) || matches!(err, TransactionError::Timeout(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        // given
+        let policy = RetryPolicy {
+            attempts: 10,
+            backoff: Duration::from_millis(100),
+        };
+
+        // then
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff(7), MAX_BACKOFF);
+        assert_eq!(policy.backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn errors_which_are_not_retryable_are_returned_right_away() {
+        // given
+        let policy = RetryPolicy {
+            attempts: 3,
+            backoff: Duration::ZERO,
+        };
+        let mut attempts = 0;
+
+        // when
+        let result: Result<(), _> = policy
+            .run("test", || {
+                attempts += 1;
+                async { Err(CmdError::NotJson(String::from("not json"))) }
+            })
+            .await;
+
+        // then
+        assert!(matches!(result, Err(CmdError::NotJson(_))));
+        assert_eq!(attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn the_last_error_is_returned_once_the_attempts_are_used_up() {
+        // given
+        let policy = RetryPolicy {
+            attempts: 3,
+            backoff: Duration::ZERO,
+        };
+        let mut attempts = 0;
+
+        // when
+        let result: Result<(), _> = policy
+            .run_if("test", is_retryable_in_browser, || {
+                attempts += 1;
+                let err = TransactionError::Timeout(format!("attempt {}", attempts));
+                async move { Err(err) }
+            })
+            .await;
+
+        // then
+        assert!(
+            matches!(result, Err(TransactionError::Timeout(reason)) if reason == "attempt 3")
+        );
+        assert_eq!(attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn a_retried_action_which_succeeds_returns_its_value() {
+        // given
+        let policy = RetryPolicy {
+            attempts: 3,
+            backoff: Duration::ZERO,
+        };
+        let mut attempts = 0;
+
+        // when
+        let result = policy
+            .run_if("test", is_retryable_transaction, || {
+                attempts += 1;
+                let result = if attempts < 2 {
+                    Err(TransactionError::Invalid(String::from(
+                        "Priority is too low: (1 vs 1)",
+                    )))
+                } else {
+                    Ok(attempts)
+                };
+                async move { result }
+            })
+            .await;
+
+        // then
+        assert!(matches!(result, Ok(2)));
+    }
+
+    #[test]
+    fn only_the_browser_uis_retry_timeouts() {
+        // given
+        let timeout = TransactionError::Timeout(String::from("no outcome"));
+
+        // then
+        assert!(is_retryable_in_browser(&timeout));
+        assert!(!is_retryable_transaction(&timeout));
+    }
+}
@@ -0,0 +1,996 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A browserless backend which talks to the `substrate-contracts-node` directly
+//! via its WebSocket JSON-RPC interface.
+//!
+//! Contracts are uploaded and called with signed extrinsics from the keyring,
+//! RPC calls are executed as dry-runs of the `ContractsApi` runtime API.
+//! Their outputs are decoded with the return type from the contract metadata.
+
+use crate::{
+    uis::{
+        chain::{
+            self,
+            ExtrinsicId,
+        },
+        decode,
+        keyring::{
+            keypair,
+            resolve_account,
+            Keypair,
+        },
+        metadata::{
+            is_account_id,
+            is_endowment,
+            is_u8,
+            normalize,
+            normalize_arg,
+            resolve,
+            ContractBundle,
+            Contracts,
+            MessageSpec,
+        },
+        retry::{
+            self,
+            RetryPolicy,
+        },
+        ArgValue,
+        Call,
+        ContractsUi,
+        Event,
+        Events,
+        Result,
+        TransactionError,
+        TransactionResult,
+        Upload,
+    },
+    utils::test_name,
+};
+use async_trait::async_trait;
+use codec::{
+    Decode,
+    Encode,
+};
+use convert_case::{
+    Case,
+    Casing,
+};
+use rand::Rng;
+use scale_info::{
+    form::PortableForm,
+    PortableRegistry,
+    TypeDef,
+    TypeDefPrimitive,
+};
+use std::{
+    path::Path,
+    time::Duration,
+};
+use subxt::{
+    error::DispatchError,
+    ext::scale_value::{
+        self,
+        Composite,
+        Value,
+    },
+    utils::AccountId32,
+    Metadata,
+    OnlineClient,
+    PolkadotConfig,
+};
+
+/// The gas limit which is used if a dry-run did not yield any gas estimate.
+const FALLBACK_GAS_LIMIT: Weight = Weight {
+    ref_time: 500_000_000_000,
+    proof_size: 1024 * 1024,
+};
+
+/// Bit in the `ReturnFlags` of a contract execution which signals a revert.
+const REVERT_FLAG: u32 = 0x0000_0001;
+
+/// Holds everything necessary to interact with the node directly.
+pub struct Rpc {
+    /// The connection to the `substrate-contracts-node`.
+    client: OnlineClient<PolkadotConfig>,
+    /// The metadata of all contracts which were uploaded via this instance,
+    /// indexed by the contract address.
+    contracts: Contracts,
+}
+
+impl Rpc {
+    /// Creates a new `Rpc` instance.
+    ///
+    /// As part of this set-up a connection to the node at [`crate::utils::node_port`] is opened.
+    pub async fn new() -> Result<Self> {
+        let client = chain::connect().await?;
+        Ok(Self {
+            client,
+            contracts: Contracts::default(),
+        })
+    }
+
+    /// Signs `tx` with `signer`, submits it and returns the events of the
+    /// extrinsic once it is included in a block.
+    ///
+    /// Fails with [`TransactionError::Timeout`] if the extrinsic is not included
+    /// within `timeout`.
+    async fn submit(
+        &self,
+        tx: subxt::tx::DynamicPayload,
+        signer: &Keypair,
+        timeout: Duration,
+    ) -> TransactionResult<Events> {
+        let progress = self
+            .client
+            .tx()
+            .sign_and_submit_then_watch_default(&tx, signer)
+            .await
+            .map_err(pool_error)?;
+        let in_block = tokio::time::timeout(timeout, progress.wait_for_in_block())
+            .await
+            .map_err(|_| {
+                TransactionError::Timeout(format!(
+                    "extrinsic not included in a block within {:?}",
+                    timeout
+                ))
+            })?
+            .map_err(pool_error)?;
+        let extrinsic_events = in_block
+            .fetch_events()
+            .await
+            .map_err(|err| TransactionError::Other(Box::new(err)))?;
+
+        let mut events = Vec::new();
+        for event in extrinsic_events.iter() {
+            let event = event.map_err(|err| TransactionError::Other(Box::new(err)))?;
+            let pallet = event.pallet_name().to_case(Case::Camel);
+            let mut decoded = Event::new(&pallet, event.variant_name());
+
+            let instantiated = (event.pallet_name() == "Contracts"
+                && event.variant_name() == "Instantiated")
+                .then(|| <(AccountId32, AccountId32)>::decode(&mut event.field_bytes()))
+                .and_then(|decoded| decoded.ok());
+            if let Some((deployer, contract)) = instantiated {
+                // The generic rendering would show the raw bytes of the accounts.
+                decoded = decoded
+                    .push_field("deployer", &deployer.to_string())
+                    .push_field("contract", &contract.to_string());
+            } else {
+                match event.field_values() {
+                    Ok(Composite::Named(fields)) => {
+                        for (name, value) in fields {
+                            decoded = decoded.push_field(&name, &value.to_string());
+                        }
+                    }
+                    Ok(Composite::Unnamed(fields)) => {
+                        for (index, value) in fields.into_iter().enumerate() {
+                            decoded = decoded
+                                .push_field(&index.to_string(), &value.to_string());
+                        }
+                    }
+                    Err(_) => {
+                        decoded = decoded
+                            .push_field("bytes", &hex::encode(event.field_bytes()));
+                    }
+                }
+            }
+            log::info!(
+                "[{}] found event {} with {:?}",
+                test_name(),
+                decoded,
+                decoded.fields()
+            );
+
+            // For a failed extrinsic the UIs show the dispatch error as a separate
+            // status message, e.g. `contracts.ContractNotFound`.
+            if event.pallet_name() == "System"
+                && event.variant_name() == "ExtrinsicFailed"
+            {
+                let error = chain::decode_dispatch_error(
+                    &self.client.metadata(),
+                    event.field_bytes(),
+                )
+                .and_then(|error| chain::module_error(&error));
+                if let Ok(Some(error)) = error {
+                    events.push(
+                        Event::new(error.pallet(), error.error())
+                            .push_field("docs", error.docs()),
+                    );
+                }
+            }
+            events.push(decoded);
+        }
+        let block = self
+            .client
+            .blocks()
+            .at(in_block.block_hash())
+            .await
+            .map_err(|err| TransactionError::Other(Box::new(err)))?;
+        let mut events = Events::new(events);
+        events.set_extrinsic(ExtrinsicId {
+            block: block.number(),
+            index: extrinsic_events.extrinsic_index(),
+        });
+        Ok(events)
+    }
+}
+
+#[async_trait]
+impl ContractsUi for Rpc {
+    /// Uploads the contract behind `contract_path`.
+    async fn execute_upload(
+        &mut self,
+        upload_input: Upload,
+    ) -> TransactionResult<String> {
+        let log_id = test_name();
+        log::info!(
+            "[{}] uploading {:?} via rpc",
+            log_id,
+            upload_input.contract_path
+        );
+        let Instantiation {
+            metadata,
+            constructor,
+            value,
+            code,
+            data,
+            signer,
+            salt,
+        } = prepare_upload(&upload_input)?;
+        log::info!(
+            "[{}] using constructor {:?} with an endowment of {} plancks",
+            log_id,
+            constructor,
+            value
+        );
+
+        let dry_run = dry_run_instantiate(
+            &self.client,
+            &signer.account_id(),
+            value,
+            &code,
+            &data,
+            &salt,
+        )
+        .await?;
+        // As for transactions, a failed dry-run does not stop the upload, so that
+        // the failure shows up in the events.
+        match &dry_run.result {
+            DispatchOutcome::Ok(instantiated) if instantiated.result.is_revert() => {
+                log::info!("[{}] dry-run of the instantiation reverted", log_id)
+            }
+            DispatchOutcome::Ok(instantiated) => {
+                log::info!(
+                    "[{}] dry-run instantiates the contract at {}",
+                    log_id,
+                    instantiated.account_id
+                )
+            }
+            DispatchOutcome::Err(err) => {
+                log::info!(
+                    "[{}] dry-run of the instantiation failed: {}",
+                    log_id,
+                    describe_dispatch_error(err)
+                )
+            }
+        }
+        let gas_limit = dry_run.gas_limit();
+        log::info!(
+            "[{}] estimated gas for upload is {}",
+            log_id,
+            gas_limit.ref_time
+        );
+
+        let tx = subxt::dynamic::tx(
+            "Contracts",
+            "instantiate_with_code",
+            vec![
+                Value::u128(value),
+                gas_limit.into_value(),
+                Value::unnamed_variant("None", []),
+                Value::from_bytes(&code),
+                Value::from_bytes(&data),
+                Value::from_bytes(salt),
+            ],
+        );
+        let timeout = upload_input.outcome_timeout();
+        let events = RetryPolicy::from_env()
+            .run_if("upload", retry::is_retryable_transaction, || {
+                self.submit(tx.clone(), &signer, timeout)
+            })
+            .await?
+            .into_outcome()?;
+
+        let addr = events
+            .find("contracts.Instantiated")
+            .and_then(|event| event.field("contract"))
+            .map(ToString::to_string)
+            .ok_or_else(|| {
+                TransactionError::Other("no `contracts.Instantiated` event found".into())
+            })?;
+        log::info!("[{}] contract address {:?}", log_id, addr);
+        self.contracts.insert(&addr, metadata);
+        Ok(addr)
+    }
+
+    /// Executes the RPC call `call`.
+    async fn execute_rpc(&mut self, call: Call) -> Result<ArgValue> {
+        let log_id = format!("{} {}", test_name(), call.method);
+        let metadata = self.contracts.get(&call.contract_address)?;
+        let message = metadata.message(&call.method)?;
+
+        log::info!("[{}] dry-running {:?}", log_id, message.label);
+        if message.mutates {
+            log::info!(
+                "[{}] {:?} mutates the storage, the changes of the dry-run are discarded",
+                log_id,
+                message.label
+            );
+        }
+        let dry_run = dry_run_message(&self.client, metadata, message, &call).await?;
+        let output = match dry_run.result {
+            DispatchOutcome::Ok(output) => output,
+            DispatchOutcome::Err(err) => {
+                return Err(format!(
+                    "dry-run of {:?} failed: {}",
+                    call.method,
+                    describe_dispatch_error(&err)
+                )
+                .into())
+            }
+        };
+        if output.is_revert() {
+            log::info!("[{}] the contract reverted", log_id);
+        }
+
+        let outcome = decode::decode_output(metadata, message, &output.data)?;
+        log::info!("[{}] outcome value is {:?}", log_id, outcome);
+        Ok(outcome)
+    }
+
+    /// Executes the transaction `call`.
+    async fn execute_transaction(&mut self, call: Call) -> TransactionResult<Events> {
+        let log_id = format!("{} {}", test_name(), call.method);
+        let (signer, dest, value, data) = {
+            let metadata = self.contracts.get(&call.contract_address)?;
+            let message = metadata.message(&call.method)?;
+            let data = input_data(metadata, message, &call.values, &call.items)?;
+            let signer = keypair(call.caller.as_deref())?;
+            let dest = resolve_account(&call.contract_address)?;
+            let value = match &call.payment {
+                Some(payment) => payment.as_plancks(),
+                None => 0,
+            };
+            (signer, dest, value, data)
+        };
+
+        // The dry-run is only used to estimate the gas. If it fails we still submit
+        // the transaction, so that the failure shows up in the events ‒ the same as
+        // in the UIs.
+        let origin = signer.account_id();
+        let gas_limit = match &call.max_gas_allowed {
+            Some(max_gas) => {
+                Weight {
+                    ref_time: max_gas
+                        .parse()
+                        .map_err(|err| TransactionError::Other(Box::new(err)))?,
+                    proof_size: FALLBACK_GAS_LIMIT.proof_size,
+                }
+            }
+            None => {
+                dry_run_call(&self.client, &origin, &dest, value, &data)
+                    .await?
+                    .gas_limit()
+            }
+        };
+        log::info!(
+            "[{}] estimated gas for transaction is {}",
+            log_id,
+            gas_limit.ref_time
+        );
+
+        let tx = subxt::dynamic::tx(
+            "Contracts",
+            "call",
+            vec![
+                Value::unnamed_variant("Id", [Value::from_bytes(dest.0)]),
+                Value::u128(value),
+                gas_limit.into_value(),
+                Value::unnamed_variant("None", []),
+                Value::from_bytes(&data),
+            ],
+        );
+        let timeout = call.outcome_timeout();
+        RetryPolicy::from_env()
+            .run_if(
+                &format!("transaction {:?}", call.method),
+                retry::is_retryable_transaction,
+                || self.submit(tx.clone(), &signer, timeout),
+            )
+            .await?
+            .into_outcome()
+    }
+
+    /// Replaces the metadata which is used for interacting with the contract
+    /// at `contract_addr` with `new_abi`.
+    async fn update_metadata(
+        &mut self,
+        contract_addr: &str,
+        new_abi: &Path,
+    ) -> Result<String> {
+        log::info!(
+            "[{}] updating metadata of {} to {:?}",
+            test_name(),
+            contract_addr,
+            new_abi
+        );
+        self.contracts.load(contract_addr, new_abi)?;
+        Ok(String::from(""))
+    }
+
+    /// There is nothing to tear down for this backend, the connection is closed
+    /// when the client is dropped.
+    async fn shutdown(&mut self) -> Result<()> {
+        log::debug!("closing rpc connection");
+        Ok(())
+    }
+}
+
+/// Encodes the input data for `spec`, i.e. the selector followed by the
+/// SCALE encoded arguments.
+fn input_data(
+    metadata: &ContractBundle,
+    spec: &MessageSpec,
+    values: &[(String, ArgValue)],
+    items: &[(String, ArgValue)],
+) -> Result<Vec<u8>> {
+    let mut data = hex_decode(&spec.selector)?;
+    for arg in &spec.args {
+        let label = normalize(&arg.label);
+        let value = values
+            .iter()
+            .find(|(key, _)| normalize_arg(key) == label)
+            .map(|(_, value)| value.clone());
+        let arg_items: Vec<ArgValue> = items
+            .iter()
+            .filter(|(key, _)| normalize_arg(key) == label)
+            .map(|(_, value)| value.clone())
+            .collect();
+
+        let value = match value {
+            Some(value) => value_from_arg(&metadata.registry, arg.ty.ty, &value)?,
+            None if !arg_items.is_empty() => {
+                value_from_arg(&metadata.registry, arg.ty.ty, &ArgValue::Vec(arg_items))?
+            }
+            // The UIs pre-fill fields with default values, we do the same.
+            None => default_value(&metadata.registry, arg.ty.ty)?,
+        };
+        log::info!(
+            "[{}] encoding {:?} for argument {:?}",
+            test_name(),
+            value,
+            arg.label
+        );
+        scale_value::scale::encode_as_type(
+            &value,
+            arg.ty.ty,
+            &metadata.registry,
+            &mut data,
+        )?;
+    }
+    Ok(data)
+}
+
+/// An upload, prepared for being dry-run or submitted.
+struct Instantiation {
+    metadata: ContractBundle,
+    /// The label of the constructor.
+    constructor: String,
+    /// The endowment in plancks, which is transferred to the contract.
+    value: u128,
+    code: Vec<u8>,
+    /// The input data for the constructor.
+    data: Vec<u8>,
+    signer: Keypair,
+    salt: [u8; 32],
+}
+
+/// Reads the contract bundle of `upload` and encodes the input data for its
+/// constructor.
+///
+/// A `value` which is not an argument of the constructor is the endowment, as in
+/// the browser UIs.
+fn prepare_upload(upload: &Upload) -> Result<Instantiation> {
+    let metadata = ContractBundle::load(&upload.contract_path)?;
+    let code = hex_decode(
+        metadata
+            .source
+            .wasm
+            .as_ref()
+            .ok_or("the contract bundle does not contain the Wasm code")?,
+    )?;
+    let constructor = metadata.constructor(upload.constructor.as_deref())?;
+    let (endowment, values): (Vec<_>, Vec<_>) = upload
+        .initial_values
+        .iter()
+        .cloned()
+        .partition(|(key, _)| is_endowment(constructor, key));
+    let value = match endowment.last() {
+        None => 0,
+        Some((_, ArgValue::Balance(balance))) => balance.as_plancks(),
+        Some((_, ArgValue::UInt(plancks))) => *plancks,
+        Some((key, other)) => {
+            return Err(format!(
+                "the endowment {:?} must be a balance, got {:?}",
+                key, other
+            )
+            .into())
+        }
+    };
+    let data = input_data(&metadata, constructor, &values, &upload.items)?;
+    Ok(Instantiation {
+        constructor: constructor.label.clone(),
+        value,
+        code,
+        data,
+        signer: keypair(upload.caller.as_deref())?,
+        // equivalent to the 'Unique Instantiation Salt' of the UIs
+        salt: rand::thread_rng().gen(),
+        metadata,
+    })
+}
+
+/// Dry-runs `upload` via the `ContractsApi` runtime API and returns the label of
+/// the constructor and the gas which the instantiation requires.
+pub(crate) async fn estimate_upload(
+    client: &OnlineClient<PolkadotConfig>,
+    upload: &Upload,
+) -> Result<(String, u64)> {
+    let instantiation = prepare_upload(upload)?;
+    let dry_run = dry_run_instantiate(
+        client,
+        &instantiation.signer.account_id(),
+        instantiation.value,
+        &instantiation.code,
+        &instantiation.data,
+        &instantiation.salt,
+    )
+    .await?;
+    Ok((instantiation.constructor, dry_run.gas_required()))
+}
+
+/// Dry-runs the `message` of `call` via the `ContractsApi` runtime API.
+pub(crate) async fn dry_run_message(
+    client: &OnlineClient<PolkadotConfig>,
+    metadata: &ContractBundle,
+    message: &MessageSpec,
+    call: &Call,
+) -> Result<ContractResult<ExecReturnValue>> {
+    let data = input_data(metadata, message, &call.values, &call.items)?;
+    let origin = keypair(call.caller.as_deref())?.account_id();
+    let dest = resolve_account(&call.contract_address)?;
+    let value = match &call.payment {
+        Some(payment) => payment.as_plancks(),
+        None => 0,
+    };
+    dry_run_call(client, &origin, &dest, value, &data).await
+}
+
+/// Dry-runs the call via the `ContractsApi` runtime API.
+async fn dry_run_call(
+    client: &OnlineClient<PolkadotConfig>,
+    origin: &AccountId32,
+    dest: &AccountId32,
+    value: u128,
+    data: &[u8],
+) -> Result<ContractResult<ExecReturnValue>> {
+    let args = (origin, dest, value, None::<Weight>, None::<u128>, data).encode();
+    let bytes = client
+        .rpc()
+        .state_call_raw("ContractsApi_call", Some(&args), None)
+        .await?;
+    ContractResult::decode(&client.metadata(), &bytes)
+}
+
+/// Dry-runs the instantiation via the `ContractsApi` runtime API.
+async fn dry_run_instantiate(
+    client: &OnlineClient<PolkadotConfig>,
+    origin: &AccountId32,
+    value: u128,
+    code: &[u8],
+    data: &[u8],
+    salt: &[u8],
+) -> Result<ContractResult<InstantiateReturnValue>> {
+    let args = (
+        origin,
+        value,
+        None::<Weight>,
+        None::<u128>,
+        Code::Upload(code.to_vec()),
+        data,
+        salt,
+    )
+        .encode();
+    let bytes = client
+        .rpc()
+        .state_call_raw("ContractsApi_instantiate", Some(&args), None)
+        .await?;
+    ContractResult::decode(&client.metadata(), &bytes)
+}
+
+/// The weight of an extrinsic, as defined in `sp_weights::Weight`.
+#[derive(Encode, Decode, Clone, Copy, Debug)]
+struct Weight {
+    #[codec(compact)]
+    ref_time: u64,
+    #[codec(compact)]
+    proof_size: u64,
+}
+
+impl Weight {
+    /// Returns the weight as a value which can be used for a dynamic extrinsic.
+    fn into_value(self) -> Value {
+        Value::named_composite([
+            ("ref_time", Value::u128(self.ref_time as u128)),
+            ("proof_size", Value::u128(self.proof_size as u128)),
+        ])
+    }
+}
+
+/// The code which is instantiated, as defined in `pallet_contracts_primitives::Code`.
+#[derive(Encode)]
+enum Code {
+    #[codec(index = 0)]
+    Upload(Vec<u8>),
+}
+
+/// The result of a dry-run, as defined in `pallet_contracts_primitives::ContractResult`.
+pub(crate) struct ContractResult<T> {
+    gas_required: Weight,
+    pub(crate) debug_message: Vec<u8>,
+    pub(crate) result: DispatchOutcome<T>,
+}
+
+impl<T: Decode> ContractResult<T> {
+    /// Decodes the result of a dry-run from `bytes`, the dispatch error of a failed
+    /// dry-run with the help of the chain `metadata`.
+    ///
+    /// The consumed gas and the storage deposit are skipped, as well as the
+    /// trailing `events`, which are only collected on request.
+    fn decode(metadata: &Metadata, bytes: &[u8]) -> Result<Self> {
+        let input = &mut &*bytes;
+        let _gas_consumed = Weight::decode(input)?;
+        let gas_required = Weight::decode(input)?;
+        // `StorageDeposit::Refund(u128)` or `StorageDeposit::Charge(u128)`
+        let _storage_deposit = <(u8, u128)>::decode(input)?;
+        let debug_message = Vec::<u8>::decode(input)?;
+        let result = match u8::decode(input)? {
+            0 => DispatchOutcome::Ok(T::decode(input)?),
+            1 => DispatchOutcome::Err(chain::decode_dispatch_error(metadata, input)?),
+            _ => return Err("invalid `Result` discriminant of a dry-run".into()),
+        };
+        Ok(Self {
+            gas_required,
+            debug_message,
+            result,
+        })
+    }
+
+    /// Returns the `ref_time` which the execution requires, i.e. the estimated gas.
+    pub(crate) fn gas_required(&self) -> u64 {
+        self.gas_required.ref_time
+    }
+
+    /// Returns the gas limit which should be used when submitting the extrinsic.
+    fn gas_limit(&self) -> Weight {
+        if self.gas_required.ref_time == 0 {
+            FALLBACK_GAS_LIMIT
+        } else {
+            self.gas_required
+        }
+    }
+}
+
+#[derive(Decode)]
+pub(crate) struct ExecReturnValue {
+    flags: u32,
+    pub(crate) data: Vec<u8>,
+}
+
+impl ExecReturnValue {
+    /// Returns `true` if the contract reverted, e.g. because a message returned
+    /// `Result::Err`. The state changes of a reverted execution are discarded.
+    pub(crate) fn is_revert(&self) -> bool {
+        self.flags & REVERT_FLAG != 0
+    }
+}
+
+#[derive(Decode)]
+struct InstantiateReturnValue {
+    result: ExecReturnValue,
+    account_id: AccountId32,
+}
+
+/// The `Result<T, DispatchError>` of a dry-run.
+pub(crate) enum DispatchOutcome<T> {
+    Ok(T),
+    Err(DispatchError),
+}
+
+/// Returns a human readable description of the dispatch error `err`.
+fn describe_dispatch_error(err: &DispatchError) -> String {
+    match chain::module_error(err) {
+        Ok(Some(error)) => error.to_string(),
+        _ => format!("{:?}", err),
+    }
+}
+
+/// Maps an error of submitting a transaction, or of watching its progress.
+///
+/// The transaction pool rejects a transaction with an RPC error (e.g.
+/// `1014: Priority is too low`), while a transaction which already was in the
+/// pool is reported as usurped, invalid or dropped.
+fn pool_error(err: subxt::Error) -> TransactionError {
+    use subxt::error::TransactionError as Status;
+    match err {
+        subxt::Error::Transaction(Status::Usurped) => {
+            TransactionError::Usurped(Events::default())
+        }
+        subxt::Error::Transaction(Status::Invalid | Status::Dropped) => {
+            TransactionError::Invalid(err.to_string())
+        }
+        subxt::Error::Rpc(_)
+            if ["Invalid Transaction", "Priority is too low"]
+                .iter()
+                .any(|reason| err.to_string().contains(reason)) =>
+        {
+            TransactionError::Invalid(err.to_string())
+        }
+        err => TransactionError::Other(Box::new(err)),
+    }
+}
+
+/// Decodes a `0x` prefixed hex string.
+fn hex_decode(input: &str) -> Result<Vec<u8>> {
+    hex::decode(input.trim_start_matches("0x"))
+        .map_err(|err| format!("{:?} is not valid hex: {}", input, err).into())
+}
+
+/// Wraps `inner` into the single field of the composite `fields`.
+fn wrap_field(fields: &[scale_info::Field<PortableForm>], inner: Value) -> Value {
+    match fields.first().and_then(|field| field.name.as_ref()) {
+        Some(name) => Value::named_composite([(name.clone(), inner)]),
+        None => Value::unnamed_composite([inner]),
+    }
+}
+
+/// Converts `arg` into a value of type `ty`.
+fn value_from_arg(registry: &PortableRegistry, ty: u32, arg: &ArgValue) -> Result<Value> {
+    let resolved = resolve(registry, ty)?;
+    let value = match (&resolved.type_def, arg) {
+        (TypeDef::Compact(compact), _) => {
+            value_from_arg(registry, compact.type_param.id, arg)?
+        }
+        (TypeDef::Primitive(TypeDefPrimitive::Bool), ArgValue::Bool(val)) => {
+            Value::bool(*val)
+        }
+        (TypeDef::Primitive(TypeDefPrimitive::Str), ArgValue::Str(val)) => {
+            Value::string(val.clone())
+        }
+        (
+            TypeDef::Primitive(
+                TypeDefPrimitive::U8
+                | TypeDefPrimitive::U16
+                | TypeDefPrimitive::U32
+                | TypeDefPrimitive::U64
+                | TypeDefPrimitive::U128,
+            ),
+            ArgValue::UInt(val),
+        ) => Value::u128(*val),
+        (TypeDef::Primitive(TypeDefPrimitive::U128), ArgValue::Balance(balance)) => {
+            Value::u128(balance.as_plancks())
+        }
+        (
+            TypeDef::Primitive(
+                TypeDefPrimitive::I8
+                | TypeDefPrimitive::I16
+                | TypeDefPrimitive::I32
+                | TypeDefPrimitive::I64
+                | TypeDefPrimitive::I128,
+            ),
+            ArgValue::Int(val),
+        ) => Value::i128(*val),
+        (TypeDef::Composite(composite), ArgValue::Account(account))
+            if is_account_id(resolved) =>
+        {
+            let account = resolve_account(account)?;
+            wrap_field(&composite.fields, Value::from_bytes(account.0))
+        }
+        (TypeDef::Composite(composite), ArgValue::Struct(fields)) => {
+            let values = composite
+                .fields
+                .iter()
+                .map(|field| {
+                    let name = field.name.clone().unwrap_or_default();
+                    let value = match fields
+                        .iter()
+                        .find(|(key, _)| normalize_arg(key) == normalize(&name))
+                    {
+                        Some((_, value)) => value_from_arg(registry, field.ty.id, value)?,
+                        None => default_value(registry, field.ty.id)?,
+                    };
+                    Ok((name, value))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Value::named_composite(values)
+        }
+        // newtypes like `Hash` are entered as their inner value
+        (TypeDef::Composite(composite), _) if composite.fields.len() == 1 => {
+            let inner = value_from_arg(registry, composite.fields[0].ty.id, arg)?;
+            wrap_field(&composite.fields, inner)
+        }
+        (TypeDef::Array(array), ArgValue::Bytes(bytes))
+            if is_u8(registry, array.type_param.id) =>
+        {
+            if bytes.len() != array.len as usize {
+                return Err(format!(
+                    "expected {} bytes, but got {}",
+                    array.len,
+                    bytes.len()
+                )
+                .into())
+            }
+            Value::from_bytes(bytes)
+        }
+        (TypeDef::Sequence(sequence), ArgValue::Bytes(bytes))
+            if is_u8(registry, sequence.type_param.id) =>
+        {
+            Value::from_bytes(bytes)
+        }
+        (TypeDef::Array(array), ArgValue::Vec(elements)) => {
+            sequence_value(registry, array.type_param.id, elements)?
+        }
+        (TypeDef::Sequence(sequence), ArgValue::Vec(elements)) => {
+            sequence_value(registry, sequence.type_param.id, elements)?
+        }
+        (TypeDef::Variant(_), ArgValue::Option(None)) => {
+            Value::unnamed_variant("None", [])
+        }
+        (TypeDef::Variant(variant), ArgValue::Option(Some(value))) => {
+            let some = variant
+                .variants
+                .iter()
+                .find(|variant| variant.name == "Some")
+                .and_then(|variant| variant.fields.first())
+                .ok_or("malformed `Option` type")?;
+            Value::unnamed_variant("Some", [value_from_arg(registry, some.ty.id, value)?])
+        }
+        (TypeDef::Variant(variant), ArgValue::Enum(name, values)) => {
+            let variant = variant
+                .variants
+                .iter()
+                .find(|variant| variant.name == *name)
+                .ok_or_else(|| format!("variant {:?} does not exist", name))?;
+            if variant.fields.len() != values.len() {
+                return Err(format!(
+                    "variant {:?} has {} fields, but {} were given",
+                    name,
+                    variant.fields.len(),
+                    values.len()
+                )
+                .into())
+            }
+            let values = variant
+                .fields
+                .iter()
+                .zip(values)
+                .map(|(field, value)| value_from_arg(registry, field.ty.id, value))
+                .collect::<Result<Vec<_>>>()?;
+            Value::unnamed_variant(name.clone(), values)
+        }
+        _ => {
+            return Err(format!(
+                "{:?} can not be converted into type {:?}",
+                arg, resolved.path
+            )
+            .into())
+        }
+    };
+    Ok(value)
+}
+
+/// Converts `elements` into a sequence with elements of type `element_ty`.
+fn sequence_value(
+    registry: &PortableRegistry,
+    element_ty: u32,
+    elements: &[ArgValue],
+) -> Result<Value> {
+    let values = elements
+        .iter()
+        .map(|element| value_from_arg(registry, element_ty, element))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Value::unnamed_composite(values))
+}
+
+/// Returns the default value of type `ty`, as pre-filled by the UIs.
+fn default_value(registry: &PortableRegistry, ty: u32) -> Result<Value> {
+    let resolved = resolve(registry, ty)?;
+    let value = match &resolved.type_def {
+        TypeDef::Primitive(TypeDefPrimitive::Bool) => Value::bool(false),
+        TypeDef::Primitive(TypeDefPrimitive::Str) => Value::string(""),
+        TypeDef::Primitive(TypeDefPrimitive::Char) => Value::char('\0'),
+        TypeDef::Primitive(
+            TypeDefPrimitive::I8
+            | TypeDefPrimitive::I16
+            | TypeDefPrimitive::I32
+            | TypeDefPrimitive::I64
+            | TypeDefPrimitive::I128,
+        ) => Value::i128(0),
+        TypeDef::Primitive(_) => Value::u128(0),
+        TypeDef::Compact(compact) => default_value(registry, compact.type_param.id)?,
+        TypeDef::Composite(composite) if is_account_id(resolved) => {
+            let alice = resolve_account("ALICE")?;
+            wrap_field(&composite.fields, Value::from_bytes(alice.0))
+        }
+        TypeDef::Composite(composite) => {
+            let values = composite
+                .fields
+                .iter()
+                .map(|field| {
+                    Ok((field.name.clone(), default_value(registry, field.ty.id)?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if values.iter().all(|(name, _)| name.is_some()) {
+                Value::named_composite(
+                    values
+                        .into_iter()
+                        .map(|(name, value)| (name.unwrap_or_default(), value)),
+                )
+            } else {
+                Value::unnamed_composite(values.into_iter().map(|(_, value)| value))
+            }
+        }
+        TypeDef::Array(array) => {
+            let values = (0..array.len)
+                .map(|_| default_value(registry, array.type_param.id))
+                .collect::<Result<Vec<_>>>()?;
+            Value::unnamed_composite(values)
+        }
+        TypeDef::Sequence(_) => Value::unnamed_composite([]),
+        TypeDef::Tuple(tuple) => {
+            let values = tuple
+                .fields
+                .iter()
+                .map(|field| default_value(registry, field.id))
+                .collect::<Result<Vec<_>>>()?;
+            Value::unnamed_composite(values)
+        }
+        TypeDef::Variant(variant) => {
+            let first = variant
+                .variants
+                .iter()
+                .min_by_key(|variant| variant.index)
+                .ok_or("enum without variants")?;
+            let values = first
+                .fields
+                .iter()
+                .map(|field| default_value(registry, field.ty.id))
+                .collect::<Result<Vec<_>>>()?;
+            Value::unnamed_variant(first.name.clone(), values)
+        }
+        TypeDef::BitSequence(_) => return Err("bit sequences are not supported".into()),
+    };
+    Ok(value)
+}
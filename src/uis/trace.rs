@@ -0,0 +1,181 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A structured trace of the steps which a test executes.
+//!
+//! Every interaction with a [`super::Ui`] and every browser action within it is
+//! recorded as a [`Step`], with its duration, the number of retries and its
+//! outcome. At the end of a test the trace is written to `trace.json` in the
+//! artifacts directory of the test.
+
+use crate::utils::{
+    test_artifacts_dir,
+    test_name,
+};
+use serde::Serialize;
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    future::Future,
+    time::Instant,
+};
+
+thread_local! {
+    /// The trace of the currently executing test.
+    static TRACE: RefCell<Trace> = RefCell::new(Trace::new());
+}
+
+/// The steps which a test executed, in the order in which they were started.
+#[derive(Serialize)]
+struct Trace {
+    test: String,
+    steps: Vec<Step>,
+    /// When the test started, the steps are timed relative to it.
+    #[serde(skip)]
+    started: Instant,
+    /// The indices of the steps which are not yet finished, the innermost last.
+    #[serde(skip)]
+    open: Vec<usize>,
+}
+
+impl Trace {
+    fn new() -> Self {
+        Self {
+            test: test_name(),
+            steps: Vec::new(),
+            started: Instant::now(),
+            open: Vec::new(),
+        }
+    }
+}
+
+/// A single step of a test, e.g. `execute_upload` or a `click` within it.
+#[derive(Serialize)]
+struct Step {
+    /// The name of the step, e.g. `click`.
+    step: String,
+    /// The locator of the element which the step interacts with, if any.
+    selector: Option<String>,
+    /// The number of steps which this one is nested in.
+    depth: usize,
+    /// Milliseconds since the start of the test, when the step was started.
+    started_ms: u128,
+    /// Milliseconds which the step took.
+    duration_ms: u128,
+    /// How often the step was retried.
+    retries: u32,
+    outcome: Outcome,
+}
+
+/// How a step ended.
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum Outcome {
+    Ok,
+    Error {
+        message: String,
+    },
+    /// The step did not finish, e.g. because the test panicked within it.
+    Aborted,
+}
+
+/// Starts a new, empty trace for the currently executing test.
+pub fn reset() {
+    TRACE.with(|trace| *trace.borrow_mut() = Trace::new());
+}
+
+/// Executes `action` as the step `step` and records it in the trace.
+///
+/// `selector` is the locator of the element which the step interacts with.
+pub async fn step<T, E, Fut>(
+    step: &str,
+    selector: Option<String>,
+    action: Fut,
+) -> Result<T, E>
+where
+    E: Display,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let index = TRACE.with(|trace| {
+        let mut trace = trace.borrow_mut();
+        let index = trace.steps.len();
+        let started_ms = trace.started.elapsed().as_millis();
+        let depth = trace.open.len();
+        trace.steps.push(Step {
+            step: step.to_string(),
+            selector,
+            depth,
+            started_ms,
+            duration_ms: 0,
+            retries: 0,
+            outcome: Outcome::Aborted,
+        });
+        trace.open.push(index);
+        index
+    });
+    let started = Instant::now();
+    let result = action.await;
+    TRACE.with(|trace| {
+        let mut trace = trace.borrow_mut();
+        trace.open.retain(|open| *open != index);
+        let step = &mut trace.steps[index];
+        step.duration_ms = started.elapsed().as_millis();
+        step.outcome = match &result {
+            Ok(_) => Outcome::Ok,
+            Err(err) => {
+                Outcome::Error {
+                    message: err.to_string(),
+                }
+            }
+        };
+    });
+    result
+}
+
+/// Records a retry of the innermost step which is not yet finished.
+pub fn retry() {
+    TRACE.with(|trace| {
+        let mut trace = trace.borrow_mut();
+        if let Some(index) = trace.open.last().copied() {
+            trace.steps[index].retries += 1;
+        }
+    });
+}
+
+/// Writes the trace of the currently executing test to `trace.json` in its
+/// artifacts directory.
+///
+/// Errors are only logged, they must not fail the test.
+pub fn save() {
+    let dir = test_artifacts_dir();
+    let path = dir.join("trace.json");
+    let written = std::fs::create_dir_all(&dir).and_then(|_| {
+        TRACE.with(|trace| {
+            let json = serde_json::to_string_pretty(&*trace.borrow())
+                .expect("the trace can always be serialized");
+            std::fs::write(&path, json)
+        })
+    });
+    match written {
+        Ok(()) => log::info!("[{}] wrote trace to {:?}", test_name(), path),
+        Err(err) => {
+            log::error!(
+                "[{}] unable to write trace to {:?}: {}",
+                test_name(),
+                path,
+                err
+            )
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Waiting for a condition instead of sleeping for a fixed time.
+//!
+//! The condition is polled without blocking the tokio worker in between. Every
+//! wait is bounded by a timeout, which defaults to [`timeout`] and can be
+//! overridden per call.
+
+use crate::uis::{
+    Result,
+    TransactionError,
+    TransactionResult,
+};
+use std::{
+    future::Future,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// Returns the default timeout for waiting on a condition.
+///
+/// Set via `WATERFALL_TIMEOUT` in seconds, defaults to 60 seconds.
+pub fn timeout() -> Duration {
+    std::env::var("WATERFALL_TIMEOUT")
+        .map(|secs| {
+            Duration::from_secs(
+                secs.parse()
+                    .expect("unable to parse `WATERFALL_TIMEOUT` into seconds"),
+            )
+        })
+        .unwrap_or(Duration::from_secs(60))
+}
+
+/// Returns the default interval in which a condition is polled.
+///
+/// Set via `WATERFALL_POLL_INTERVAL` in milliseconds, defaults to 250 milliseconds.
+pub fn poll_interval() -> Duration {
+    std::env::var("WATERFALL_POLL_INTERVAL")
+        .map(|millis| {
+            Duration::from_millis(
+                millis.parse().expect(
+                    "unable to parse `WATERFALL_POLL_INTERVAL` into milliseconds",
+                ),
+            )
+        })
+        .unwrap_or(Duration::from_millis(250))
+}
+
+/// Polls `condition` every `poll_interval` until it returns `Some`, and returns
+/// the contained value.
+///
+/// `what` describes the condition, e.g. `element //button`, and ends up in the
+/// [`TransactionError::Timeout`] returned if the condition is not met within
+/// `timeout`. An error returned by `condition` aborts the wait.
+pub async fn wait_until<T, F, Fut>(
+    what: &str,
+    timeout: Duration,
+    poll_interval: Duration,
+    mut condition: F,
+) -> TransactionResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<T>>>,
+{
+    let started = Instant::now();
+    loop {
+        if let Some(value) = condition().await? {
+            return Ok(value)
+        }
+        if started.elapsed() > timeout {
+            return Err(TransactionError::Timeout(format!(
+                "{} not reached within {:?}",
+                what, timeout
+            )))
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
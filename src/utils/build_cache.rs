@@ -0,0 +1,414 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A cache of the contracts which the tests build.
+//!
+//! Many tests build the same contract, often at the same time. Every build is
+//! identified by a key, which hashes the sources of the contract and of its local
+//! dependencies, the version of `cargo-contract` and the [`BuildOptions`]. The
+//! first caller of a key builds the contract, concurrent callers of the same key
+//! wait for this build instead of starting their own. The bundle is then copied
+//! into `<cache>/<key>/`, which later runs reuse as well.
+//!
+//! The cache directory is set via `WATERFALL_BUILD_CACHE`, defaults to
+//! `target/waterfall/build-cache`.
+
+use super::cargo_contract::{
+    self,
+    BuildOptions,
+    BuildResult,
+    CargoMetadata,
+};
+use lazy_static::lazy_static;
+use sha2::{
+    Digest,
+    Sha256,
+};
+use std::{
+    collections::HashMap,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+};
+
+/// The file of a cache entry which holds the output of `cargo contract build`.
+const BUILD_RESULT: &str = "build-result.json";
+
+lazy_static! {
+    /// The directory which holds the cache entries.
+    static ref CACHE_DIR: PathBuf = {
+        let dir = std::env::var("WATERFALL_BUILD_CACHE")
+            .unwrap_or(String::from("target/waterfall/build-cache"));
+        std::fs::create_dir_all(&dir).unwrap_or_else(|err| {
+            panic!("unable to create build cache directory {:?}: {}", dir, err)
+        });
+        // the UIs upload the bundles from this directory, which requires an
+        // absolute path
+        PathBuf::from(&dir).canonicalize().unwrap_or_else(|err| {
+            panic!("unable to canonicalize build cache directory {:?}: {}", dir, err)
+        })
+    };
+
+    /// The version of `cargo-contract`, which is part of every key.
+    static ref CARGO_CONTRACT_VERSION: Result<String, String> = cargo_contract::version();
+
+    /// The bundles which were resolved in this run, by their key.
+    ///
+    /// The lock of an entry is held while its bundle is resolved, so that all
+    /// callers of a key wait for a single build.
+    static ref ENTRIES: Mutex<HashMap<String, Arc<Mutex<Option<PathBuf>>>>> =
+        Mutex::new(HashMap::new());
+
+    /// The locks of the target directories, builds of different keys which
+    /// write into the same target directory must not overlap.
+    static ref TARGET_DIRS: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>> =
+        Mutex::new(HashMap::new());
+
+    /// The sources of the contracts by their manifest path, the sources do not
+    /// change during a run.
+    static ref SOURCES: Mutex<HashMap<PathBuf, Arc<Sources>>> = Mutex::new(HashMap::new());
+}
+
+/// The sources of a contract and its local dependencies.
+struct Sources {
+    /// The hash of all source trees.
+    hash: String,
+    metadata: CargoMetadata,
+}
+
+/// Locks `mutex`, even if a thread panicked while holding it.
+///
+/// A build which panicked leaves its entry empty, the next caller of the key then
+/// builds again.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Returns the bundle of the variant `options` of the contract at
+/// `manifest_path`, builds the contract only if the cache does not hold it yet.
+pub(crate) fn build(
+    manifest_path: &Path,
+    options: &BuildOptions,
+) -> Result<PathBuf, String> {
+    let sources = sources(manifest_path)?;
+    let version = CARGO_CONTRACT_VERSION.clone()?;
+    let key = key(manifest_path, &sources, &version, options);
+    let entry = lock(&ENTRIES).entry(key.clone()).or_default().clone();
+    let mut bundle = lock(&entry);
+    if let Some(bundle) = &*bundle {
+        log::info!("reusing {:?} which was built in this run", bundle);
+        return Ok(bundle.clone())
+    }
+
+    let dir = CACHE_DIR.join(&key);
+    let result = match load(&dir) {
+        Some(result) => {
+            log::info!("reusing build {:?} of {:?}", dir, manifest_path);
+            result
+        }
+        None => {
+            let target_dir = options.target_directory(&sources.metadata);
+            let target_lock = lock(&TARGET_DIRS).entry(target_dir).or_default().clone();
+            let _guard = lock(&target_lock);
+            let json = cargo_contract::run_build(manifest_path, options)?;
+            store(&key, &json)?
+        }
+    };
+    log::info!("{:?} was built in {:?} mode", dir, result.build_mode);
+    let mut contract_dir = manifest_path.to_path_buf();
+    contract_dir.pop();
+    cargo_contract::record_size(&contract_dir, &result);
+
+    let path = bundle_in(&dir, &result)?;
+    log::info!("path to the resulting contract bundle: {:?}", path);
+    *bundle = Some(path.clone());
+    Ok(path)
+}
+
+/// Returns the key of the build of `options` of the contract at `manifest_path`
+/// with the `version` of `cargo-contract`.
+///
+/// The key is prefixed with the name of the contract's directory, so that the
+/// entries of the cache can be told apart.
+fn key(
+    manifest_path: &Path,
+    sources: &Sources,
+    version: &str,
+    options: &BuildOptions,
+) -> String {
+    let mut hasher = Sha256::new();
+    for part in [
+        manifest_path.to_string_lossy().as_ref(),
+        &sources.hash,
+        version,
+        &format!("{:?}", options),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    let name = manifest_path
+        .parent()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    format!("{}-{}", name, hex::encode(&hasher.finalize()[..16]))
+}
+
+/// Returns the sources of the contract at `manifest_path`.
+fn sources(manifest_path: &Path) -> Result<Arc<Sources>, String> {
+    if let Some(sources) = lock(&SOURCES).get(manifest_path) {
+        return Ok(sources.clone())
+    }
+    // `cargo metadata` writes a missing `Cargo.lock`, so it has to run before the
+    // sources are hashed
+    let metadata = cargo_contract::metadata(manifest_path)?;
+    let mut roots: Vec<PathBuf> = metadata
+        .packages
+        .iter()
+        .filter(|package| package.source.is_none())
+        .filter_map(|package| package.manifest_path.parent().map(Path::to_path_buf))
+        .collect();
+    roots.sort();
+    roots.dedup();
+
+    let mut hasher = Sha256::new();
+    for root in &roots {
+        hash_tree(&mut hasher, root, root)?;
+    }
+    let lockfile = metadata.workspace_root.join("Cargo.lock");
+    if lockfile.exists() {
+        hash_file(&mut hasher, &metadata.workspace_root, &lockfile)?;
+    }
+    let sources = Arc::new(Sources {
+        hash: hex::encode(hasher.finalize()),
+        metadata,
+    });
+    lock(&SOURCES).insert(manifest_path.to_path_buf(), sources.clone());
+    Ok(sources)
+}
+
+/// Hashes all files below `dir`, in a stable order.
+///
+/// Hidden files and `target` directories are skipped, they do not contain the
+/// sources.
+fn hash_tree(hasher: &mut Sha256, root: &Path, dir: &Path) -> Result<(), String> {
+    let mut paths = std::fs::read_dir(dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<std::io::Result<Vec<_>>>()
+        })
+        .map_err(|err| format!("unable to read {:?}: {}", dir, err))?;
+    paths.sort();
+    for path in paths {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with('.') || name == "target" {
+            continue
+        }
+        if path.is_dir() {
+            hash_tree(hasher, root, &path)?;
+        } else {
+            hash_file(hasher, root, &path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Hashes the path of `file` relative to `root` and its contents.
+fn hash_file(hasher: &mut Sha256, root: &Path, file: &Path) -> Result<(), String> {
+    let contents = std::fs::read(file)
+        .map_err(|err| format!("unable to read {:?}: {}", file, err))?;
+    let relative = file.strip_prefix(root).unwrap_or(file);
+    hasher.update(relative.to_string_lossy().as_bytes());
+    hasher.update([0]);
+    hasher.update((contents.len() as u64).to_le_bytes());
+    hasher.update(&contents);
+    Ok(())
+}
+
+/// Returns the build result of the cache entry `dir`, if it exists and is
+/// complete.
+fn load(dir: &Path) -> Option<BuildResult> {
+    let json = std::fs::read(dir.join(BUILD_RESULT)).ok()?;
+    let result = cargo_contract::parse_build_result(&json)
+        .map_err(|err| log::warn!("ignoring cache entry {:?}: {}", dir, err))
+        .ok()?;
+    let bundle = bundle_in(dir, &result).ok()?;
+    bundle.exists().then_some(result)
+}
+
+/// Stores the bundle of the build `json` in the cache entry `key`.
+///
+/// The entry is written into a temporary directory first and then renamed, so
+/// that an entry is never seen half-written, not even by another run.
+fn store(key: &str, json: &[u8]) -> Result<BuildResult, String> {
+    let result = cargo_contract::parse_build_result(json)?;
+    let bundle = result.bundle()?;
+    let dir = CACHE_DIR.join(key);
+    let tmp = CACHE_DIR.join(format!("{}.tmp-{}", key, std::process::id()));
+    let copy = bundle_in(&tmp, &result)?;
+    let _ = std::fs::remove_dir_all(&tmp);
+    std::fs::create_dir_all(&tmp)
+        .and_then(|_| std::fs::copy(bundle, &copy))
+        .and_then(|_| std::fs::write(tmp.join(BUILD_RESULT), json))
+        .map_err(|err| format!("unable to write cache entry {:?}: {}", tmp, err))?;
+    if let Err(err) = std::fs::rename(&tmp, &dir) {
+        let _ = std::fs::remove_dir_all(&tmp);
+        // another run might have stored the same entry in the meantime
+        if load(&dir).is_none() {
+            return Err(format!("unable to store cache entry {:?}: {}", dir, err))
+        }
+    }
+    Ok(result)
+}
+
+/// Returns the path of the bundle of `result` within the cache entry `dir`.
+fn bundle_in(dir: &Path, result: &BuildResult) -> Result<PathBuf, String> {
+    let bundle = result.bundle()?;
+    let name = bundle
+        .file_name()
+        .ok_or_else(|| format!("the bundle {:?} has no file name", bundle))?;
+    Ok(dir.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a fresh directory for the test `name`.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "waterfall-build-cache-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("creating temporary directory failed");
+        dir
+    }
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().expect("path has a parent"))
+            .and_then(|_| std::fs::write(path, contents))
+            .expect("writing file failed");
+    }
+
+    fn hash(root: &Path) -> String {
+        let mut hasher = Sha256::new();
+        hash_tree(&mut hasher, root, root).expect("hashing failed");
+        hex::encode(hasher.finalize())
+    }
+
+    fn sources(hash: &str) -> Sources {
+        Sources {
+            hash: hash.to_string(),
+            metadata: CargoMetadata {
+                packages: Vec::new(),
+                workspace_root: PathBuf::from("/examples"),
+                target_directory: PathBuf::from("/examples/target"),
+            },
+        }
+    }
+
+    #[test]
+    fn source_hash_only_changes_with_the_sources() {
+        // given
+        let dir = temp_dir("sources");
+        write(&dir.join("Cargo.toml"), "[package]");
+        write(&dir.join("lib.rs"), "fn flip() {}");
+        let original = hash(&dir);
+
+        // when
+        write(&dir.join("target/ink/flipper.contract"), "{}");
+        write(&dir.join(".git/HEAD"), "ref: refs/heads/master");
+        let with_artifacts = hash(&dir);
+        write(&dir.join("lib.rs"), "fn flop() {}");
+        let changed = hash(&dir);
+        std::fs::rename(dir.join("lib.rs"), dir.join("main.rs"))
+            .expect("renaming file failed");
+        let renamed = hash(&dir);
+
+        // then
+        assert_eq!(original, with_artifacts);
+        assert_ne!(original, changed);
+        assert_ne!(changed, renamed);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn source_hash_does_not_depend_on_the_location() {
+        // given
+        let first = temp_dir("first");
+        let second = temp_dir("second");
+
+        // when
+        for dir in [&first, &second] {
+            write(&dir.join("Cargo.toml"), "[package]");
+            write(&dir.join("src/lib.rs"), "fn flip() {}");
+        }
+
+        // then
+        assert_eq!(hash(&first), hash(&second));
+        let _ = std::fs::remove_dir_all(&first);
+        let _ = std::fs::remove_dir_all(&second);
+    }
+
+    #[test]
+    fn key_changes_with_every_input() {
+        // given
+        let manifest = Path::new("/examples/flipper/Cargo.toml");
+        let options = BuildOptions::new();
+        let original = key(manifest, &sources("abc"), "3.2.0", &options);
+
+        // when
+        let same = key(manifest, &sources("abc"), "3.2.0", &options);
+        let variants = [
+            key(
+                Path::new("/examples/erc20/Cargo.toml"),
+                &sources("abc"),
+                "3.2.0",
+                &options,
+            ),
+            key(manifest, &sources("abd"), "3.2.0", &options),
+            key(manifest, &sources("abc"), "4.0.0", &options),
+            key(
+                manifest,
+                &sources("abc"),
+                "3.2.0",
+                &options.clone().release(),
+            ),
+            key(
+                manifest,
+                &sources("abc"),
+                "3.2.0",
+                &options.clone().feature("e2e-tests"),
+            ),
+        ];
+
+        // then
+        assert_eq!(original, same);
+        assert!(original.starts_with("flipper-"));
+        for variant in variants {
+            assert_ne!(original, variant);
+        }
+    }
+}
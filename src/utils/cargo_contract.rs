@@ -12,121 +12,400 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use regex::Regex;
+use lazy_static::lazy_static;
+use serde::Deserialize;
 use std::{
-    path::PathBuf,
+    fs::OpenOptions,
+    io::Write,
+    path::{
+        Path,
+        PathBuf,
+    },
     process::Command,
+    sync::Mutex,
 };
 
-/// Builds the contract at `manifest_path` using `cargo contract`.
+lazy_static! {
+    /// Serializes the writes of all tests to the size report.
+    static ref SIZES: Mutex<()> = Mutex::new(());
+}
+
+/// The outcome of `cargo contract build --output-json`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct BuildResult {
+    /// The metadata and the bundle, missing if only the code was built.
+    pub(crate) metadata_result: Option<MetadataArtifacts>,
+    /// The directory into which the artifacts were written.
+    pub(crate) target_directory: PathBuf,
+    /// The sizes of the Wasm code before and after `wasm-opt`.
+    pub(crate) optimization_result: Option<OptimizationResult>,
+    pub(crate) build_mode: BuildMode,
+}
+
+impl BuildResult {
+    /// Returns the path to the `.contract` bundle.
+    pub(crate) fn bundle(&self) -> Result<&Path, String> {
+        self.metadata_result
+            .as_ref()
+            .map(|metadata| metadata.dest_bundle.as_path())
+            .ok_or_else(|| {
+                format!(
+                    "no contract bundle was built into {:?}",
+                    self.target_directory
+                )
+            })
+    }
+}
+
+/// The metadata artifacts of a build.
+#[derive(Debug, Deserialize)]
+pub(crate) struct MetadataArtifacts {
+    /// The `.contract` bundle of code and metadata.
+    pub(crate) dest_bundle: PathBuf,
+}
+
+/// The sizes of the Wasm code in KiB.
+#[derive(Debug, Deserialize)]
+pub(crate) struct OptimizationResult {
+    pub(crate) original_size: f64,
+    pub(crate) optimized_size: f64,
+}
+
+/// The mode in which a contract was built.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub(crate) enum BuildMode {
+    Debug,
+    Release,
+    /// A release build within the `cargo-contract` docker image.
+    Verifiable,
+}
+
+/// The variant of a contract which `cargo contract build` builds.
+///
+/// Everything is passed on the command line, so that the `Cargo.toml` of an
+/// example never needs to be edited to e.g. enable a feature. The default is a
+/// debug build with the default features, the way `cargo contract build` builds
+/// without any arguments.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct BuildOptions {
+    release: bool,
+    features: Vec<String>,
+    optimization_passes: Option<String>,
+    keep_debug_symbols: bool,
+    target_dir: Option<PathBuf>,
+}
+
+impl BuildOptions {
+    /// Creates the options of a default debug build.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds in release mode, like `scripts/ci/build-contract.sh` does.
+    pub(crate) fn release(mut self) -> Self {
+        self.release = true;
+        self
+    }
+
+    /// Enables the cargo feature `feature` of the contract.
+    pub(crate) fn feature(mut self, feature: &str) -> Self {
+        self.features.push(feature.to_string());
+        self
+    }
+
+    /// Sets the number of optimization passes of `wasm-opt`, e.g. `0` or `z`.
+    pub(crate) fn optimization_passes(mut self, passes: &str) -> Self {
+        self.optimization_passes = Some(passes.to_string());
+        self
+    }
+
+    /// Keeps the debug symbols in the Wasm code.
+    pub(crate) fn keep_debug_symbols(mut self) -> Self {
+        self.keep_debug_symbols = true;
+        self
+    }
+
+    /// Writes the artifacts into `target_dir` instead of the `target` directory
+    /// of the example.
+    ///
+    /// Variants of the same example should be built into different directories,
+    /// otherwise they overwrite each other's artifacts.
+    pub(crate) fn target_dir(mut self, target_dir: impl Into<PathBuf>) -> Self {
+        self.target_dir = Some(target_dir.into());
+        self
+    }
+
+    /// Returns the directory into which the contract described by `metadata`
+    /// is built with these options.
+    pub(crate) fn target_directory(&self, metadata: &CargoMetadata) -> PathBuf {
+        self.target_dir
+            .clone()
+            .unwrap_or_else(|| metadata.target_directory.clone())
+    }
+
+    /// Returns the arguments of `cargo contract build` for these options.
+    fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.release {
+            args.push(String::from("--release"));
+        }
+        if !self.features.is_empty() {
+            args.push(format!("--features={}", self.features.join(",")));
+        }
+        if let Some(passes) = &self.optimization_passes {
+            args.push(format!("--optimization-passes={}", passes));
+        }
+        if self.keep_debug_symbols {
+            args.push(String::from("--keep-debug-symbols"));
+        }
+        if let Some(target_dir) = &self.target_dir {
+            args.push(format!("--target-dir={}", target_dir.display()));
+        }
+        args
+    }
+}
+
+/// Builds the contract at `manifest_path` using `cargo contract`, with the
+/// default [`BuildOptions`].
+///
+/// If successful, returns the path to the `.contract` file.
+pub(crate) fn build(manifest_path: &Path) -> Result<PathBuf, String> {
+    build_with(manifest_path, &BuildOptions::new())
+}
+
+/// Builds the variant `options` of the contract at `manifest_path` using
+/// `cargo contract`.
 ///
 /// If successful, returns the path to the `.contract` file.
-pub(crate) fn build(manifest_path: &PathBuf) -> Result<PathBuf, String> {
+pub(crate) fn build_with(
+    manifest_path: &Path,
+    options: &BuildOptions,
+) -> Result<PathBuf, String> {
     let skip_build: String =
         std::env::var("WATERFALL_SKIP_CONTRACT_BUILD").unwrap_or(String::from("false"));
     if skip_build == "true" {
         log::info!("skipping contract build");
-        let mut manifest_path = manifest_path.clone();
-        manifest_path.pop();
-
-        // extract example name from manifest path
-        let example_name = manifest_path
-            .iter()
-            .last()
-            .expect("last must exist")
-            .to_str()
-            .expect("to_str must work")
-            .replace("-", "_");
-
-        // we need to take special care of examples in sub-directories
-        let name = match example_name.as_str() {
-            "accumulator" => {
-                manifest_path.pop();
-                "accumulator/accumulator"
-            }
-            "subber" => {
-                manifest_path.pop();
-                "subber/subber"
-            }
-            "adder" => {
-                manifest_path.pop();
-                "adder/adder"
-            }
-            "set_code_hash" => "incrementer",
-            _ => example_name.as_str(),
-        };
-        let possibly_target_dir = std::env::var("CARGO_TARGET_DIR");
-        let artifact_path = match possibly_target_dir {
-            Ok(target_dir) => {
-                let mut path = PathBuf::from(target_dir);
-                path.push(format!("ink/{}.contract", name));
-                path
-            }
-            Err(_) => {
-                manifest_path.push(format!("target/ink/{}.contract", name));
-                manifest_path.clone()
-            }
-        };
+        let metadata = metadata(manifest_path)?;
+        let artifact_path = bundle_path(manifest_path, &metadata, options)?;
+        if !artifact_path.exists() {
+            return Err(format!(
+                "the contract bundle {:?} of {:?} is missing, build it first or unset \
+                `WATERFALL_SKIP_CONTRACT_BUILD`",
+                artifact_path, manifest_path
+            ))
+        }
         log::info!("using artifact path {:?}", artifact_path);
         return Ok(artifact_path)
     }
 
+    super::build_cache::build(manifest_path, options)
+}
+
+/// Runs `cargo contract build` for the variant `options` of the contract at
+/// `manifest_path`, bypassing the build cache.
+///
+/// If successful, returns the JSON printed by `cargo contract`, which
+/// [`parse_build_result`] parses.
+pub(crate) fn run_build(
+    manifest_path: &Path,
+    options: &BuildOptions,
+) -> Result<Vec<u8>, String> {
     assert_wasm_opt_available();
 
-    let mut dir = manifest_path.clone();
+    let mut dir = manifest_path.to_path_buf();
     dir.pop(); // pop `Cargo.toml` from the path
 
     let output = Command::new("cargo")
         .arg("contract")
         .arg("build")
         .arg("--manifest-path=Cargo.toml")
-        .current_dir(dir)
-        // we want to receive the child's output as part of the ink-waterfall stdout
+        .arg("--output-json")
+        .args(options.args())
+        .current_dir(&dir)
+        // the build result is printed to stdout, the progress to stderr
         .stdout(std::process::Stdio::piped())
         .spawn()
+        .and_then(|child| child.wait_with_output())
         .map_err(|err| {
             format!(
                 "ERROR while executing `cargo-contract` with {:?}: {:?}",
                 manifest_path, err
             )
-        })
-        .expect("failed to execute process")
-        .wait_with_output()
-        .expect("failed to receive output");
-
-    if output.status.success() {
-        let stdout = String::from_utf8(output.stdout).expect("string conversion failed");
-        // extract the path to the resulting `.contract` from the output
-        let re_path = Regex::new(
-            r"Your contract artifacts are ready. You can find them in:\n([A-Za-z0-9_\-/]+)\n"
-        )
-            .expect("invalid regex");
-        let captures = re_path
-            .captures(&stdout)
-            .ok_or("regex does not match the command output")
-            .map_err(|err| format!("{}: '{:?}'", err, stdout))?;
-        let directory = captures.get(1).expect("no capture group found").as_str();
-
-        // extract the basename to the resulting `.contract`
-        let re_basename =
-            Regex::new(r"\- ([A-Za-z0-9_\-/]+).contract \(code \+ metadata\)\n")
-                .expect("invalid regex");
-        let captures = re_basename
-            .captures(&stdout)
-            .ok_or("regex does not match the command output")
-            .map_err(|err| format!("{}: '{:?}'", err, stdout))?;
-        let basename = captures.get(1).expect("no capture group found").as_str();
-        let path = PathBuf::from(directory).join(format!("{}.contract", basename));
-        log::info!("path to the resulting contract bundle: {:?}", path);
-        Ok(path)
-    } else {
+        })?;
+
+    if !output.status.success() {
         let stderr = String::from_utf8(output.stderr).expect("string conversion failed");
-        Err(format!(
+        return Err(format!(
             "Failed with exit code: {:?} and '{:?}'",
             output.status.code(),
             stderr
         ))
     }
+    log::info!("built {:?} with {:?}", manifest_path, options);
+    Ok(output.stdout)
+}
+
+/// Parses the JSON output of `cargo contract build --output-json`.
+pub(crate) fn parse_build_result(json: &[u8]) -> Result<BuildResult, String> {
+    serde_json::from_slice(json).map_err(|err| {
+        format!(
+            "unable to parse the build result {:?}: {}",
+            String::from_utf8_lossy(json),
+            err
+        )
+    })
+}
+
+/// Returns the output of `cargo contract --version`, e.g.
+/// `cargo-contract-contract 3.2.0-unknown-x86_64-unknown-linux-gnu`.
+pub(crate) fn version() -> Result<String, String> {
+    let output = Command::new("cargo")
+        .arg("contract")
+        .arg("--version")
+        .output()
+        .map_err(|err| {
+            format!("unable to execute `cargo contract --version`: {}", err)
+        })?;
+    if !output.status.success() {
+        return Err(format!(
+            "`cargo contract --version` failed with exit code {:?}: {}",
+            output.status.code(),
+            String::from_utf8_lossy(&output.stderr)
+        ))
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The parts of `cargo metadata` which the waterfall uses.
+#[derive(Debug, Deserialize)]
+pub(crate) struct CargoMetadata {
+    /// All packages of the dependency graph.
+    pub(crate) packages: Vec<CargoPackage>,
+    /// The root directory of the workspace, which contains its `Cargo.lock`.
+    pub(crate) workspace_root: PathBuf,
+    /// The directory into which the workspace is built.
+    pub(crate) target_directory: PathBuf,
+}
+
+/// A package in [`CargoMetadata`].
+#[derive(Debug, Deserialize)]
+pub(crate) struct CargoPackage {
+    pub(crate) name: String,
+    /// Where the package comes from, `None` for local packages.
+    pub(crate) source: Option<String>,
+    pub(crate) manifest_path: PathBuf,
+    pub(crate) targets: Vec<CargoTarget>,
+}
+
+/// A target of a [`CargoPackage`], e.g. its library.
+#[derive(Debug, Deserialize)]
+pub(crate) struct CargoTarget {
+    pub(crate) name: String,
+    /// The kinds of the target, e.g. `lib`, `rlib` or `bin`.
+    pub(crate) kind: Vec<String>,
+}
+
+/// Returns the path at which `cargo contract build` writes the bundle of the
+/// contract at `manifest_path`, described by `metadata`, when built with
+/// `options`.
+///
+/// The bundle is named after the contract's library, or its package if it has
+/// none. Like `cargo-contract` does, the bundle of a workspace member is put into
+/// a sub-directory of the same name, e.g. `target/ink/adder/adder.contract`.
+pub(crate) fn bundle_path(
+    manifest_path: &Path,
+    metadata: &CargoMetadata,
+    options: &BuildOptions,
+) -> Result<PathBuf, String> {
+    let canonicalize = |path: &Path| path.canonicalize().unwrap_or(path.to_path_buf());
+    let manifest_path = canonicalize(manifest_path);
+    let package = metadata
+        .packages
+        .iter()
+        .find(|package| canonicalize(&package.manifest_path) == manifest_path)
+        .ok_or_else(|| {
+            format!(
+                "the package of {:?} is missing in its `cargo metadata`",
+                manifest_path
+            )
+        })?;
+    let name = package
+        .targets
+        .iter()
+        .find(|target| {
+            target
+                .kind
+                .iter()
+                .any(|kind| ["lib", "rlib", "cdylib", "dylib"].contains(&kind.as_str()))
+        })
+        .map(|target| target.name.as_str())
+        .unwrap_or(package.name.as_str())
+        .replace('-', "_");
+
+    let mut path = options.target_directory(metadata).join("ink");
+    if manifest_path.parent() != Some(canonicalize(&metadata.workspace_root).as_path()) {
+        path.push(&name);
+    }
+    path.push(format!("{}.contract", name));
+    Ok(path)
+}
+
+/// Returns the `cargo metadata` of the package at `manifest_path`.
+pub(crate) fn metadata(manifest_path: &Path) -> Result<CargoMetadata, String> {
+    let output = Command::new("cargo")
+        .arg("metadata")
+        .arg("--format-version=1")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .output()
+        .map_err(|err| format!("unable to execute `cargo metadata`: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "`cargo metadata` for {:?} failed with exit code {:?}: {}",
+            manifest_path,
+            output.status.code(),
+            String::from_utf8_lossy(&output.stderr)
+        ))
+    }
+    serde_json::from_slice(&output.stdout).map_err(|err| {
+        format!(
+            "unable to parse `cargo metadata` for {:?}: {}",
+            manifest_path, err
+        )
+    })
+}
+
+/// Appends the optimized size of the contract in `dir` to `contract-sizes.csv`
+/// in the artifacts directory of the run.
+///
+/// The lines have the form `<contract>, <optimized size in KiB>`, the same as
+/// the size CSV files which `csv-comparator` reads.
+pub(crate) fn record_size(dir: &Path, result: &BuildResult) {
+    let Some(optimization) = &result.optimization_result else {
+        return
+    };
+    let contract = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    log::info!(
+        "optimized {} from {} KiB to {} KiB",
+        contract,
+        optimization.original_size,
+        optimization.optimized_size
+    );
+    let line = format!("{}, {}\n", contract, optimization.optimized_size);
+    let path = super::artifacts_dir().join("contract-sizes.csv");
+
+    let _guard = SIZES.lock().expect("failed locking `SIZES`");
+    let written = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut sizes| sizes.write_all(line.as_bytes()));
+    if let Err(err) = written {
+        log::error!("unable to write contract size to {:?}: {}", path, err);
+    }
 }
 
 /// Asserts that `wasm-opt` is available.
@@ -139,3 +418,179 @@ fn assert_wasm_opt_available() {
         https://github.com/paritytech/cargo-contract."
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The output of `cargo contract build --output-json` of cargo-contract 3.2.
+    const BUILD_RESULT: &str = r#"{
+  "dest_wasm": "/ink/integration-tests/flipper/target/ink/flipper.wasm",
+  "metadata_result": {
+    "dest_metadata": "/ink/integration-tests/flipper/target/ink/flipper.json",
+    "dest_bundle": "/ink/integration-tests/flipper/target/ink/flipper.contract"
+  },
+  "target_directory": "/ink/integration-tests/flipper/target/ink",
+  "optimization_result": {
+    "original_size": 47.557,
+    "optimized_size": 1.548
+  },
+  "build_mode": "Release",
+  "build_artifact": "All",
+  "verbosity": "Default",
+  "output_type": "Json"
+}"#;
+
+    #[test]
+    fn build_result_is_parsed() {
+        // when
+        let result = parse_build_result(BUILD_RESULT.as_bytes()).expect("parsing failed");
+
+        // then
+        assert_eq!(result.build_mode, BuildMode::Release);
+        assert_eq!(
+            result.bundle(),
+            Ok(Path::new(
+                "/ink/integration-tests/flipper/target/ink/flipper.contract"
+            ))
+        );
+        let optimization = result.optimization_result.expect("no optimization result");
+        assert_eq!(optimization.original_size, 47.557);
+        assert_eq!(optimization.optimized_size, 1.548);
+    }
+
+    #[test]
+    fn build_result_without_bundle_is_parsed() {
+        // given
+        let json = r#"{
+  "dest_wasm": "/flipper/target/ink/flipper.wasm",
+  "metadata_result": null,
+  "target_directory": "/flipper/target/ink",
+  "optimization_result": null,
+  "build_mode": "Debug",
+  "build_artifact": "CodeOnly"
+}"#;
+
+        // when
+        let result = parse_build_result(json.as_bytes()).expect("parsing failed");
+
+        // then
+        assert_eq!(result.build_mode, BuildMode::Debug);
+        assert!(result.bundle().is_err());
+        assert!(result.optimization_result.is_none());
+    }
+
+    #[test]
+    fn build_output_other_than_json_is_rejected() {
+        // when
+        let result = parse_build_result(b"Original wasm size: 47.6K");
+
+        // then
+        let err = result.expect_err("parsing succeeded");
+        assert!(err.contains("Original wasm size"), "{}", err);
+    }
+
+    /// Returns the `cargo metadata` of the workspace `multi-contract-caller` with
+    /// the member `adder`.
+    fn workspace_metadata() -> CargoMetadata {
+        let package = |name: &str, manifest_path: &str, targets: Vec<(&str, &str)>| {
+            serde_json::json!({
+                "name": name,
+                "version": "4.3.0",
+                "source": null,
+                "manifest_path": manifest_path,
+                "targets": targets
+                    .into_iter()
+                    .map(|(name, kind)| serde_json::json!({ "name": name, "kind": [kind] }))
+                    .collect::<Vec<_>>()
+            })
+        };
+        serde_json::from_value(serde_json::json!({
+            "packages": [
+                package(
+                    "multi-contract-caller",
+                    "/examples/multi-contract-caller/Cargo.toml",
+                    vec![("multi_contract_caller", "lib")]
+                ),
+                package(
+                    "adder",
+                    "/examples/multi-contract-caller/adder/Cargo.toml",
+                    vec![("adder", "cdylib"), ("adder-cli", "bin")]
+                ),
+                package(
+                    "no-lib",
+                    "/examples/multi-contract-caller/no-lib/Cargo.toml",
+                    vec![("no-lib", "bin")]
+                ),
+                package("ink", "/registry/ink-4.3.0/Cargo.toml", vec![("ink", "lib")])
+            ],
+            "workspace_root": "/examples/multi-contract-caller",
+            "target_directory": "/examples/multi-contract-caller/target",
+            "version": 1
+        }))
+        .expect("parsing failed")
+    }
+
+    #[test]
+    fn bundle_of_the_workspace_root_is_in_the_ink_directory() {
+        // given
+        let metadata = workspace_metadata();
+        let manifest_path = Path::new("/examples/multi-contract-caller/Cargo.toml");
+
+        // when
+        let path = bundle_path(manifest_path, &metadata, &BuildOptions::new());
+
+        // then
+        assert_eq!(
+            path,
+            Ok(PathBuf::from(
+                "/examples/multi-contract-caller/target/ink/multi_contract_caller.contract"
+            ))
+        );
+    }
+
+    #[test]
+    fn bundle_of_a_workspace_member_is_in_a_sub_directory() {
+        // given
+        let metadata = workspace_metadata();
+        let adder = Path::new("/examples/multi-contract-caller/adder/Cargo.toml");
+        let no_lib = Path::new("/examples/multi-contract-caller/no-lib/Cargo.toml");
+        let options = BuildOptions::new().target_dir("/tmp/variant");
+
+        // when
+        let adder_path = bundle_path(adder, &metadata, &BuildOptions::new());
+        let variant_path = bundle_path(adder, &metadata, &options);
+        let no_lib_path = bundle_path(no_lib, &metadata, &BuildOptions::new());
+
+        // then
+        assert_eq!(
+            adder_path,
+            Ok(PathBuf::from(
+                "/examples/multi-contract-caller/target/ink/adder/adder.contract"
+            ))
+        );
+        assert_eq!(
+            variant_path,
+            Ok(PathBuf::from("/tmp/variant/ink/adder/adder.contract"))
+        );
+        assert_eq!(
+            no_lib_path,
+            Ok(PathBuf::from(
+                "/examples/multi-contract-caller/target/ink/no_lib/no_lib.contract"
+            ))
+        );
+    }
+
+    #[test]
+    fn bundle_of_a_package_outside_the_metadata_is_an_error() {
+        // given
+        let metadata = workspace_metadata();
+        let manifest_path = Path::new("/examples/flipper/Cargo.toml");
+
+        // when
+        let path = bundle_path(manifest_path, &metadata, &BuildOptions::new());
+
+        // then
+        assert!(path.is_err());
+    }
+}
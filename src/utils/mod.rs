@@ -12,16 +12,41 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod build_cache;
 pub mod cargo_contract;
+pub mod node;
 
-use serde_json;
+use crate::uis::Balance;
+use lazy_static::lazy_static;
 use std::{
-    fs::File,
-    io::BufReader,
     path::PathBuf,
-    process::Command,
+    sync::Mutex,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
 };
 
+lazy_static! {
+    /// The ports which were handed out by [`pick_unused_port`].
+    static ref PICKED_PORTS: Mutex<Vec<u16>> = Mutex::new(vec![]);
+
+    /// The directory into which the artifacts of this test run are written.
+    static ref ARTIFACTS_DIR: PathBuf = {
+        let base = std::env::var("WATERFALL_ARTIFACTS")
+            .unwrap_or(String::from("target/waterfall"));
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time must be after the unix epoch")
+            .as_secs();
+        let dir = PathBuf::from(base).join(format!("run-{}-{}", started, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap_or_else(|err| {
+            panic!("unable to create artifacts directory {:?}: {}", dir, err)
+        });
+        dir
+    };
+}
+
 /// Returns the name of the test which is currently executed.
 pub fn test_name() -> String {
     crate::TEST_NAME.with(|test_name| test_name.borrow().clone())
@@ -49,23 +74,6 @@ pub fn example_path(example: &str) -> PathBuf {
     path
 }
 
-/// Extracts the `source.hash` field from the contract bundle.
-pub fn extract_hash_from_contract_bundle(path: &PathBuf) -> String {
-    let file =
-        File::open(path).expect(&format!("Contract file at {:?} does not exist", path));
-    let reader = BufReader::new(file);
-    let json: serde_json::Value = serde_json::from_reader(reader).unwrap_or_else(|err| {
-        panic!("JSON at {:?} is not well-formatted: {:?}", path, err)
-    });
-    json.get("source")
-        .expect("Unable to get 'source' field from contract JSON")
-        .get("hash")
-        .expect("Unable to get 'hash' field from contract JSON")
-        .to_string()
-        .trim_matches('"')
-        .to_string()
-}
-
 /// Asserts that some process is listening at the [`node_port`].
 pub fn assert_node_running() {
     let url = format!("127.0.0.1:{}", node_port());
@@ -77,22 +85,81 @@ pub fn assert_node_running() {
     });
 }
 
-/// Returns the port under which the node is running.
+/// Returns the port under which the node of the currently executing test is
+/// running.
+///
+/// For an external node this is `NODE_PORT`, defaults to `9944`.
 pub fn node_port() -> String {
-    std::env::var("NODE_PORT").unwrap_or(String::from("9944"))
+    match node::current() {
+        Some(node) => node.port().to_string(),
+        None => std::env::var("NODE_PORT").unwrap_or(String::from("9944")),
+    }
+}
+
+/// Returns the directory into which the artifacts of this test run, e.g. the
+/// node logs, are written.
+///
+/// A new directory is created under `WATERFALL_ARTIFACTS` for each run,
+/// defaults to `target/waterfall`.
+pub fn artifacts_dir() -> PathBuf {
+    ARTIFACTS_DIR.clone()
+}
+
+/// Returns the directory into which the artifacts of the currently executing
+/// test are written, i.e. `<example>/<test>` within [`artifacts_dir`].
+pub fn test_artifacts_dir() -> PathBuf {
+    let (example, test) = example_and_test();
+    artifacts_dir().join(example).join(test)
+}
+
+/// Returns the name of the example and of the test which is currently executed,
+/// as recorded in [`test_name`].
+pub fn example_and_test() -> (String, String) {
+    let test_name = test_name();
+    match test_name
+        .strip_prefix("example: ")
+        .and_then(|name| name.split_once(", test: "))
+    {
+        Some((example, test)) => (example.to_string(), test.to_string()),
+        None => (String::from("no-example"), test_name),
+    }
+}
+
+/// Returns a free port which was not yet handed out to another thread.
+pub fn pick_unused_port() -> u16 {
+    for retry in 0..10 {
+        let port_candidate = portpicker::pick_unused_port().expect("no free port");
+        log::info!("picked free port candidate {}", port_candidate);
+
+        // add this port to a global variable and check that no other thread has yet chosen it.
+        let mut picked_ports =
+            PICKED_PORTS.lock().expect("failed locking `PICKED_PORTS`");
+        log::info!("picked ports {:?}", picked_ports);
+        if !picked_ports.contains(&port_candidate) {
+            picked_ports.push(port_candidate);
+            return port_candidate
+        } else {
+            log::info!("port {} was already chosen by another thread, picking another one (try {})", port_candidate, retry);
+        }
+    }
+    panic!("no free port could be determined!");
+}
+
+/// Returns the secret URI of the account which funds the fresh accounts of each
+/// test. Defaults to `//Alice//stash`, which no test signs with.
+pub fn faucet_uri() -> String {
+    std::env::var("WATERFALL_FAUCET").unwrap_or(String::from("//Alice//stash"))
 }
 
-/// Returns true if the `substrate-contracts-node` log under
-/// `/tmp/substrate-contracts-node.log` contains `msg`.
-pub fn node_log_contains(msg: &str) -> bool {
-    let output = Command::new("grep")
-        .arg("-q")
-        .arg(msg)
-        .arg("/tmp/substrate-contracts-node.log")
-        .spawn()
-        .map_err(|err| format!("ERROR while executing `grep` with {:?}: {:?}", msg, err))
-        .expect("failed to execute process")
-        .wait_with_output()
-        .expect("failed to receive output");
-    output.status.success()
+/// Returns the balance with which the fresh accounts of each test are funded.
+pub fn funding() -> Balance {
+    std::env::var("WATERFALL_FUNDING")
+        .map(|funding| {
+            funding
+                .parse()
+                .expect("unable to parse `WATERFALL_FUNDING`")
+        })
+        .unwrap_or_else(|_| {
+            Balance::units(1_000).expect("unable to compute the default funding")
+        })
 }
@@ -0,0 +1,276 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Manages the `substrate-contracts-node` which the tests run against.
+//!
+//! By default one node is spawned for the whole test run, when the first test
+//! starts. It is killed when the test binary exits. With `WATERFALL_NODE=test`
+//! every test gets a node of its own instead, which is killed at the end of the
+//! test. With `WATERFALL_NODE=external` no node is spawned, the tests then run
+//! against the node listening on `NODE_PORT`.
+
+use super::artifacts_dir;
+use lazy_static::lazy_static;
+use std::{
+    cell::RefCell,
+    fs::File,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::{
+        Child,
+        Command,
+        Stdio,
+    },
+    str::FromStr,
+    sync::{
+        Arc,
+        Once,
+        Weak,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+use subxt::{
+    rpc::Rpc,
+    PolkadotConfig,
+};
+use tokio::sync::Mutex;
+
+/// The maximum time to wait for a spawned node to answer RPC calls.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    /// The node which is shared by all tests of this run.
+    ///
+    /// The lock is held while the node is spawned, so that concurrent tests wait
+    /// for the same node.
+    static ref RUN_NODE: Mutex<Option<Arc<Node>>> = Mutex::new(None);
+}
+
+thread_local! {
+    /// The node which the currently executing test runs against.
+    static NODE: RefCell<Weak<Node>> = RefCell::new(Weak::new());
+}
+
+/// Ensures the node of the run is killed when the test binary exits.
+static REGISTER_TEARDOWN: Once = Once::new();
+
+/// How the node which the tests run against is provided.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NodeMode {
+    /// One node is spawned for the whole test run.
+    #[default]
+    Run,
+    /// One node is spawned for each test.
+    Test,
+    /// The node is started outside of the test run, e.g. by CI.
+    External,
+}
+
+impl NodeMode {
+    /// Returns the mode configured via `WATERFALL_NODE`, defaults to
+    /// [`NodeMode::Run`].
+    pub fn from_env() -> Self {
+        std::env::var("WATERFALL_NODE")
+            .map(|mode| {
+                mode.parse()
+                    .unwrap_or_else(|err| panic!("invalid `WATERFALL_NODE`: {}", err))
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl FromStr for NodeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "run" => Ok(Self::Run),
+            "test" => Ok(Self::Test),
+            "external" => Ok(Self::External),
+            _ => {
+                Err(format!(
+                    "unknown node mode {:?}, expected `run`, `test` or `external`",
+                    s
+                ))
+            }
+        }
+    }
+}
+
+/// A `substrate-contracts-node` spawned by the test suite.
+///
+/// The node is killed when this is dropped.
+#[derive(Debug)]
+pub struct Node {
+    process: Child,
+    port: u16,
+    log: PathBuf,
+}
+
+impl Node {
+    /// Spawns a development node with a temporary database and waits until it
+    /// answers RPC calls.
+    ///
+    /// The binary is taken from `CONTRACTS_NODE`, defaults to
+    /// `substrate-contracts-node`. Its output is written to `log`.
+    async fn spawn(log: PathBuf) -> Result<Self, String> {
+        let binary = std::env::var("CONTRACTS_NODE")
+            .unwrap_or(String::from("substrate-contracts-node"));
+        let port = super::pick_unused_port();
+        let p2p_port = super::pick_unused_port();
+        let output = File::create(&log)
+            .map_err(|err| format!("unable to create node log {:?}: {}", log, err))?;
+        let stderr = output
+            .try_clone()
+            .map_err(|err| format!("unable to create node log {:?}: {}", log, err))?;
+        let process = Command::new(&binary)
+            .args(["--dev", "--tmp", "--no-prometheus", "--no-telemetry"])
+            .args(["--rpc-port", &port.to_string()])
+            .args(["--port", &p2p_port.to_string()])
+            .arg("-lruntime::contracts=debug")
+            .stdout(Stdio::from(output))
+            .stderr(Stdio::from(stderr))
+            .spawn()
+            .map_err(|err| format!("unable to spawn `{}`: {}", binary, err))?;
+        let mut node = Self { process, port, log };
+        node.wait_until_ready().await?;
+        log::info!("spawned node listening on port {}", node.port);
+        Ok(node)
+    }
+
+    /// Waits until the node answers a `system_health` RPC call.
+    ///
+    /// The RPC port is opened before the node is able to serve requests, so
+    /// accepting connections is not enough.
+    async fn wait_until_ready(&mut self) -> Result<(), String> {
+        let started = Instant::now();
+        loop {
+            let err = match self.system_health().await {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+            if let Ok(Some(status)) = self.process.try_wait() {
+                return Err(format!(
+                    "node exited with {} during start-up, see {:?}",
+                    status, self.log
+                ))
+            }
+            if started.elapsed() > STARTUP_TIMEOUT {
+                return Err(format!(
+                    "node at port {} did not answer RPC calls within {:?}, last error: {}, see {:?}",
+                    self.port, STARTUP_TIMEOUT, err, self.log
+                ))
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    /// Calls `system_health` via a fresh RPC connection to the node.
+    async fn system_health(&self) -> Result<(), subxt::Error> {
+        let url = format!("ws://127.0.0.1:{}", self.port);
+        let client = subxt::client::default_rpc_client(url).await?;
+        Rpc::<PolkadotConfig>::new(Arc::new(client))
+            .system_health()
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the port of the node's RPC interface.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the path of the file into which the node logs.
+    pub fn log(&self) -> &Path {
+        &self.log
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        log::debug!("killing node listening on port {}", self.port);
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+/// Provides the node for the currently executing test, according to
+/// [`NodeMode::from_env`].
+///
+/// The returned handle keeps a per-test node alive, further calls within the
+/// same test return the same node while it is alive. Returns `None` for an
+/// external node.
+pub async fn start() -> Result<Option<Arc<Node>>, String> {
+    let node = match NodeMode::from_env() {
+        NodeMode::External => return Ok(None),
+        NodeMode::Run => {
+            let mut run_node = RUN_NODE.lock().await;
+            match &*run_node {
+                Some(node) => node.clone(),
+                None => {
+                    let node =
+                        Arc::new(Node::spawn(artifacts_dir().join("node.log")).await?);
+                    REGISTER_TEARDOWN.call_once(|| {
+                        // SAFETY: `teardown` is a plain function without arguments,
+                        // which is what `atexit` expects.
+                        unsafe {
+                            libc::atexit(teardown);
+                        }
+                    });
+                    *run_node = Some(node.clone());
+                    node
+                }
+            }
+        }
+        NodeMode::Test => {
+            match current() {
+                Some(node) => node,
+                None => {
+                    let test_name: String = super::test_name()
+                        .chars()
+                        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                        .collect();
+                    let log = artifacts_dir().join(format!("node-{}.log", test_name));
+                    Arc::new(Node::spawn(log).await?)
+                }
+            }
+        }
+    };
+    log::info!(
+        "[{}] running against node at port {}, logging to {:?}",
+        super::test_name(),
+        node.port(),
+        node.log()
+    );
+    NODE.with(|current| *current.borrow_mut() = Arc::downgrade(&node));
+    Ok(Some(node))
+}
+
+/// Returns the node which the currently executing test runs against, if it
+/// was spawned by the test suite.
+pub fn current() -> Option<Arc<Node>> {
+    NODE.with(|node| node.borrow().upgrade())
+}
+
+/// Kills the node of the run, called when the test binary exits.
+extern "C" fn teardown() {
+    if let Ok(mut node) = RUN_NODE.try_lock() {
+        node.take();
+    }
+}